@@ -0,0 +1,142 @@
+use crate::{
+    metrics::{Metrics, NormalizedMetrics},
+    render::{stamp, Canvas},
+};
+
+pub const MIN_SAMPLES: u64 = 5;
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Welford {
+    count: u64,
+    mean: f64,
+    m2: f64,
+}
+
+impl Welford {
+    pub fn push(&mut self, value: f64) {
+        self.count += 1;
+        let delta = value - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (value - self.mean);
+    }
+
+    pub fn std_dev(&self) -> f64 {
+        if self.count < 2 {
+            0.0
+        } else {
+            (self.m2 / (self.count - 1) as f64).sqrt()
+        }
+    }
+
+    pub fn z_score(&self, value: f64) -> f64 {
+        let std_dev = self.std_dev();
+        if self.count < MIN_SAMPLES || std_dev <= 0.0 {
+            0.0
+        } else {
+            (value - self.mean).abs() / std_dev
+        }
+    }
+
+    pub fn is_anomaly(&self, value: f64, sigma: f64) -> bool {
+        let std_dev = self.std_dev();
+        self.count >= MIN_SAMPLES && std_dev > 0.0 && (value - self.mean).abs() > sigma * std_dev
+    }
+}
+
+#[derive(Default)]
+pub struct AnomalyDetector {
+    series: [Welford; 4],
+}
+
+fn samples(metrics: &Metrics) -> [(&'static str, f64); 4] {
+    let inputs = NormalizedMetrics::from_metrics(metrics);
+    [
+        ("CPU", inputs.cpu as f64),
+        ("MEM", inputs.memory as f64),
+        ("NET", inputs.network as f64),
+        ("LOAD", metrics.load_avg),
+    ]
+}
+
+pub fn hold_ticks(z: f64, sigma: f64, base: u32) -> u32 {
+    if sigma <= 0.0 || z <= sigma {
+        return 0;
+    }
+    (base as f64 * (z / sigma).min(4.0)).round() as u32
+}
+
+impl AnomalyDetector {
+    pub fn peak(&self, metrics: &Metrics) -> f64 {
+        self.series.iter().zip(samples(metrics)).map(|(series, (_, value))| series.z_score(value)).fold(0.0, f64::max)
+    }
+
+    pub fn check(&mut self, metrics: &Metrics, sigma: f64) -> Vec<&'static str> {
+        let mut flagged = Vec::new();
+        for (series, (label, value)) in self.series.iter_mut().zip(samples(metrics)) {
+            if series.is_anomaly(value, sigma) {
+                flagged.push(label);
+            }
+            series.push(value);
+        }
+        flagged
+    }
+}
+
+pub fn mark_anomalies(canvas: &mut Canvas, flagged: &[&'static str]) {
+    if flagged.is_empty() {
+        return;
+    }
+    let text = format!(" ANOMALY {} ", flagged.join(" "));
+    if let Some(line) = canvas.rows.get_mut(canvas.overlay_row) {
+        let start = line.chars().count().saturating_sub(text.chars().count());
+        stamp(line, start, &text);
+    }
+    canvas.alerts.push("ANOMALY");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metrics(cpu_usage: f32) -> Metrics {
+        Metrics {
+            cpu_usage,
+            cpu_cores: vec![cpu_usage],
+            load_avg: 0.5,
+            total_memory: 100,
+            used_memory: 40,
+            disk_usage: Vec::new(),
+            network_rx: 0,
+            network_tx: 0,
+            interfaces: Vec::new(),
+            entropy: 0,
+            temperature: None,
+        }
+    }
+
+    #[test]
+    fn an_injected_spike_is_flagged_once_the_baseline_settles() {
+        let mut early = AnomalyDetector::default();
+        early.check(&metrics(20.0), 3.0);
+        early.check(&metrics(24.0), 3.0);
+        assert!(early.check(&metrics(95.0), 3.0).is_empty());
+        let mut detector = AnomalyDetector::default();
+        for tick in 0..20 {
+            let flagged = detector.check(&metrics(if tick % 2 == 0 { 20.0 } else { 24.0 }), 3.0);
+            assert!(flagged.is_empty(), "tick {tick}: {flagged:?}");
+        }
+        assert!(detector.peak(&metrics(95.0)) > 3.0);
+        assert_eq!(detector.check(&metrics(95.0), 3.0), ["CPU"]);
+        assert!(detector.check(&metrics(22.0), 3.0).is_empty());
+    }
+
+    #[test]
+    fn hold_grows_with_spike_size_and_caps_at_four_times() {
+        assert_eq!(hold_ticks(2.0, 3.0, 5), 0);
+        assert_eq!(hold_ticks(3.0, 3.0, 5), 0);
+        assert_eq!(hold_ticks(6.0, 3.0, 5), 10);
+        assert_eq!(hold_ticks(9.0, 3.0, 5), 15);
+        assert_eq!(hold_ticks(60.0, 3.0, 5), 20);
+        assert_eq!(hold_ticks(6.0, 0.0, 5), 0);
+    }
+}
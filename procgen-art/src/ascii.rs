@@ -0,0 +1,39 @@
+use crate::{
+    metrics::NormalizedMetrics,
+    render::{art_char, cell_noise, Canvas},
+    substrate::Substrate,
+};
+
+pub fn ascii_intensity(luma: f32, noise: f32, strength: f32) -> f32 {
+    (luma * (1.0 - strength) + noise * strength).clamp(0.0, 1.0)
+}
+
+pub fn ascii_canvas(image: &Substrate, ramp: &str, strength: f32, key: u64, width: u16, height: u16) -> Canvas {
+    let intensity: Vec<Vec<f32>> = (0..height)
+        .map(|y| {
+            (0..width)
+                .map(|x| ascii_intensity(image.luma_at(x, y, width, height), cell_noise(key, x, y, 0), strength))
+                .collect()
+        })
+        .collect();
+    Canvas {
+        rows: intensity.iter().map(|row| row.iter().map(|&value| art_char(ramp, value)).collect()).collect(),
+        intensity,
+        overlay_row: usize::MAX,
+        alerts: Vec::new(),
+        inputs: NormalizedMetrics::default(),
+        sanitized: 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_dark_to_light_gradient_walks_up_the_ramp() {
+        let image = Substrate { width: 4, height: 1, luma: vec![0.0, 1.0 / 3.0, 2.0 / 3.0, 1.0] };
+        let canvas = ascii_canvas(&image, " .:#", 0.0, 7, 8, 2);
+        assert_eq!(canvas.rows, vec!["  ..::##".to_string(); 2]);
+    }
+}
@@ -0,0 +1,45 @@
+use std::{
+    ffi::OsString,
+    fs::{self, File},
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+pub fn temp_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().map_or_else(OsString::new, OsString::from);
+    name.push(".tmp");
+    path.with_file_name(name)
+}
+
+pub fn write_atomic(path: &Path, contents: &[u8]) -> io::Result<()> {
+    let temp = temp_path(path);
+    let mut file = File::create(&temp)?;
+    file.write_all(contents)?;
+    file.sync_all()?;
+    fs::rename(&temp, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{env, process, thread};
+
+    #[test]
+    fn readers_never_see_a_partial_write() {
+        let path = env::temp_dir().join(format!("procgen-atomic-{}", process::id()));
+        assert_eq!(temp_path(&path).file_name().unwrap(), format!("procgen-atomic-{}.tmp", process::id()).as_str());
+        let versions = [vec![b'a'; 1 << 16], vec![b'b'; 1 << 16]];
+        write_atomic(&path, &versions[0]).unwrap();
+        let writer = {
+            let (path, versions) = (path.clone(), versions.clone());
+            thread::spawn(move || (0..50).for_each(|round| write_atomic(&path, &versions[round % 2]).unwrap()))
+        };
+        while !writer.is_finished() {
+            let seen = fs::read(&path).unwrap();
+            assert!(versions.contains(&seen));
+        }
+        writer.join().unwrap();
+        assert!(!temp_path(&path).exists());
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,69 @@
+use crate::metrics::{Metrics, MetricsSource};
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    thread,
+    time::Duration,
+};
+
+pub struct BackgroundSource {
+    latest: Arc<Mutex<Metrics>>,
+    stop: Arc<AtomicBool>,
+}
+
+impl BackgroundSource {
+    pub fn spawn(mut source: Box<dyn MetricsSource + Send>, initial: Metrics, interval: Duration) -> Self {
+        let latest = Arc::new(Mutex::new(initial));
+        let stop = Arc::new(AtomicBool::new(false));
+        let (publish, stopped) = (Arc::clone(&latest), Arc::clone(&stop));
+        thread::spawn(move || {
+            while !stopped.load(Ordering::Relaxed) {
+                thread::sleep(interval);
+                let metrics = source.sample();
+                if let Ok(mut slot) = publish.lock() {
+                    *slot = metrics;
+                }
+            }
+        });
+        BackgroundSource { latest, stop }
+    }
+}
+
+impl MetricsSource for BackgroundSource {
+    fn sample(&mut self) -> Metrics {
+        match self.latest.lock() {
+            Ok(latest) => latest.clone(),
+            Err(poisoned) => poisoned.into_inner().clone(),
+        }
+    }
+}
+
+impl Drop for BackgroundSource {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::MockSource;
+    use std::time::Instant;
+
+    #[test]
+    fn render_reads_the_last_published_metrics() {
+        let initial = MockSource::new(1).sample();
+        let mut stalled = BackgroundSource::spawn(Box::new(MockSource::new(2)), initial.clone(), Duration::from_secs(3600));
+        let json = |metrics: &Metrics| serde_json::to_string(metrics).unwrap();
+        assert_eq!(json(&stalled.sample()), json(&initial));
+        assert_eq!(json(&stalled.sample()), json(&initial));
+        let mut live = BackgroundSource::spawn(Box::new(MockSource::new(2)), initial.clone(), Duration::from_millis(1));
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while json(&live.sample()) == json(&initial) {
+            assert!(Instant::now() < deadline, "no metrics were published");
+            thread::sleep(Duration::from_millis(1));
+        }
+    }
+}
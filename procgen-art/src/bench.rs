@@ -0,0 +1,93 @@
+use serde::Serialize;
+use std::time::Duration;
+
+pub const BENCH_SEED: u64 = 0;
+
+pub struct BenchResult {
+    pub style: String,
+    pub frames: u32,
+    pub elapsed: Duration,
+    pub frame_us: Vec<u64>,
+}
+
+#[derive(Serialize)]
+pub struct BenchReport {
+    pub style: String,
+    pub frames: u32,
+    pub total_us: u64,
+    pub mean_us: f64,
+    pub median_us: f64,
+    pub max_fps: f64,
+}
+
+#[derive(Serialize)]
+pub struct BenchSummary {
+    pub width: u16,
+    pub height: u16,
+    pub seed: u64,
+    pub results: Vec<BenchReport>,
+}
+
+impl BenchResult {
+    pub fn fps(&self) -> f64 {
+        self.frames as f64 / self.elapsed.as_secs_f64().max(f64::EPSILON)
+    }
+
+    pub fn ms_per_frame(&self) -> f64 {
+        self.elapsed.as_secs_f64() * 1000.0 / self.frames.max(1) as f64
+    }
+
+    pub fn median_us(&self) -> f64 {
+        let mut sorted = self.frame_us.clone();
+        sorted.sort_unstable();
+        match sorted.len() {
+            0 => 0.0,
+            len if len % 2 == 0 => (sorted[len / 2 - 1] + sorted[len / 2]) as f64 / 2.0,
+            len => sorted[len / 2] as f64,
+        }
+    }
+
+    pub fn report(&self) -> BenchReport {
+        BenchReport {
+            style: self.style.clone(),
+            frames: self.frames,
+            total_us: self.elapsed.as_micros() as u64,
+            mean_us: self.ms_per_frame() * 1000.0,
+            median_us: self.median_us(),
+            max_fps: self.fps(),
+        }
+    }
+}
+
+pub fn bench_table(results: &[BenchResult]) -> String {
+    let name_width = results.iter().map(|result| result.style.len()).max().unwrap_or(0).max("style".len());
+    let mut table = format!("{:<name_width$}  {:>8}  {:>10}  {:>10}  {:>10}\n", "style", "frames", "ms/frame", "median ms", "fps");
+    for result in results {
+        table.push_str(&format!(
+            "{:<name_width$}  {:>8}  {:>10.3}  {:>10.3}  {:>10.1}\n",
+            result.style,
+            result.frames,
+            result.ms_per_frame(),
+            result.median_us() / 1000.0,
+            result.fps()
+        ));
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bench_json_reports_total_mean_median_and_fps() {
+        let result = BenchResult { style: "plasma".to_string(), frames: 4, elapsed: Duration::from_millis(2), frame_us: vec![400, 700, 300, 600] };
+        let summary = BenchSummary { width: 80, height: 24, seed: BENCH_SEED, results: vec![result.report()] };
+        let json = serde_json::to_value(&summary).unwrap();
+        assert_eq!((json["width"].as_u64(), json["height"].as_u64(), json["seed"].as_u64()), (Some(80), Some(24), Some(BENCH_SEED)));
+        let report = &json["results"][0];
+        assert_eq!((report["style"].as_str(), report["frames"].as_u64(), report["total_us"].as_u64()), (Some("plasma"), Some(4), Some(2000)));
+        assert_eq!((report["mean_us"].as_f64(), report["median_us"].as_f64(), report["max_fps"].as_f64()), (Some(500.0), Some(500.0), Some(2000.0)));
+        assert_eq!(BenchResult { frame_us: vec![9, 1, 5], ..result }.median_us(), 5.0);
+    }
+}
@@ -0,0 +1,138 @@
+use crate::{
+    metrics::NormalizedMetrics,
+    render::{stamp, Canvas},
+};
+use clap::ValueEnum;
+use serde::Serialize;
+use std::collections::VecDeque;
+
+const LEFT_DOTS: [u8; 4] = [0x01, 0x02, 0x04, 0x40];
+const RIGHT_DOTS: [u8; 4] = [0x08, 0x10, 0x20, 0x80];
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PlotMetric {
+    Cpu,
+    Mem,
+    Net,
+}
+
+impl PlotMetric {
+    pub fn sample(&self, inputs: &NormalizedMetrics) -> f32 {
+        match self {
+            PlotMetric::Cpu => inputs.cpu,
+            PlotMetric::Mem => inputs.memory,
+            PlotMetric::Net => inputs.network,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            PlotMetric::Cpu => "CPU",
+            PlotMetric::Mem => "MEM",
+            PlotMetric::Net => "NET",
+        }
+    }
+}
+
+pub struct History<T = f32> {
+    samples: VecDeque<T>,
+    capacity: usize,
+}
+
+impl<T: Clone> History<T> {
+    pub fn new(capacity: usize) -> Self {
+        History {
+            samples: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    pub fn push(&mut self, sample: T) {
+        if self.samples.len() == self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(sample);
+    }
+
+    pub fn samples(&self) -> Vec<T> {
+        self.samples.iter().cloned().collect()
+    }
+}
+
+pub fn cell_pattern(left: Option<usize>, right: Option<usize>) -> char {
+    let mut bits = 0u8;
+    if let Some(row) = left.filter(|row| *row < 4) {
+        bits |= LEFT_DOTS[row];
+    }
+    if let Some(row) = right.filter(|row| *row < 4) {
+        bits |= RIGHT_DOTS[row];
+    }
+    char::from_u32(0x2800 + bits as u32).unwrap_or(' ')
+}
+
+fn dot_row(value: f32, dots_high: usize) -> usize {
+    let level = (value.clamp(0.0, 1.0) * (dots_high - 1) as f32).round() as usize;
+    dots_high - 1 - level
+}
+
+pub fn render_plot(samples: &[f32], width: u16, height: u16) -> Vec<String> {
+    let (width, height) = (width as usize, height as usize);
+    let dots_high = (height * 4).max(1);
+    let visible = &samples[samples.len().saturating_sub(width * 2)..];
+    let offset = width * 2 - visible.len();
+    let rows: Vec<Option<usize>> = (0..width * 2)
+        .map(|sub| sub.checked_sub(offset).map(|idx| dot_row(visible[idx], dots_high)))
+        .collect();
+
+    (0..height)
+        .map(|cy| {
+            let local = |row: Option<usize>| row.and_then(|row| row.checked_sub(cy * 4));
+            (0..width)
+                .map(|cx| cell_pattern(local(rows[cx * 2]), local(rows[cx * 2 + 1])))
+                .collect()
+        })
+        .collect()
+}
+
+pub fn plot_canvas(history: &History, metric: PlotMetric, inputs: NormalizedMetrics, width: u16, height: u16) -> Canvas {
+    let samples = history.samples();
+    let mut rows = render_plot(&samples, width, height);
+    if let (Some(first), Some(latest)) = (rows.first_mut(), samples.last()) {
+        stamp(first, 0, &format!("{} {:.0}%", metric.label(), latest * 100.0));
+    }
+    Canvas {
+        rows,
+        intensity: Vec::new(),
+        overlay_row: usize::MAX,
+        alerts: Vec::new(),
+        inputs,
+        sanitized: 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::{MetricsSource, MetricsSummary, MockSource};
+
+    #[test]
+    fn samples_map_to_braille_dots() {
+        assert_eq!(render_plot(&[0.0, 1.0], 1, 1), vec!["\u{2848}"]);
+        assert_eq!(render_plot(&[1.0 / 3.0, 2.0 / 3.0], 1, 1), vec!["\u{2814}"]);
+        assert_eq!(render_plot(&[1.0], 1, 2), vec!["\u{2808}", "\u{2800}"]);
+        assert_eq!(cell_pattern(None, None), '\u{2800}');
+    }
+
+    #[test]
+    fn history_keeps_only_the_most_recent_summaries() {
+        let mut source = MockSource::new(5);
+        let summaries: Vec<MetricsSummary> = (0..6).map(|_| MetricsSummary::from_metrics(&source.sample())).collect();
+        let mut history = History::new(4);
+        for summary in &summaries {
+            history.push(*summary);
+        }
+        assert_ne!(summaries[0], summaries[2]);
+    assert_eq!(history.samples(), summaries[2..]);
+    }
+}
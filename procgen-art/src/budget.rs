@@ -0,0 +1,267 @@
+use procgen_art::log::warn;
+
+pub const LINE_LIMIT: usize = 500;
+
+pub const SOURCES: &[(&str, &str)] = &[
+    ("anomaly.rs", include_str!("anomaly.rs")),
+    ("ascii.rs", include_str!("ascii.rs")),
+    ("atomic.rs", include_str!("atomic.rs")),
+    ("background.rs", include_str!("background.rs")),
+    ("bench.rs", include_str!("bench.rs")),
+    ("braille.rs", include_str!("braille.rs")),
+    ("budget.rs", include_str!("budget.rs")),
+    ("burnin.rs", include_str!("burnin.rs")),
+    ("calibrate.rs", include_str!("calibrate.rs")),
+    ("cli.rs", include_str!("cli.rs")),
+    ("clock.rs", include_str!("clock.rs")),
+    ("color.rs", include_str!("color.rs")),
+    ("commands.rs", include_str!("commands.rs")),
+    ("csv.rs", include_str!("csv.rs")),
+    ("demoscene.rs", include_str!("demoscene.rs")),
+    ("devices.rs", include_str!("devices.rs")),
+    ("disks.rs", include_str!("disks.rs")),
+    ("emit.rs", include_str!("emit.rs")),
+    ("eq.rs", include_str!("eq.rs")),
+    ("fifo.rs", include_str!("fifo.rs")),
+    ("filters.rs", include_str!("filters.rs")),
+    ("fps.rs", include_str!("fps.rs")),
+    ("frames.rs", include_str!("frames.rs")),
+    ("gauge.rs", include_str!("gauge.rs")),
+    ("graphics.rs", include_str!("graphics.rs")),
+    ("html.rs", include_str!("html.rs")),
+    ("ink.rs", include_str!("ink.rs")),
+    ("keymap.rs", include_str!("keymap.rs")),
+    ("legend.rs", include_str!("legend.rs")),
+    ("letterbox.rs", include_str!("letterbox.rs")),
+    ("lib.rs", include_str!("lib.rs")),
+    ("live.rs", include_str!("live.rs")),
+    ("log.rs", include_str!("log.rs")),
+    ("main.rs", include_str!("main.rs")),
+    ("mask.rs", include_str!("mask.rs")),
+    ("memmap.rs", include_str!("memmap.rs")),
+    ("metrics.rs", include_str!("metrics.rs")),
+    ("options.rs", include_str!("options.rs")),
+    ("panels.rs", include_str!("panels.rs")),
+    ("phase.rs", include_str!("phase.rs")),
+    ("process.rs", include_str!("process.rs")),
+    ("prometheus.rs", include_str!("prometheus.rs")),
+    ("pulse.rs", include_str!("pulse.rs")),
+    ("rain.rs", include_str!("rain.rs")),
+    ("record.rs", include_str!("record.rs")),
+    ("render.rs", include_str!("render.rs")),
+    ("scene.rs", include_str!("scene.rs")),
+    ("seedfile.rs", include_str!("seedfile.rs")),
+    ("shapes.rs", include_str!("shapes.rs")),
+    ("sinks.rs", include_str!("sinks.rs")),
+    ("sixel.rs", include_str!("sixel.rs")),
+    ("snapshot.rs", include_str!("snapshot.rs")),
+    ("solid.rs", include_str!("solid.rs")),
+    ("spectrum.rs", include_str!("spectrum.rs")),
+    ("stats.rs", include_str!("stats.rs")),
+    ("styles.rs", include_str!("styles.rs")),
+    ("substrate.rs", include_str!("substrate.rs")),
+    ("summary.rs", include_str!("summary.rs")),
+    ("template.rs", include_str!("template.rs")),
+    ("termenv.rs", include_str!("termenv.rs")),
+    ("theme.rs", include_str!("theme.rs")),
+    ("trails.rs", include_str!("trails.rs")),
+    ("transition.rs", include_str!("transition.rs")),
+    ("validate.rs", include_str!("validate.rs")),
+];
+
+struct TestItem {
+    level: usize,
+    opened: bool,
+}
+
+#[derive(Default)]
+struct Scan {
+    block_depth: usize,
+    in_string: bool,
+    braces: usize,
+    test_item: Option<TestItem>,
+}
+
+impl Scan {
+    fn has_code(&mut self, line: &str) -> bool {
+        let chars: Vec<char> = line.chars().collect();
+        let mut code = false;
+        let mut idx = 0;
+        while idx < chars.len() {
+            let (ch, next) = (chars[idx], chars.get(idx + 1).copied());
+            if self.block_depth > 0 {
+                match (ch, next) {
+                    ('*', Some('/')) => {
+                        self.block_depth -= 1;
+                        idx += 1;
+                    }
+                    ('/', Some('*')) => {
+                        self.block_depth += 1;
+                        idx += 1;
+                    }
+                    _ => {}
+                }
+            } else if self.in_string {
+                code = true;
+                match ch {
+                    '\\' => idx += 1,
+                    '"' => self.in_string = false,
+                    _ => {}
+                }
+            } else {
+                match (ch, next) {
+                    ('/', Some('/')) => break,
+                    ('/', Some('*')) => {
+                        self.block_depth = 1;
+                        idx += 1;
+                    }
+                    ('"', _) => {
+                        self.in_string = true;
+                        code = true;
+                    }
+                    ('\'', Some('\\')) => {
+                        code = true;
+                        idx += chars.get(idx + 3..).and_then(|rest| rest.iter().position(|&c| c == '\'')).map_or(chars.len(), |end| end + 3);
+                    }
+                    ('\'', _) if chars.get(idx + 2) == Some(&'\'') => {
+                        code = true;
+                        idx += 2;
+                    }
+                    ('{', _) => {
+                        self.braces += 1;
+                        code = true;
+                    }
+                    ('}', _) => {
+                        self.braces = self.braces.saturating_sub(1);
+                        code = true;
+                    }
+                    _ if !ch.is_whitespace() => code = true,
+                    _ => {}
+                }
+            }
+            idx += 1;
+        }
+        code
+    }
+
+    fn counts(&mut self, line: &str) -> bool {
+        if self.test_item.is_none() && self.block_depth == 0 && !self.in_string && line.trim() == "#[cfg(test)]" {
+            self.test_item = Some(TestItem { level: self.braces, opened: false });
+            return false;
+        }
+        let code = self.has_code(line);
+        let Some(item) = self.test_item.as_mut() else {
+            return code;
+        };
+        item.opened |= self.braces > item.level;
+        if self.braces <= item.level && (item.opened || line.trim_end().ends_with(';')) {
+            self.test_item = None;
+        }
+        false
+    }
+}
+
+pub fn count_executable_lines<'a>(files: &[(&'a str, &str)]) -> Vec<(&'a str, usize)> {
+    files
+        .iter()
+        .map(|(name, source)| {
+            let mut scan = Scan::default();
+            (*name, source.lines().filter(|line| scan.counts(line)).count())
+        })
+        .collect()
+}
+
+pub fn ensure_line_budget(limit: usize) {
+    for (name, count) in count_executable_lines(SOURCES) {
+        if count > limit {
+            warn(&format!("{name} has {count} executable lines, over the limit of {limit}"));
+            std::process::exit(3);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{fs, path::Path};
+
+    fn count(source: &str) -> usize {
+        count_executable_lines(&[("fixture.rs", source)])[0].1
+    }
+
+    #[test]
+    fn skips_blank_and_line_comments() {
+        assert_eq!(count("fn main() {\n\n    // note\n    //\n}\n"), 2);
+    }
+
+    #[test]
+    fn skips_doc_comments() {
+        assert_eq!(count("//! crate docs\n/// item docs\n///\nfn run() {}\n"), 1);
+    }
+
+    #[test]
+    fn skips_single_line_block_comments() {
+        assert_eq!(count("/* header */\nlet a = 1;\n    /** doc block */\n"), 1);
+    }
+
+    #[test]
+    fn skips_multi_line_block_comments() {
+        assert_eq!(count("/*\n * line one\n * line two\n */\nlet a = 1;\n"), 1);
+    }
+
+    #[test]
+    fn tracks_nested_block_comments() {
+        assert_eq!(count("/* outer\n/* inner */\nstill comment\n*/\nlet a = 1;\n"), 1);
+    }
+
+    #[test]
+    fn counts_code_beside_block_comments_once() {
+        assert_eq!(count("let a = 1; /* trailing\n   comment */ let b = 2;\n/* lead */ let c = 3;\n"), 3);
+    }
+
+    #[test]
+    fn ignores_comment_markers_inside_strings() {
+        assert_eq!(count("let a = \"/* not a comment\";\nlet b = 2;\nlet c = \"// nor this\";\n"), 3);
+    }
+
+    #[test]
+    fn counts_every_line_of_a_multi_line_string() {
+        assert_eq!(count("let s = \"first\n// second\n\";\n"), 3);
+    }
+
+    #[test]
+    fn handles_quote_char_literals_and_lifetimes() {
+        assert_eq!(count("let q = '\"'; /* c */\nlet e = '\\''; // c\nfn f<'a>(x: &'a str) {}\n/* done */\n"), 3);
+    }
+
+    #[test]
+    fn skips_test_only_items() {
+        assert_eq!(count("fn run() {}\n#[cfg(test)]\nuse std::fmt;\n#[cfg(test)]\nmod tests {\n    #[test]\n    fn t() { let b = '{'; }\n}\nfn after() {}\n"), 2);
+    }
+
+    #[test]
+    fn counts_each_file_separately() {
+        let files = [("a.rs", "/* never closed\nfn hidden() {}\n"), ("b.rs", "fn one() {}\nfn two() {}\n"), ("c.rs", "")];
+        assert_eq!(count_executable_lines(&files), vec![("a.rs", 0), ("b.rs", 2), ("c.rs", 0)]);
+    }
+
+    #[test]
+    fn every_source_fits_the_default_limit() {
+        let counts = count_executable_lines(SOURCES);
+        assert_eq!(counts.len(), SOURCES.len());
+        assert!(counts.iter().all(|(_, count)| *count <= LINE_LIMIT), "{counts:?}");
+    }
+
+    #[test]
+    fn sources_list_every_rust_file_in_src() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("src");
+        let mut on_disk: Vec<String> = fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .filter(|name| name.ends_with(".rs"))
+            .collect();
+        on_disk.sort();
+        let listed: Vec<&str> = SOURCES.iter().map(|(name, _)| *name).collect();
+        assert_eq!(listed, on_disk);
+    }
+}
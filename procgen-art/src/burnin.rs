@@ -0,0 +1,43 @@
+use std::f32::consts::TAU;
+
+pub const BURNIN_DIM: f32 = 0.08;
+
+pub fn burnin_offset(elapsed: u64, shift: u16, period: u64) -> (u16, u16) {
+    if shift == 0 {
+        return (0, 0);
+    }
+    let side = shift as u64;
+    let step = (elapsed / period.max(1)) % (4 * side);
+    let along = (step % side) as u16;
+    match step / side {
+        0 => (along, 0),
+        1 => (shift, along),
+        2 => (shift - along, shift),
+        _ => (0, shift - along),
+    }
+}
+
+pub fn burnin_brightness(elapsed: u64, shift: u16, period: u64) -> f32 {
+    let cycle = (period.max(1) * 4 * shift.max(1) as u64) as f32;
+    let phase = (elapsed as f32 / cycle).fract();
+    1.0 - BURNIN_DIM * (1.0 - (phase * TAU).cos()) / 2.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn offset_walks_a_small_square_and_repeats() {
+        let path: Vec<(u16, u16)> = (0..5).map(|step| burnin_offset(step * 10, 1, 10)).collect();
+        assert_eq!(path, vec![(0, 0), (1, 0), (1, 1), (0, 1), (0, 0)]);
+        assert_eq!(burnin_offset(15, 1, 10), (1, 0));
+        let wide: Vec<(u16, u16)> = (0..8).map(|step| burnin_offset(step, 2, 1)).collect();
+        assert_eq!(wide, vec![(0, 0), (1, 0), (2, 0), (2, 1), (2, 2), (1, 2), (0, 2), (0, 1)]);
+        assert!((0..1000).all(|elapsed| {
+            let (x, y) = burnin_offset(elapsed, 2, 3);
+            x <= 2 && y <= 2
+        }));
+        assert_eq!(burnin_offset(500, 0, 10), (0, 0));
+    }
+}
@@ -0,0 +1,85 @@
+use crate::{filters::grid_mark, render::art_char};
+use crossterm::style::Color;
+
+pub const COLOR_BARS: [Color; 8] = [
+    Color::White,
+    Color::Yellow,
+    Color::Cyan,
+    Color::Green,
+    Color::Magenta,
+    Color::Red,
+    Color::Blue,
+    Color::Black,
+];
+pub const ASPECT_BOX_ROWS: usize = 4;
+
+pub struct Swatch {
+    pub text: String,
+    pub background: Option<Color>,
+}
+
+fn plain(text: String) -> Vec<Swatch> {
+    vec![Swatch { text, background: None }]
+}
+
+pub fn color_bars(width: usize) -> Vec<Swatch> {
+    (0..COLOR_BARS.len())
+        .map(|idx| {
+            let (start, end) = (idx * width / COLOR_BARS.len(), (idx + 1) * width / COLOR_BARS.len());
+            Swatch { text: " ".repeat(end - start), background: Some(COLOR_BARS[idx]) }
+        })
+        .collect()
+}
+
+pub fn gray_ramp(width: usize) -> Vec<Swatch> {
+    let span = width.saturating_sub(1).max(1) as f32;
+    (0..width)
+        .map(|x| {
+            let level = (x as f32 / span * 255.0).round() as u8;
+            Swatch { text: " ".to_string(), background: Some(Color::Rgb { r: level, g: level, b: level }) }
+        })
+        .collect()
+}
+
+pub fn ruler(width: usize) -> [String; 2] {
+    let tens = (0..width).map(|x| if x % 10 == 0 { char::from_digit((x / 10 % 10) as u32, 10).unwrap_or(' ') } else { ' ' }).collect();
+    let units = (0..width).map(|x| char::from_digit((x % 10) as u32, 10).unwrap_or(' ')).collect();
+    [tens, units]
+}
+
+pub fn aspect_box(rows: usize) -> Vec<String> {
+    let cols = rows * 2;
+    (0..rows)
+        .map(|y| match y {
+            0 => format!("+{}+", "-".repeat(cols.saturating_sub(2))),
+            _ if y + 1 == rows => format!("+{}+ should look square", "-".repeat(cols.saturating_sub(2))),
+            _ => format!("|{}|", " ".repeat(cols.saturating_sub(2))),
+        })
+        .collect()
+}
+
+pub fn calibration_lines(width: usize, ramp: &str) -> Vec<Vec<Swatch>> {
+    let span = width.saturating_sub(1).max(1) as f32;
+    let mut lines = vec![plain(format!("calibration {width} cols")), color_bars(width), color_bars(width), gray_ramp(width)];
+    lines.push(plain((0..width).map(|x| art_char(ramp, x as f32 / span)).collect()));
+    lines.extend(ruler(width).map(plain));
+    lines.extend((0..ASPECT_BOX_ROWS * 3).map(|y| plain((0..width).map(|x| grid_mark(x, y).unwrap_or(' ')).collect())));
+    lines.extend(aspect_box(ASPECT_BOX_ROWS).into_iter().map(plain));
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn calibration_frame_has_one_segment_per_color_bar() {
+        let lines = calibration_lines(60, " .:#");
+        let bars: Vec<&Vec<Swatch>> = lines.iter().filter(|line| line.len() == COLOR_BARS.len() && line.iter().all(|swatch| swatch.background.is_some())).collect();
+        assert_eq!(bars.len(), 2);
+        for bar in bars {
+            assert_eq!(bar.iter().map(|swatch| swatch.background).collect::<Vec<_>>(), COLOR_BARS.map(Some));
+            assert_eq!(bar.iter().map(|swatch| swatch.text.len()).sum::<usize>(), 60);
+        }
+    }
+}
@@ -0,0 +1,354 @@
+use clap::{builder::PossibleValuesParser, Parser, Subcommand};
+use procgen_art::{
+    braille::PlotMetric,
+    clock::parse_clock_format,
+    color::{parse_contrast, ColorChoice},
+    devices::parse_device_weight,
+    emit::{parse_emit, EmitTarget},
+    filters::FilterKind,
+    gauge::GaugeMetric,
+    graphics::ImageProtocol,
+    letterbox::parse_ratio,
+    metrics::Subsystem,
+    record::RecordFormat,
+    panels::{parse_panels, PanelGrid},
+    render::{parse_fraction, parse_ramp, parse_zoom, OverlayLine, OverlayPos},
+    snapshot::JsonCase,
+    stats::SeedScore,
+    styles::style_names,
+    trails::parse_decay,
+    transition::{parse_transition, Transition},
+};
+use serde::Serialize;
+use std::path::PathBuf;
+
+#[derive(Parser, Serialize)]
+#[command(name = "procgen-art", about = "Terminal generative art driven by system metrics", version)]
+pub struct Args {
+    #[arg(long, env = "PROCGEN_ART_INTERVAL", default_value_t = 500, help = "Frame interval in milliseconds")]
+    pub interval: u64,
+    #[arg(long, env = "PROCGEN_ART_STYLE", default_value = "plasma", value_parser = PossibleValuesParser::new(style_names()), help = "Art style")]
+    pub style: String,
+    #[arg(long, env = "PROCGEN_ART_RANDOM_STYLE", conflicts_with = "style", help = "Pick the style at random, reproducibly when --seed or --seed-phrase is given")]
+    pub random_style: bool,
+    #[arg(long, env = "PROCGEN_ART_MORPH_TO", value_name = "SEED", help = "Blend the live noise field from the current seed's pattern into SEED's over --morph-frames frames, then carry on from SEED")]
+    pub morph_to: Option<u64>,
+    #[arg(long, env = "PROCGEN_ART_MORPH_FRAMES", value_name = "N", default_value_t = 30, value_parser = clap::value_parser!(u64).range(1..), requires = "morph_to", help = "Number of frames a --morph-to blend takes")]
+    pub morph_frames: u64,
+    #[arg(long, env = "PROCGEN_ART_NO_FLOW", help = "Reshuffle the noise every frame instead of drifting a smooth field from the frame counter")]
+    pub no_flow: bool,
+    #[arg(long, env = "PROCGEN_ART_DEMOSCENE", conflicts_with_all = ["metrics_csv", "braille_plot"], help = "Play a metric-independent looping intro: mirrored, looped noise with a cycling, hue-rotating palette")]
+    pub demoscene: bool,
+    #[arg(long, env = "PROCGEN_ART_ROTATE_STYLES", value_name = "SECS", value_parser = clap::value_parser!(u64).range(1..), help = "Switch to the next style every SECS seconds in the live view")]
+    pub rotate_styles: Option<u64>,
+    #[arg(long, env = "PROCGEN_ART_IDLE_TIMEOUT", value_name = "SECS", value_parser = clap::value_parser!(u64).range(1..), conflicts_with = "screensaver", help = "After SECS without a keypress, slow the live view down and rotate its hues until the next key")]
+    pub idle_timeout: Option<u64>,
+    #[arg(long, env = "PROCGEN_ART_ONCE", alias = "once-json", help = "Render and display one frame, then print it as JSON and exit")]
+    pub once: bool,
+    #[arg(long, env = "PROCGEN_ART_JSON", help = "Output JSON snapshot instead of live art")]
+    pub json: bool,
+    #[arg(long, env = "PROCGEN_ART_WIDTH", default_value_t = 80, help = "Width of canvas")]
+    pub width: u16,
+    #[arg(long, env = "PROCGEN_ART_HEIGHT", default_value_t = 24, help = "Height of canvas")]
+    pub height: u16,
+    #[arg(long, env = "PROCGEN_ART_SEED", help = "Seed override for deterministic art")]
+    pub seed: Option<u64>,
+    #[arg(long, env = "PROCGEN_ART_NOISE_SEED", value_name = "U64", help = "Seed for the fine per-cell texture only, leaving the swirl composition to --seed; defaults to the art seed")]
+    pub noise_seed: Option<u64>,
+    #[arg(long, env = "PROCGEN_ART_SEED_PHRASE", conflicts_with = "seed", help = "Derive the seed from a passphrase")]
+    pub seed_phrase: Option<String>,
+    #[arg(long, env = "PROCGEN_ART_SEED_FROM_FILE", value_name = "PATH", conflicts_with_all = ["seed", "seed_phrase"], help = "Share a seed through a file, creating it if missing")]
+    pub seed_from_file: Option<PathBuf>,
+    #[arg(long, env = "PROCGEN_ART_TRUE_RANDOM", conflicts_with_all = ["seed", "seed_phrase", "seed_from_file"], help = "Seed from the OS random number generator for unrepeatable art")]
+    pub true_random: bool,
+    #[arg(long, env = "PROCGEN_ART_OVERLAY_POS", value_enum, default_value_t = OverlayPos::Center, help = "Where the metrics overlay is drawn")]
+    pub overlay_pos: OverlayPos,
+    #[arg(long, env = "PROCGEN_ART_ALERT_CPU", value_name = "PCT", help = "Flag the overlay red when CPU usage exceeds this percentage")]
+    pub alert_cpu: Option<f32>,
+    #[arg(long, env = "PROCGEN_ART_ALERT_MEM", value_name = "PCT", help = "Flag the overlay red when memory usage exceeds this percentage")]
+    pub alert_mem: Option<f32>,
+    #[arg(long, env = "PROCGEN_ART_PANELS", value_name = "ROWSxCOLS", value_parser = parse_panels, help = "Tile independently seeded canvases in a grid")]
+    pub panels: Option<PanelGrid>,
+    #[arg(long, env = "PROCGEN_ART_WATERMARK", help = "Occasionally replace the top row with a style/entropy watermark")]
+    pub watermark: bool,
+    #[arg(long, env = "PROCGEN_ART_SMOOTH", help = "Dither each cell's background between neighbouring palette stops")]
+    pub smooth: bool,
+    #[arg(long, env = "PROCGEN_ART_NO_CLEAR", help = "Overwrite frames in place instead of clearing the screen first")]
+    pub no_clear: bool,
+    #[arg(long, env = "PROCGEN_ART_INLINE", alias = "no-alt-screen", help = "Render frames one after another in the normal buffer to keep them in scrollback")]
+    pub inline: bool,
+    #[arg(long, env = "PROCGEN_ART_SCREENSAVER", help = "Run at a gentle pace and exit on any key press or mouse event")]
+    pub screensaver: bool,
+    #[arg(long, env = "PROCGEN_ART_BG_IMAGE", value_name = "PATH", help = "Modulate the art by the luminance of an image")]
+    pub bg_image: Option<PathBuf>,
+    #[arg(long, env = "PROCGEN_ART_MASK", value_name = "FILE", help = "Blank every cell outside a stencil: an image (bright is inside) or a .txt map (non-space is inside), stretched to the canvas")]
+    pub mask: Option<PathBuf>,
+    #[arg(long, env = "PROCGEN_ART_CLOCK", help = "Draw the current time in a corner of the canvas")]
+    pub clock: bool,
+    #[arg(long, env = "PROCGEN_ART_CLOCK_FORMAT", default_value = "%H:%M:%S", value_parser = parse_clock_format, help = "strftime-style format for --clock")]
+    pub clock_format: String,
+    #[arg(long, env = "PROCGEN_ART_RECORD", value_name = "PATH", help = "Append every live frame to a recording in --record-format")]
+    pub record: Option<PathBuf>,
+    #[arg(long, env = "PROCGEN_ART_RECORD_FORMAT", value_enum, default_value_t = RecordFormat::Ndjson, requires = "record", help = "Recording format: NDJSON snapshots, or plain rows under '--- frame N ---' markers, with palette colours for ansi")]
+    pub record_format: RecordFormat,
+    #[arg(long, env = "PROCGEN_ART_REPLAY", value_name = "PATH", conflicts_with = "record", help = "Replay a recording made with --record without reading system metrics; an explicit --interval overrides the recorded timing")]
+    pub replay: Option<PathBuf>,
+    #[arg(long, env = "PROCGEN_ART_AUTO_PALETTE", help = "Swap to cool, mild or warm colours by CPU temperature when sensors exist")]
+    pub auto_palette: bool,
+    #[arg(long, env = "PROCGEN_ART_PALETTE_BIAS", help = "Bias each cell's colour toward the busiest subsystem")]
+    pub palette_bias: bool,
+    #[arg(long, env = "PROCGEN_ART_TINT", help = "Tint the whole frame red, yellow or blue by whether CPU, disk or network is busiest (256-colour and truecolor only)")]
+    pub tint: bool,
+    #[arg(long, env = "PROCGEN_ART_PALETTE_STEPS", value_name = "N", value_parser = clap::value_parser!(u16).range(2..=1024), help = "Resample the palette into exactly N colours for deliberate banding (256-colour and truecolor only)")]
+    pub palette_steps: Option<u16>,
+    #[arg(long, env = "PROCGEN_ART_ONLY_WHEN_BUSY", value_name = "PCT", help = "Hold the last frame while CPU usage is below PCT and animate again once it rises")]
+    pub only_when_busy: Option<f32>,
+    #[arg(long, env = "PROCGEN_ART_ZOOM", default_value_t = 1.0, value_parser = parse_zoom, help = "Magnify the procedural field by this factor (arrow keys pan in the live view)")]
+    pub zoom: f32,
+    #[arg(long, env = "PROCGEN_ART_PAN_X", default_value_t = 0.0, allow_hyphen_values = true, value_name = "OFFSET", help = "Shift the procedural field horizontally, in canvas widths")]
+    pub pan_x: f32,
+    #[arg(long, env = "PROCGEN_ART_PAN_Y", default_value_t = 0.0, allow_hyphen_values = true, value_name = "OFFSET", help = "Shift the procedural field vertically, in canvas heights")]
+    pub pan_y: f32,
+    #[arg(long, env = "PROCGEN_ART_MIN_CONTRAST", value_name = "RATIO", value_parser = parse_contrast, help = "Switch the overlay text to black or white where its contrast against the background falls below RATIO")]
+    pub min_contrast: Option<f32>,
+    #[arg(long, env = "PROCGEN_ART_FADE_TICKS", default_value_t = 8, help = "Frames to cross-fade palettes over when switching styles with 's' (truecolor only, 0 to snap)")]
+    pub fade_ticks: u8,
+    #[arg(long, env = "PROCGEN_ART_BRAILLE_PLOT", value_enum, value_name = "METRIC", help = "Plot a scrolling braille chart of one metric instead of art")]
+    pub braille_plot: Option<PlotMetric>,
+    #[arg(long, env = "PROCGEN_ART_LEGEND", help = "Add a value scale row with tick labels to the gauge and braille-plot modes")]
+    pub legend: bool,
+    #[arg(long, env = "PROCGEN_ART_AXIS", requires = "braille_plot", help = "Frame the braille plot with percent ticks in a left gutter and time marks along the bottom")]
+    pub axis: bool,
+    #[arg(long, env = "PROCGEN_ART_METRICS_CSV", value_name = "PATH", help = "Drive the renderer from CSV rows of metrics, one frame per row, matched by header name")]
+    pub metrics_csv: Option<PathBuf>,
+    #[arg(long = "metrics", env = "PROCGEN_ART_METRICS", value_enum, value_delimiter = ',', value_name = "LIST", help = "Only refresh these subsystems (cpu, memory, disks, networks, sensors) and zero the rest; defaults to all")]
+    pub subsystems: Vec<Subsystem>,
+    #[arg(long, env = "PROCGEN_ART_CPU_WINDOW", value_name = "MS", value_parser = clap::value_parser!(u64).range(1..), help = "Report CPU usage as the mean of the samples taken over the trailing MS milliseconds")]
+    pub cpu_window: Option<u64>,
+    #[arg(long = "disk-weight", env = "PROCGEN_ART_DISK_WEIGHTS", value_parser = parse_device_weight, value_delimiter = ',', value_name = "NAME=WEIGHT", help = "Scale how much a named disk counts towards the disk signal (unlisted disks weigh 1); repeatable")]
+    pub disk_weights: Vec<(String, f64)>,
+    #[arg(long = "net-weight", env = "PROCGEN_ART_NET_WEIGHTS", value_parser = parse_device_weight, value_delimiter = ',', value_name = "NAME=WEIGHT", help = "Scale how much a named network interface counts towards the network signal (unlisted interfaces weigh 1); repeatable")]
+    pub net_weights: Vec<(String, f64)>,
+    #[arg(long, env = "PROCGEN_ART_SIMULATE", help = "Drive the renderer from deterministic simulated metrics seeded by --seed")]
+    pub simulate: bool,
+    #[arg(long, env = "PROCGEN_ART_TRAILS", value_name = "DECAY", value_parser = parse_decay, help = "Leave fading trails by blending in the previous frame scaled by DECAY (0 disables)")]
+    pub trails: Option<f32>,
+    #[arg(long, env = "PROCGEN_ART_INTERPOLATE", help = "Tween between metric samples with intermediate frames inside each interval")]
+    pub interpolate: bool,
+    #[arg(long, env = "PROCGEN_ART_PHASE_LOCK", help = "Derive the frame tick from the wall clock so seeded displays stay in sync")]
+    pub phase_lock: bool,
+    #[arg(long, env = "PROCGEN_ART_RNG_STATE", help = "Reseed the noise every frame from seed and tick, and record both in snapshots")]
+    pub rng_state: bool,
+    #[arg(long, env = "PROCGEN_ART_START_TICK", visible_alias = "tick", value_name = "TICK", help = "Resume the per-frame noise sequence at TICK, or with --json render that tick directly (implies --rng-state)")]
+    pub start_tick: Option<u64>,
+    #[arg(long, env = "PROCGEN_ART_PULSE", help = "Breathe the frame brightness at a rate driven by the load average")]
+    pub pulse: bool,
+    #[arg(long, env = "PROCGEN_ART_RAMP", value_parser = parse_ramp, help = "Override the style's glyph ramp, darkest to brightest (e.g. \" .oO@\")")]
+    pub ramp: Option<String>,
+    #[arg(long, env = "PROCGEN_ART_RAMP_BY_LOAD", help = "Switch between the style's soft and jagged glyph ramps as CPU load rises")]
+    pub ramp_by_load: bool,
+    #[arg(long, env = "PROCGEN_ART_MONO", help = "Disable all colour output and rely on the ASCII density ramp")]
+    pub mono: bool,
+    #[arg(long, env = "PROCGEN_ART_LOG_JSON", help = "Write warnings to stderr as one JSON object per line with level and message")]
+    pub log_json: bool,
+    #[arg(long, visible_alias = "color-mode", env = "PROCGEN_ART_COLOR", value_enum, value_name = "DEPTH", default_value = "auto", help = "Force the colour backend instead of detecting it from TERM and COLORTERM; truecolor shades every cell along the palette")]
+    pub color: ColorChoice,
+    #[arg(long, env = "PROCGEN_ART_SIXEL", help = "Draw the intensity field as Sixel graphics on capable terminals")]
+    pub sixel: bool,
+    #[arg(long, env = "PROCGEN_ART_IMAGE_PROTOCOL", value_enum, value_name = "PROTOCOL", help = "Draw the intensity field as an inline PNG via the kitty or iTerm2 image protocol")]
+    pub image_protocol: Option<ImageProtocol>,
+    #[arg(long, env = "PROCGEN_ART_RICH_JSON", help = "Emit the JSON frame as {char, intensity, color} cells")]
+    pub rich_json: bool,
+    #[arg(long, env = "PROCGEN_ART_LEVELS", value_name = "N", value_parser = clap::value_parser!(u8).range(2..), help = "Posterize the intensity field into N discrete levels")]
+    pub levels: Option<u8>,
+    #[arg(long, env = "PROCGEN_ART_CENTER_X", value_parser = parse_fraction, help = "Focal column (0.0-1.0) of a radial gradient; enables radial mode")]
+    pub center_x: Option<f32>,
+    #[arg(long, env = "PROCGEN_ART_CENTER_Y", value_parser = parse_fraction, help = "Focal row (0.0-1.0) of a radial gradient; enables radial mode")]
+    pub center_y: Option<f32>,
+    #[arg(long, env = "PROCGEN_ART_NO_CPU_INFLUENCE", help = "Ignore CPU usage when computing intensity")]
+    pub no_cpu_influence: bool,
+    #[arg(long, env = "PROCGEN_ART_NO_MEM_INFLUENCE", help = "Ignore memory usage when computing intensity")]
+    pub no_mem_influence: bool,
+    #[arg(long, env = "PROCGEN_ART_NO_NET_INFLUENCE", help = "Ignore network traffic when computing intensity")]
+    pub no_net_influence: bool,
+    #[arg(long, env = "PROCGEN_ART_SEAMLESS_LOOP", requires = "frames", help = "Emit --frames JSON snapshots whose noise cycles through one period so the last frame flows back into the first")]
+    pub seamless_loop: bool,
+    #[arg(long, env = "PROCGEN_ART_FRAMES", value_name = "N", value_parser = clap::value_parser!(u64).range(2..), help = "Number of frames in a --seamless-loop or --apng")]
+    pub frames: Option<u64>,
+    #[arg(long, env = "PROCGEN_ART_TRANSITION", value_name = "FROM:TO:FRAMES", value_parser = parse_transition, conflicts_with = "seamless_loop", help = "Emit FRAMES JSON snapshots cross-fading the intensity and palette from one style to another")]
+    pub transition: Option<Transition>,
+    #[arg(long, env = "PROCGEN_ART_REPEAT", value_name = "COUNT", value_parser = clap::value_parser!(u64).range(1..), help = "With --json, emit COUNT snapshots spaced by --interval")]
+    pub repeat: Option<u64>,
+    #[arg(long, env = "PROCGEN_ART_CODEPOINTS", conflicts_with = "rich_json", help = "Emit each JSON frame row as an array of Unicode code points")]
+    pub codepoints: bool,
+    #[arg(long, env = "PROCGEN_ART_COMPACT", help = "Emit JSON on a single line instead of pretty-printed")]
+    pub compact: bool,
+    #[arg(long, env = "PROCGEN_ART_JSON_CASE", value_enum, default_value_t = JsonCase::Snake, help = "Field naming for printed JSON; recordings keep snake_case")]
+    pub json_case: JsonCase,
+    #[arg(long, env = "PROCGEN_ART_CHECKSUM", help = "Include a stable FNV-1a checksum of the frame text in JSON snapshots")]
+    pub checksum: bool,
+    #[arg(long, env = "PROCGEN_ART_STAMP_COMMIT", help = "Record the git commit the binary was built from in JSON snapshots")]
+    pub stamp_commit: bool,
+    #[arg(long, env = "PROCGEN_ART_CAPTURE_ENV", help = "Record TERM, COLORTERM, the terminal size and the detected colour depth in JSON snapshots")]
+    pub capture_env: bool,
+    #[arg(long, env = "PROCGEN_ART_STATS", help = "Add glyph coverage and frequency stats to the JSON snapshot")]
+    pub stats: bool,
+    #[arg(long, env = "PROCGEN_ART_STREAM_OUT", value_name = "PATH", help = "Stream one frame row by row to a file and exit")]
+    pub stream_out: Option<PathBuf>,
+    #[arg(long, env = "PROCGEN_ART_METRICS_OUT", value_name = "PATH", help = "Atomically rewrite this file with the latest metrics JSON every live frame")]
+    pub metrics_out: Option<PathBuf>,
+    #[arg(long, env = "PROCGEN_ART_LOG", value_name = "PATH", help = "Append one JSON line per live frame to PATH with the time, metrics, frame checksum and render time")]
+    pub log: Option<PathBuf>,
+    #[arg(long, env = "PROCGEN_ART_JSON_FD", value_name = "FD", value_parser = clap::value_parser!(u32).range(2..), help = "Write one compact JSON snapshot per live frame to the already-open file descriptor FD, keeping stdout for the art")]
+    pub json_fd: Option<u32>,
+    #[arg(long, env = "PROCGEN_ART_HISTORY", value_name = "N", value_parser = clap::value_parser!(u32).range(1..), help = "Keep the last N metric summaries from the live view and include them in JSON snapshots")]
+    pub history: Option<u32>,
+    #[arg(long, env = "PROCGEN_ART_FRAME_OUT", value_name = "PATH", help = "Atomically rewrite this file with the latest frame text every live frame")]
+    pub frame_out: Option<PathBuf>,
+    #[arg(long, env = "PROCGEN_ART_FIFO", value_name = "PATH", help = "Write each live frame to an existing named pipe, pausing while no reader is attached")]
+    pub fifo: Option<PathBuf>,
+    #[arg(long, env = "PROCGEN_ART_EMIT", value_name = "URL", value_parser = parse_emit, help = "Send each live frame's metrics as a JSON line to udp://host:port or tcp://host:port, retrying with backoff")]
+    pub emit: Option<EmitTarget>,
+    #[arg(long, env = "PROCGEN_ART_HTML", value_name = "PATH", help = "Write the frame as an HTML <pre> with per-cell colours")]
+    pub html: Option<PathBuf>,
+    #[arg(long, env = "PROCGEN_ART_APNG", value_name = "PATH", requires = "frames", conflicts_with_all = ["seamless_loop", "transition"], help = "Write --frames seamlessly looping frames to PATH as a truecolor animated PNG, --interval ms apart")]
+    pub apng: Option<PathBuf>,
+    #[arg(long, env = "PROCGEN_ART_INTERPOLATE_FRAMES", value_name = "FACTOR", default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..=16), requires = "apng", help = "Blend FACTOR-1 in-between frames after each exported --apng frame, shortening the delay to keep the loop length")]
+    pub interpolate_frames: u32,
+    #[arg(long, env = "PROCGEN_ART_LETTERBOX", value_name = "RATIO", value_parser = parse_ratio, help = "Fit the live canvas to the terminal at a fixed cell aspect ratio (e.g. 16:9), centred with blank margins")]
+    pub letterbox: Option<f32>,
+    #[arg(long, env = "PROCGEN_ART_ANTI_BURNIN", help = "Slowly orbit the live frame by a few cells and vary its brightness to spare OLED and plasma panels")]
+    pub anti_burnin: bool,
+    #[arg(long, env = "PROCGEN_ART_BURNIN_SHIFT", value_name = "CELLS", default_value_t = 1, value_parser = clap::value_parser!(u16).range(1..), requires = "anti_burnin", help = "How many cells --anti-burnin moves the frame away from its origin")]
+    pub burnin_shift: u16,
+    #[arg(long, env = "PROCGEN_ART_BURNIN_PERIOD", value_name = "SECS", default_value_t = 60, value_parser = clap::value_parser!(u64).range(1..), requires = "anti_burnin", help = "Seconds between --anti-burnin shifts")]
+    pub burnin_period: u64,
+    #[arg(long, env = "PROCGEN_ART_MAX_CELLS", value_name = "N", value_parser = clap::value_parser!(u32).range(1..), help = "Scale the canvas down, keeping its aspect ratio, when width*height exceeds N cells")]
+    pub max_cells: Option<u32>,
+    #[arg(long, env = "PROCGEN_ART_HIGHLIGHT_ANOMALIES", help = "Flash the overlay row when a metric strays from its running mean")]
+    pub highlight_anomalies: bool,
+    #[arg(long, env = "PROCGEN_ART_ANOMALY_SIGMA", default_value_t = 3.0, value_name = "N", help = "Standard deviations from the running mean that count as an anomaly")]
+    pub anomaly_sigma: f64,
+    #[arg(long, env = "PROCGEN_ART_HOLD_ON_SPIKE", value_name = "TICKS", help = "Freeze the frame for TICKS extra frames, more for bigger jumps, when a metric strays past --anomaly-sigma")]
+    pub hold_on_spike: Option<u32>,
+    #[arg(long, env = "PROCGEN_ART_SHOW_FPS", help = "Show the measured frame rate and frame count in a corner of the live view")]
+    pub show_fps: bool,
+    #[arg(long, env = "PROCGEN_ART_DEBUG", help = "Show how many non-finite intensity cells were zeroed in each live frame")]
+    pub debug: bool,
+    #[arg(long, env = "PROCGEN_ART_GRID_OVERLAY", help = "Draw faint guides on every 10th row and column to check cell alignment")]
+    pub grid_overlay: bool,
+    #[arg(long, env = "PROCGEN_ART_KEYMAP", value_name = "PATH", help = "JSON file rebinding live keys, e.g. {\"quit\": [\"x\"], \"next-style\": [\"n\"]}")]
+    pub keymap: Option<PathBuf>,
+    #[arg(long, env = "PROCGEN_ART_THEME", value_name = "PATH", help = "JSON file with a custom palette and ramp, e.g. {\"colors\": [\"dark_blue\", \"#ffaa00\"], \"ramp\": \" .:#\"}")]
+    pub theme: Option<PathBuf>,
+    #[arg(long, env = "PROCGEN_ART_ASCII_ONLY", help = "Replace every non-ASCII glyph with '#' for terminals or fonts that cannot show them")]
+    pub ascii_only: bool,
+    #[arg(long, env = "PROCGEN_ART_FALLBACK_GLYPH", value_name = "CHAR", help = "Glyph to substitute for non-ASCII characters (implies --ascii-only)")]
+    pub fallback_glyph: Option<char>,
+    #[arg(long, env = "PROCGEN_ART_DUMP_PALETTE", help = "Print the style's palette stops as hex, RGB and 256-colour values, then exit")]
+    pub dump_palette: bool,
+    #[arg(long, env = "PROCGEN_ART_OVERLAY_SCALE", default_value_t = 1, value_name = "N", value_parser = clap::value_parser!(u8).range(1..=8), help = "Enlarge the metrics overlay N times in each direction for big displays")]
+    pub overlay_scale: u8,
+    #[arg(long, env = "PROCGEN_ART_OVERLAY_LINES", value_enum, value_delimiter = ',', value_name = "LINES", help = "Draw the overlay as a block with one line per metric, e.g. cpu,mem,net,disk,temp")]
+    pub overlay_lines: Vec<OverlayLine>,
+    #[arg(long, env = "PROCGEN_ART_RAIN_SPEED", default_value_t = 1.0, value_name = "MULT", help = "Scale how far received network traffic makes the rain style fall each frame")]
+    pub rain_speed: f32,
+    #[arg(long, env = "PROCGEN_ART_RAIN_DENSITY", default_value_t = 1.0, value_name = "MULT", help = "Scale how many drops CPU usage spawns in the rain style")]
+    pub rain_density: f32,
+    #[arg(long = "filter", env = "PROCGEN_ART_FILTERS", value_enum, value_delimiter = ',', value_name = "FILTER", help = "Post-process each frame with these filters, applied in order; repeatable")]
+    pub filters: Vec<FilterKind>,
+    #[arg(long, env = "PROCGEN_ART_SCANLINES", value_name = "N", value_parser = clap::value_parser!(u16).range(2..), help = "Dim every Nth row for a CRT scanline look")]
+    pub scanlines: Option<u16>,
+    #[arg(long, env = "PROCGEN_ART_SCANLINE_ROLL", requires = "scanlines", help = "Roll the scanlines down one row per frame")]
+    pub scanline_roll: bool,
+    #[arg(long, env = "PROCGEN_ART_CHECK", help = "Validate the arguments, print OK or the first error, and exit")]
+    pub check: bool,
+    #[arg(long, env = "PROCGEN_ART_STRICT", help = "Enforce 500-line executable budget")]
+    pub strict: bool,
+    #[arg(long, env = "PROCGEN_ART_MAX_LINES", value_name = "N", help = "Fail if any source file exceeds N executable lines (overrides --strict)")]
+    pub max_lines: Option<usize>,
+    #[command(subcommand)]
+    #[serde(skip)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    #[command(about = "Launch the generative art canvas")]
+    Run,
+    #[command(about = "Print current system metrics in JSON")]
+    Metrics {
+        #[arg(long, env = "PROCGEN_ART_ONELINE", help = "Print a single status-bar line instead of JSON")]
+        oneline: bool,
+        #[arg(long, env = "PROCGEN_ART_FORMAT", help = "Template for --oneline using {cpu} {mem} {net} {disk} {load}")]
+        format: Option<String>,
+        #[arg(long, env = "PROCGEN_ART_PROMETHEUS", conflicts_with = "oneline", help = "Print metrics in Prometheus text exposition format")]
+        prometheus: bool,
+        #[arg(long, env = "PROCGEN_ART_WATCH", help = "Keep printing every --interval milliseconds")]
+        watch: bool,
+    },
+    #[command(about = "Print a one-line health assessment of the current metrics")]
+    Summary {
+        #[arg(long, env = "PROCGEN_ART_IDLE_CPU", default_value_t = 10.0, value_name = "PCT", help = "CPU usage below which the system reads as idle")]
+        idle_cpu: f32,
+        #[arg(long, env = "PROCGEN_ART_BUSY_CPU", default_value_t = 80.0, value_name = "PCT", help = "CPU usage at which the CPU reads as busy")]
+        busy_cpu: f32,
+        #[arg(long, env = "PROCGEN_ART_HIGH_MEMORY", default_value_t = 90.0, value_name = "PCT", help = "Memory usage at which memory pressure reads as high")]
+        high_memory: f32,
+        #[arg(long, env = "PROCGEN_ART_HEAVY_NETWORK", default_value_t = 1024.0, value_name = "KIB", help = "Combined network traffic per sample at which the network reads as heavy")]
+        heavy_network: f32,
+    },
+    #[command(about = "Replay a recording made with --record")]
+    Playback {
+        #[arg(help = "Recording to replay")]
+        file: PathBuf,
+    },
+    #[command(about = "Check a JSON snapshot for internal consistency")]
+    Validate {
+        #[arg(help = "Snapshot file to check")]
+        file: PathBuf,
+    },
+    #[command(about = "Convert an image to text art with the style's ramp at the canvas size")]
+    AsciiArt {
+        #[arg(help = "Image to convert")]
+        image: PathBuf,
+        #[arg(long, env = "PROCGEN_ART_NOISE", default_value_t = 0.0, value_parser = parse_fraction, help = "Fraction of procedural noise to mix into the image luminance")]
+        noise: f32,
+    },
+    #[command(about = "Render a run of seeds headlessly and list the highest-scoring ones")]
+    CompareSeeds {
+        #[arg(long, env = "PROCGEN_ART_COUNT", default_value_t = 20, help = "Number of sequential seeds to render")]
+        count: usize,
+        #[arg(long, env = "PROCGEN_ART_RANK_BY", value_enum, default_value_t = SeedScore::Entropy, help = "Score to rank frames by")]
+        by: SeedScore,
+        #[arg(long, env = "PROCGEN_ART_TOP", default_value_t = 5, help = "Number of seeds to print")]
+        top: usize,
+    },
+    #[command(about = "Print a metric-free test pattern to check colour depth, cell aspect and glyphs")]
+    Calibrate,
+    #[command(about = "Time headless rendering with fixed synthetic metrics and report frames per second")]
+    Bench {
+        #[arg(long, env = "PROCGEN_ART_BENCH_FRAMES", default_value_t = 100, value_parser = clap::value_parser!(u32).range(1..), help = "Frames to render per style")]
+        frames: u32,
+        #[arg(long, env = "PROCGEN_ART_ALL_STYLES", help = "Benchmark every registered style at the same canvas size")]
+        all_styles: bool,
+    },
+    #[command(about = "Show one metric as a full-screen gauge")]
+    Gauge {
+        #[arg(long, env = "PROCGEN_ART_METRIC", value_enum, default_value_t = GaugeMetric::Cpu, help = "Metric to display")]
+        metric: GaugeMetric,
+    },
+    #[command(about = "Print the effective configuration after style defaults and flags as JSON")]
+    Config,
+    #[command(about = "Render a contact sheet of thumbnails for sequential seeds")]
+    SeedGrid {
+        #[arg(long, env = "PROCGEN_ART_COUNT", default_value_t = 9, help = "Number of seeds to render")]
+        count: usize,
+        #[arg(long, env = "PROCGEN_ART_THUMB_WIDTH", default_value_t = 20, help = "Thumbnail width")]
+        thumb_width: u16,
+        #[arg(long, env = "PROCGEN_ART_THUMB_HEIGHT", default_value_t = 6, help = "Thumbnail height")]
+        thumb_height: u16,
+        #[arg(long, env = "PROCGEN_ART_PNG", value_name = "PATH", help = "Write the contact sheet to PATH as a PNG instead of printing it")]
+        png: Option<PathBuf>,
+    },
+}
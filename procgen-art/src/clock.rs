@@ -0,0 +1,47 @@
+use crate::render::{stamp, Canvas};
+use chrono::{
+    format::{Item, StrftimeItems},
+    DateTime, TimeZone,
+};
+use std::fmt::Display;
+
+pub fn parse_clock_format(value: &str) -> Result<String, String> {
+    if StrftimeItems::new(value).any(|item| item == Item::Error) {
+        return Err(format!("invalid clock format '{value}'"));
+    }
+    Ok(value.to_string())
+}
+
+pub fn clock_text<Tz: TimeZone>(now: &DateTime<Tz>, format: &str) -> String
+where
+    Tz::Offset: Display,
+{
+    now.format(format).to_string()
+}
+
+pub fn stamp_clock(canvas: &mut Canvas, text: &str) {
+    let row = if canvas.overlay_row == 0 {
+        canvas.rows.len().saturating_sub(1)
+    } else {
+        0
+    };
+    if let Some(line) = canvas.rows.get_mut(row) {
+        let start = line.chars().count().saturating_sub(text.chars().count());
+        stamp(line, start, text);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    #[test]
+    fn clock_text_formats_a_fixed_timestamp() {
+        let now = Utc.with_ymd_and_hms(2024, 3, 9, 7, 5, 30).unwrap();
+        assert_eq!(clock_text(&now, "%Y-%m-%d %H:%M:%S"), "2024-03-09 07:05:30");
+        assert_eq!(clock_text(&now, "%a %H:%M"), "Sat 07:05");
+        assert!(parse_clock_format("%H:%M").is_ok());
+        assert!(parse_clock_format("%Q").is_err());
+    }
+}
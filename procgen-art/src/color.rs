@@ -0,0 +1,315 @@
+use crate::{metrics::NormalizedMetrics, render::palette_stops};
+use clap::ValueEnum;
+use serde::Serialize;
+use crossterm::style::Color;
+
+pub const TINT_STRENGTH: f32 = 0.2;
+
+pub fn ansi_rgb(color: Color) -> (u8, u8, u8) {
+    match color {
+        Color::Black => (0, 0, 0),
+        Color::DarkGrey => (128, 128, 128),
+        Color::Red => (255, 0, 0),
+        Color::DarkRed => (128, 0, 0),
+        Color::Green => (0, 255, 0),
+        Color::DarkGreen => (0, 128, 0),
+        Color::Yellow => (255, 255, 0),
+        Color::DarkYellow => (128, 128, 0),
+        Color::Blue => (0, 0, 255),
+        Color::DarkBlue => (0, 0, 128),
+        Color::Magenta => (255, 0, 255),
+        Color::DarkMagenta => (128, 0, 128),
+        Color::Cyan => (0, 255, 255),
+        Color::DarkCyan => (0, 128, 128),
+        Color::White => (255, 255, 255),
+        Color::Grey => (192, 192, 192),
+        Color::Rgb { r, g, b } => (r, g, b),
+        _ => (255, 255, 255),
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ColorDepth {
+    #[default]
+    Ansi16,
+    Ansi256,
+    TrueColor,
+    Mono,
+}
+
+impl ColorDepth {
+    pub fn next(self) -> Self {
+        match self {
+            ColorDepth::Ansi16 => ColorDepth::Ansi256,
+            ColorDepth::Ansi256 => ColorDepth::TrueColor,
+            ColorDepth::TrueColor => ColorDepth::Mono,
+            ColorDepth::Mono => ColorDepth::Ansi16,
+        }
+    }
+
+    pub fn detect(term: &str, colorterm: &str) -> Self {
+        if ["truecolor", "24bit"].contains(&colorterm) {
+            ColorDepth::TrueColor
+        } else if term.contains("256color") {
+            ColorDepth::Ansi256
+        } else if term == "dumb" {
+            ColorDepth::Mono
+        } else {
+            ColorDepth::Ansi16
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ColorDepth::Ansi16 => "16 colours",
+            ColorDepth::Ansi256 => "256 colours",
+            ColorDepth::TrueColor => "truecolor",
+            ColorDepth::Mono => "mono",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ColorChoice {
+    #[default]
+    Auto,
+    #[value(name = "16", alias = "ansi16")]
+    #[serde(rename = "16")]
+    Ansi16,
+    #[value(name = "256", alias = "ansi256")]
+    #[serde(rename = "256")]
+    Ansi256,
+    Truecolor,
+    Mono,
+}
+
+impl ColorChoice {
+    pub fn resolve(self, detected: ColorDepth) -> ColorDepth {
+        match self {
+            ColorChoice::Auto => detected,
+            ColorChoice::Ansi16 => ColorDepth::Ansi16,
+            ColorChoice::Ansi256 => ColorDepth::Ansi256,
+            ColorChoice::Truecolor => ColorDepth::TrueColor,
+            ColorChoice::Mono => ColorDepth::Mono,
+        }
+    }
+}
+
+pub fn cube_index(r: u8, g: u8, b: u8) -> u8 {
+    let level = |channel: u8| (channel as u16 * 5 / 255) as u8;
+    16 + 36 * level(r) + 6 * level(g) + level(b)
+}
+
+pub fn at_depth(color: Color, depth: ColorDepth) -> Color {
+    let (r, g, b) = ansi_rgb(color);
+    match depth {
+        ColorDepth::Ansi16 | ColorDepth::Mono => color,
+        ColorDepth::Ansi256 => Color::AnsiValue(cube_index(r, g, b)),
+        ColorDepth::TrueColor => Color::Rgb { r, g, b },
+    }
+}
+
+pub fn blend(from: Color, to: Color, t: f32) -> Color {
+    let t = t.clamp(0.0, 1.0);
+    let ((r0, g0, b0), (r1, g1, b1)) = (ansi_rgb(from), ansi_rgb(to));
+    let mix = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+    Color::Rgb { r: mix(r0, r1), g: mix(g0, g1), b: mix(b0, b1) }
+}
+
+pub fn gradient(colors: &[Color], intensity: f32) -> Color {
+    let (lo, hi, frac) = palette_stops(colors.len(), intensity);
+    blend(colors[lo], colors[hi], frac)
+}
+
+pub fn band(colors: &[Color], intensity: f32) -> Color {
+    let (lo, hi, frac) = palette_stops(colors.len(), intensity);
+    if frac < 0.5 {
+        colors[lo]
+    } else {
+        colors[hi]
+    }
+}
+
+pub fn crossfade(from: &[Color], to: &[Color], t: f32) -> Vec<Color> {
+    if from.is_empty() {
+        return to.to_vec();
+    }
+    to.iter()
+        .enumerate()
+        .map(|(idx, &color)| blend(from[idx * from.len() / to.len()], color, t))
+        .collect()
+}
+
+pub fn hue_rotate(color: Color, degrees: f32) -> Color {
+    let (r, g, b) = ansi_rgb(color);
+    let (sin, cos) = degrees.to_radians().sin_cos();
+    let channel = |kr: f32, kg: f32, kb: f32| (kr * r as f32 + kg * g as f32 + kb * b as f32).round().clamp(0.0, 255.0) as u8;
+    Color::Rgb {
+        r: channel(0.213 + 0.787 * cos - 0.213 * sin, 0.715 - 0.715 * cos - 0.715 * sin, 0.072 - 0.072 * cos + 0.928 * sin),
+        g: channel(0.213 - 0.213 * cos + 0.143 * sin, 0.715 + 0.285 * cos + 0.140 * sin, 0.072 - 0.072 * cos - 0.283 * sin),
+        b: channel(0.213 - 0.213 * cos - 0.787 * sin, 0.715 - 0.715 * cos + 0.715 * sin, 0.072 + 0.928 * cos + 0.072 * sin),
+    }
+}
+
+pub fn cycle_palette(colors: &[Color], shift: usize) -> Vec<Color> {
+    let mut cycled = colors.to_vec();
+    if !cycled.is_empty() {
+        cycled.rotate_left(shift % colors.len());
+    }
+    cycled
+}
+
+pub fn dominant_tint(inputs: &NormalizedMetrics) -> Option<Color> {
+    [
+        (inputs.cpu, Color::Rgb { r: 255, g: 48, b: 32 }),
+        (inputs.disk, Color::Rgb { r: 255, g: 208, b: 0 }),
+        (inputs.network, Color::Rgb { r: 32, g: 96, b: 255 }),
+    ]
+    .into_iter()
+    .filter(|(level, _)| *level > 0.0)
+    .reduce(|best, candidate| if candidate.0 > best.0 { candidate } else { best })
+    .map(|(_, color)| color)
+}
+
+pub fn tint(colors: &[Color], hue: Color, strength: f32) -> Vec<Color> {
+    colors.iter().map(|&color| blend(color, hue, strength)).collect()
+}
+
+pub fn stepped_palette(colors: &[Color], steps: u16) -> Vec<Color> {
+    if colors.len() < 2 {
+        return colors.to_vec();
+    }
+    let span = (steps.max(2) - 1) as f32;
+    (0..steps.max(2))
+        .map(|step| {
+            let pos = step as f32 / span * (colors.len() - 1) as f32;
+            let lo = (pos.floor() as usize).min(colors.len() - 2);
+            blend(colors[lo], colors[lo + 1], pos - lo as f32)
+        })
+        .collect()
+}
+
+pub fn luminance(color: Color) -> f32 {
+    let (r, g, b) = ansi_rgb(color);
+    let linear = |channel: u8| {
+        let c = channel as f32 / 255.0;
+        if c <= 0.03928 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    0.2126 * linear(r) + 0.7152 * linear(g) + 0.0722 * linear(b)
+}
+
+pub fn contrast_ratio(a: Color, b: Color) -> f32 {
+    let (la, lb) = (luminance(a), luminance(b));
+    (la.max(lb) + 0.05) / (la.min(lb) + 0.05)
+}
+
+pub fn legible_text(background: Color, min_ratio: f32) -> Option<Color> {
+    if contrast_ratio(Color::White, background) >= min_ratio {
+        return None;
+    }
+    if contrast_ratio(Color::Black, background) > contrast_ratio(Color::White, background) {
+        Some(Color::Black)
+    } else {
+        Some(Color::White)
+    }
+}
+
+pub fn parse_contrast(value: &str) -> Result<f32, String> {
+    let ratio: f32 = value.parse().map_err(|_| format!("invalid contrast ratio '{value}'"))?;
+    if !(1.0..=21.0).contains(&ratio) {
+        return Err("expected a contrast ratio between 1 and 21".to_string());
+    }
+    Ok(ratio)
+}
+
+pub fn hex(color: Color) -> String {
+    let (r, g, b) = ansi_rgb(color);
+    format!("#{r:02x}{g:02x}{b:02x}")
+}
+
+pub fn palette_dump(colors: &[Color]) -> String {
+    let span = colors.len().saturating_sub(1).max(1) as f32;
+    colors
+        .iter()
+        .enumerate()
+        .map(|(idx, &color)| {
+            let (r, g, b) = ansi_rgb(color);
+            format!("{:.2}  #{r:02x}{g:02x}{b:02x}  rgb({r}, {g}, {b})  ansi256 {}\n", idx as f32 / span, cube_index(r, g, b))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::palette;
+
+    #[test]
+    fn depth_cycles_through_every_mode_and_back() {
+        let mut depth = ColorDepth::Ansi16;
+        let mut seen = Vec::new();
+        for _ in 0..4 {
+            depth = depth.next();
+            seen.push(depth);
+        }
+        assert_eq!(seen, [ColorDepth::Ansi256, ColorDepth::TrueColor, ColorDepth::Mono, ColorDepth::Ansi16]);
+    }
+
+    #[test]
+    fn crossfade_midpoint_averages_each_stop() {
+        let black = Color::Rgb { r: 0, g: 0, b: 0 };
+        let from = [black, Color::Rgb { r: 200, g: 100, b: 0 }];
+        let to = [Color::Rgb { r: 100, g: 50, b: 255 }, Color::Rgb { r: 0, g: 0, b: 0 }];
+        assert_eq!(crossfade(&from, &to, 0.5), vec![Color::Rgb { r: 50, g: 25, b: 128 }, Color::Rgb { r: 100, g: 50, b: 0 }]);
+        assert_eq!(crossfade(&from, &to, 1.0), to.to_vec());
+        let longer = [to[0], to[0], to[0], to[0]];
+        assert_eq!(crossfade(&from, &longer, 0.5)[3], Color::Rgb { r: 150, g: 75, b: 128 });
+    }
+
+    #[test]
+    fn contrast_guard_swaps_text_only_below_the_ratio() {
+        assert!((contrast_ratio(Color::White, Color::Black) - 21.0).abs() < 1e-3);
+        assert_eq!(contrast_ratio(Color::Blue, Color::Blue), 1.0);
+        assert_eq!(contrast_ratio(Color::Red, Color::White), contrast_ratio(Color::White, Color::Red));
+        let grey = Color::DarkGrey;
+        let white_on_grey = contrast_ratio(Color::White, grey);
+        assert_eq!(legible_text(grey, white_on_grey), None);
+        assert_eq!(legible_text(grey, white_on_grey + 0.01), Some(Color::Black));
+        assert_eq!(legible_text(Color::DarkBlue, 21.0), Some(Color::White));
+    }
+
+    #[test]
+    fn plasma_dump_lists_its_rgb_stops() {
+        let expected = [
+            "0.00  #ff00ff  rgb(255, 0, 255)  ansi256 201",
+            "0.33  #800080  rgb(128, 0, 128)  ansi256 90",
+            "0.67  #0000ff  rgb(0, 0, 255)  ansi256 21",
+            "1.00  #000000  rgb(0, 0, 0)  ansi256 16",
+        ];
+        assert_eq!(palette_dump(&palette("plasma")), expected.map(|line| format!("{line}\n")).concat());
+    }
+
+    #[test]
+    fn network_dominant_inputs_select_the_blue_tint() {
+        let inputs = NormalizedMetrics { cpu: 0.2, memory: 0.9, network: 0.7, disk: 0.4 };
+        assert_eq!(dominant_tint(&inputs), Some(Color::Rgb { r: 32, g: 96, b: 255 }));
+        let idle = NormalizedMetrics { cpu: 0.0, memory: 0.5, network: 0.0, disk: 0.0 };
+        assert_eq!(dominant_tint(&idle), None);
+    }
+
+    #[test]
+    fn auto_picks_truecolor_from_colorterm_and_explicit_modes_win() {
+        assert_eq!(ColorDepth::detect("xterm", "truecolor"), ColorDepth::TrueColor);
+        assert_eq!(ColorDepth::detect("xterm", "24bit"), ColorDepth::TrueColor);
+        assert_eq!(ColorDepth::detect("xterm-256color", ""), ColorDepth::Ansi256);
+        assert_eq!(ColorDepth::detect("xterm", ""), ColorDepth::Ansi16);
+        assert_eq!(ColorChoice::Auto.resolve(ColorDepth::TrueColor), ColorDepth::TrueColor);
+        assert_eq!(ColorChoice::Ansi16.resolve(ColorDepth::TrueColor), ColorDepth::Ansi16);
+        assert_eq!(ColorChoice::Truecolor.resolve(ColorDepth::Ansi16), ColorDepth::TrueColor);
+    }
+}
@@ -0,0 +1,460 @@
+use crate::{
+    cli::Args,
+    options::{capped_size, capture, decorate, flow_for, metrics_source, render_options, resolve_seed, rng_state, seeded_source},
+};
+use crossterm::{
+    event::{self, Event},
+    execute, queue,
+    style::{Print, ResetColor, SetBackgroundColor, SetForegroundColor},
+    terminal,
+};
+use procgen_art::{
+    ascii::ascii_canvas,
+    atomic::write_atomic,
+    bench::{bench_table, BenchResult, BenchSummary, BENCH_SEED},
+    calibrate::calibration_lines,
+    color::{ansi_rgb, at_depth, crossfade, hex, ColorDepth},
+    demoscene::demoscene_metrics,
+    frames::run_frames,
+    gauge::{display_gauge, gauge_color, gauge_line, GaugeMetric},
+    graphics::{encode_apng, encode_png, interpolate_fields},
+    html::render_html,
+    live::{display_frame, DisplayOptions, LiveState, TerminalGuard},
+    metrics::{gather_metrics, Metrics, NormalizedMetrics},
+    panels::{contact_grid, contact_sheet, render_panels, seed_sequence, seeded_panels},
+    prometheus::prometheus_text,
+    record::load_playback,
+    render::{effective_ramp, palette, stream_frame, RenderOptions},
+    scene::{draw, Scene},
+    sixel::{SCALE_X, SCALE_Y},
+    snapshot::{print_snapshot, to_json, Snapshot},
+    stats::{rank_seeds, score_frame, SeedScore},
+    styles::style_names,
+    substrate::Substrate,
+    summary::{health_summary, Thresholds},
+    template::{render_template, DEFAULT_ONELINE},
+    termenv::detect_depth,
+    transition::{blend_canvases, transition_t, Transition},
+    validate::validate_snapshot,
+};
+use rand::{rngs::StdRng, SeedableRng};
+use std::{
+    cell::Cell,
+    fs::File,
+    io::{self, stdout, BufReader, BufWriter, Write},
+    ops::ControlFlow,
+    path::Path,
+    time::{Duration, Instant},
+};
+
+pub fn run_snapshot(args: &Args) -> io::Result<()> {
+    write_snapshots(args, |payload| print_snapshot(payload, args.compact, args.json_case))
+}
+
+fn write_snapshots(args: &Args, mut emit: impl FnMut(&Snapshot) -> io::Result<()>) -> io::Result<()> {
+    let (mut sys, _, seed) = seeded_source(args)?;
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut scene = Scene::new(seed, args.panels);
+    let opts = render_options(args)?;
+    let start = args.start_tick.unwrap_or(0);
+    let repeat = args.repeat.or(sys.frames());
+    let tick = Cell::new(start);
+    let render = |metrics: &Metrics| {
+        if let Some(state) = rng_state(args, seed, tick.get()) {
+            rng = state.rng();
+        }
+        draw(metrics, &mut rng, &mut scene, &RenderOptions { flow: flow_for(args, seed, tick.get()), ..opts.clone() })
+    };
+    let mut written = Ok(());
+    let interval = Duration::from_millis(args.interval);
+    run_frames(sys.as_mut(), interval, render, |metrics, canvas| {
+        decorate(canvas, args);
+        let mut payload = capture(args, metrics, canvas, &opts);
+        payload.tick = repeat.or(args.start_tick).map(|_| tick.get());
+        payload.rng = rng_state(args, seed, tick.get());
+        written = args
+            .html
+            .as_deref()
+            .map_or(Ok(()), |path| write_atomic(path, render_html(canvas, &args.style).as_bytes()))
+            .and_then(|()| emit(&payload));
+        tick.set(tick.get() + 1);
+        if written.is_err() || tick.get() - start >= repeat.unwrap_or(1) {
+            ControlFlow::Break(())
+        } else {
+            ControlFlow::Continue(())
+        }
+    });
+    written
+}
+
+pub fn run_seamless(args: &Args, frames: u64) -> io::Result<()> {
+    let (_, metrics, seed) = seeded_source(args)?;
+    let mut opts = render_options(args)?;
+    for tick in 0..frames {
+        opts.loop_phase = Some(tick as f32 / frames as f32);
+        let mut scene = Scene::new(seed, args.panels);
+        let mut canvas = draw(&metrics, &mut StdRng::seed_from_u64(seed), &mut scene, &opts);
+        decorate(&mut canvas, args);
+        let payload = Snapshot { tick: Some(tick), ..capture(args, &metrics, &canvas, &opts) };
+        print_snapshot(&payload, args.compact, args.json_case)?;
+    }
+    Ok(())
+}
+
+pub fn run_apng(args: &Args, path: &Path, frames: u64) -> io::Result<()> {
+    let (_, metrics, seed) = seeded_source(args)?;
+    let mut opts = render_options(args)?;
+    let fields: Vec<Vec<Vec<f32>>> = (0..frames)
+        .map(|tick| {
+            opts.loop_phase = Some(tick as f32 / frames as f32);
+            draw(&metrics, &mut StdRng::seed_from_u64(seed), &mut Scene::new(seed, args.panels), &opts).intensity
+        })
+        .collect();
+    let colors: Vec<_> = palette(&opts.style).into_iter().map(ansi_rgb).collect();
+    let fields = interpolate_fields(&fields, args.interpolate_frames);
+    let delay = (args.interval / args.interpolate_frames as u64).max(1);
+    write_atomic(path, &encode_apng(&fields, &colors, SCALE_X, SCALE_Y, delay)?)
+}
+
+pub fn run_transition(args: &Args, transition: &Transition) -> io::Result<()> {
+    let (_, metrics, seed) = seeded_source(args)?;
+    let base = render_options(args)?;
+    let styled = |style: &str| RenderOptions { style: style.to_string(), ..base.clone() };
+    let (from_opts, to_opts) = (styled(&transition.from), styled(&transition.to));
+    let (mut from_rng, mut to_rng) = (StdRng::seed_from_u64(seed), StdRng::seed_from_u64(seed));
+    let (mut from_scene, mut to_scene) = (Scene::new(seed, args.panels), Scene::new(seed, args.panels));
+    for tick in 0..transition.frames {
+        let t = transition_t(tick, transition.frames);
+        let from = draw(&metrics, &mut from_rng, &mut from_scene, &from_opts);
+        let to = draw(&metrics, &mut to_rng, &mut to_scene, &to_opts);
+        let opts = if t < 0.5 { &from_opts } else { &to_opts };
+        let mut canvas = blend_canvases(&from, &to, t, effective_ramp(opts, &from.inputs));
+        decorate(&mut canvas, args);
+        let colors = crossfade(&palette(&transition.from), &palette(&transition.to), t);
+        let payload = Snapshot {
+            tick: Some(tick),
+            palette: Some(colors.into_iter().map(hex).collect()),
+            history: None,
+            ..capture(args, &metrics, &canvas, opts)
+        };
+        print_snapshot(&payload, args.compact, args.json_case)?;
+    }
+    Ok(())
+}
+
+pub fn run_stream(args: &Args, path: &Path) -> io::Result<()> {
+    let (_, metrics, seed) = seeded_source(args)?;
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut out = BufWriter::new(File::create(path)?);
+    stream_frame(&metrics, &mut rng, &render_options(args)?, &mut out)
+}
+
+pub fn run_playback(path: &Path, interval: Option<u64>, style: &str, text_interval: u64) -> io::Result<()> {
+    let frames = load_playback(BufReader::new(File::open(path)?), style, text_interval)?;
+    let terminal = TerminalGuard::enter(false, false)?;
+    let display = DisplayOptions {
+        smooth: false,
+        sixel: false,
+        image: None,
+        mono: false,
+        no_clear: false,
+        depth: ColorDepth::default(),
+        palette_bias: false,
+        palette: None,
+        banded: false,
+        min_contrast: None,
+        origin: (0, 0),
+        screen: terminal.screen,
+    };
+    let mut state = LiveState::default();
+    for frame in &frames {
+        display_frame(&mut stdout(), &frame.canvas, &frame.style, &display)?;
+        if event::poll(interval.map_or(frame.delay, Duration::from_millis))? {
+            if let Event::Key(key) = event::read()? {
+                state.handle_key(key);
+            }
+        }
+        if state.quit {
+            break;
+        }
+    }
+    Ok(())
+}
+
+pub fn run_seed_grid(args: &Args, count: usize, thumb_width: u16, thumb_height: u16, png: Option<&Path>) -> io::Result<()> {
+    let mut sys = metrics_source(args)?;
+    let metrics = gather_metrics(sys.as_mut());
+    let seeds = seed_sequence(resolve_seed(args)?.unwrap_or(0), count);
+    let grid = contact_grid(count);
+    let mut opts = render_options(args)?;
+    opts.width = thumb_width * grid.cols;
+    opts.height = thumb_height * grid.rows;
+    opts.overlay = false;
+    let mut panels = seeded_panels(&seeds);
+    let canvas = render_panels(&metrics, &mut panels, &opts, grid);
+    if let Some(path) = png {
+        let colors: Vec<_> = palette(&opts.style).into_iter().map(ansi_rgb).collect();
+        return write_atomic(path, &encode_png(&contact_sheet(&canvas, &panels, &opts, grid, &colors, SCALE_X, SCALE_Y)));
+    }
+    for row in &canvas.rows {
+        println!("{row}");
+    }
+    Ok(())
+}
+
+pub fn run_ascii_art(args: &Args, image: &Path, noise: f32) -> io::Result<()> {
+    let (width, height) = capped_size(args, args.width, args.height);
+    let substrate = Substrate::load(image, width, height).map_err(io::Error::other)?;
+    let opts = render_options(args)?;
+    let ramp = effective_ramp(&opts, &NormalizedMetrics::default());
+    let key = resolve_seed(args)?.unwrap_or(0);
+    let mut canvas = ascii_canvas(&substrate, ramp, noise, key, width, height);
+    decorate(&mut canvas, args);
+    for row in &canvas.rows {
+        println!("{row}");
+    }
+    Ok(())
+}
+
+pub fn run_calibrate(args: &Args) -> io::Result<()> {
+    let depth = if args.mono { ColorDepth::Mono } else { args.color.resolve(detect_depth()) };
+    let mut out = stdout();
+    for line in calibration_lines(args.width as usize, effective_ramp(&render_options(args)?, &NormalizedMetrics::default())) {
+        for swatch in line {
+            match swatch.background.filter(|_| depth != ColorDepth::Mono) {
+                Some(color) => queue!(out, SetBackgroundColor(at_depth(color, depth)), Print(&swatch.text), ResetColor)?,
+                None => queue!(out, Print(&swatch.text))?,
+            }
+        }
+        queue!(out, Print("\n"))?;
+    }
+    out.flush()
+}
+
+pub fn run_compare_seeds(args: &Args, count: usize, by: SeedScore, top: usize) -> io::Result<()> {
+    let mut sys = metrics_source(args)?;
+    let metrics = gather_metrics(sys.as_mut());
+    let opts = render_options(args)?;
+    let scored = seed_sequence(resolve_seed(args)?.unwrap_or(0), count)
+        .into_iter()
+        .map(|seed| {
+            let canvas = draw(&metrics, &mut StdRng::seed_from_u64(seed), &mut Scene::new(seed, args.panels), &opts);
+            (seed, score_frame(&canvas.rows, &canvas.intensity, by))
+        })
+        .collect();
+    for (seed, score) in rank_seeds(scored).into_iter().take(top) {
+        println!("{seed}\t{score:.4}");
+    }
+    Ok(())
+}
+
+pub fn run_bench(args: &Args, frames: u32, all_styles: bool) -> io::Result<()> {
+    let seed = resolve_seed(args)?.unwrap_or(BENCH_SEED);
+    let styles = if all_styles { style_names() } else { vec![args.style.as_str()] };
+    let base = render_options(args)?;
+    let results = bench_styles(args, &base, seed, frames, styles);
+    if args.json {
+        let summary = BenchSummary { width: base.width, height: base.height, seed, results: results.iter().map(BenchResult::report).collect() };
+        println!("{}", to_json(&summary, args.compact, args.json_case)?);
+    } else {
+        print!("{}", bench_table(&results));
+    }
+    Ok(())
+}
+
+fn bench_styles(args: &Args, base: &RenderOptions, seed: u64, frames: u32, styles: Vec<&str>) -> Vec<BenchResult> {
+    let metrics = demoscene_metrics();
+    styles
+        .into_iter()
+        .map(|style| {
+            let opts = RenderOptions { style: style.to_string(), ..base.clone() };
+            let mut rng = StdRng::seed_from_u64(seed);
+            let mut scene = Scene::new(seed, args.panels);
+            let mut frame_us = Vec::with_capacity(frames as usize);
+            let start = Instant::now();
+            for _ in 0..frames {
+                let frame_start = Instant::now();
+                std::hint::black_box(draw(&metrics, &mut rng, &mut scene, &opts));
+                frame_us.push(frame_start.elapsed().as_micros() as u64);
+            }
+            BenchResult { style: style.to_string(), frames, elapsed: start.elapsed(), frame_us }
+        })
+        .collect()
+}
+
+pub fn run_metrics(args: &Args, oneline: bool, format: Option<&str>, prometheus: bool, watch: bool) -> io::Result<()> {
+    let mut sys = metrics_source(args)?;
+    loop {
+        let metrics = gather_metrics(sys.as_mut());
+        if oneline {
+            println!("{}", render_template(format.unwrap_or(DEFAULT_ONELINE), &metrics));
+        } else if prometheus {
+            print!("{}", prometheus_text(&metrics));
+        } else {
+            println!("{}", to_json(&metrics, args.compact, args.json_case)?);
+        }
+        if !watch || args.once {
+            return Ok(());
+        }
+        std::thread::sleep(Duration::from_millis(args.interval));
+    }
+}
+
+pub fn run_gauge(args: &Args, metric: GaugeMetric) -> io::Result<()> {
+    let mut sys = metrics_source(args)?;
+    if args.once {
+        let value = metric.value(&gather_metrics(sys.as_mut()));
+        let width = terminal::size().map_or(args.width, |(cols, _)| cols);
+        return execute!(stdout(), SetForegroundColor(gauge_color(value)), Print(gauge_line(metric, value, width)), ResetColor, Print("\n"));
+    }
+    let _terminal = TerminalGuard::enter(false, false)?;
+    let mut state = LiveState::default();
+    loop {
+        let (width, height) = terminal::size()?;
+        display_gauge(metric, metric.value(&gather_metrics(sys.as_mut())), width, height, args.legend)?;
+        if event::poll(Duration::from_millis(args.interval))? {
+            if let Event::Key(key) = event::read()? {
+                state.handle_key(key);
+            }
+        }
+        if state.quit {
+            return Ok(());
+        }
+    }
+}
+
+pub fn run_validate(path: &Path) -> io::Result<()> {
+    let snapshot: Snapshot = serde_json::from_reader(BufReader::new(File::open(path)?)).map_err(io::Error::other)?;
+    validate_snapshot(&snapshot).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, format!("invalid snapshot: {err}")))?;
+    println!("OK");
+    Ok(())
+}
+
+pub fn run_summary(args: &Args, limits: &Thresholds) -> io::Result<()> {
+    let mut sys = metrics_source(args)?;
+    println!("{}", health_summary(&gather_metrics(sys.as_mut()), limits));
+    Ok(())
+}
+
+pub fn run_config(args: &Args) -> io::Result<()> {
+    println!("{}", to_json(args, args.compact, args.json_case)?);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+    use procgen_art::{metrics::MockSource, render::render_frame, snapshot::JsonCase};
+    use std::{env, fs, process};
+
+    #[test]
+    fn repeat_writes_that_many_snapshots_with_rising_ticks() {
+        let args = Args::parse_from(["procgen-art", "--simulate", "--repeat", "3", "--interval", "1", "--start-tick", "5"]);
+        let mut ticks = Vec::new();
+        write_snapshots(&args, |payload| {
+            ticks.push(payload.tick);
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(ticks, vec![Some(5), Some(6), Some(7)]);
+    }
+
+    #[test]
+    fn a_direct_tick_matches_iterating_up_to_it() {
+        let frames = |argv: &[&str]| {
+            let args = Args::parse_from([&["procgen-art", "--demoscene", "--seed", "3", "--width", "16", "--height", "4", "--interval", "1"], argv].concat());
+            let mut frames = Vec::new();
+            write_snapshots(&args, |payload| {
+                frames.push((payload.tick, serde_json::to_string(&payload.frame).unwrap()));
+                Ok(())
+            })
+            .unwrap();
+            frames
+        };
+        let direct = frames(&["--tick", "100"]);
+        let iterated = frames(&["--rng-state", "--repeat", "101"]);
+        assert_eq!(direct.len(), 1);
+        assert_eq!(iterated.last(), direct.last());
+        assert_ne!(iterated[99], direct[0]);
+    }
+
+    #[test]
+    fn bench_all_styles_reports_one_row_per_style() {
+        let args = Args::parse_from(["procgen-art", "--width", "8", "--height", "3"]);
+        let results = bench_styles(&args, &render_options(&args).unwrap(), BENCH_SEED, 2, style_names());
+        let styles: Vec<&str> = results.iter().map(|result| result.style.as_str()).collect();
+        assert_eq!(styles, style_names());
+        assert!(results.iter().all(|result| result.frames == 2 && result.frame_us.len() == 2));
+        assert_eq!(bench_table(&results).lines().count(), style_names().len() + 1);
+    }
+
+    #[test]
+    fn demoscene_runs_with_one_seed_repeat_exactly() {
+        let run = |seed: &str| {
+            let args = Args::parse_from(["procgen-art", "--demoscene", "--seed", seed, "--width", "16", "--height", "4", "--interval", "1", "--start-tick", "40", "--repeat", "6"]);
+            let mut frames = Vec::new();
+            write_snapshots(&args, |payload| {
+                frames.push(serde_json::to_string(&payload.frame).unwrap());
+                Ok(())
+            })
+            .unwrap();
+            frames
+        };
+        let first = run("12");
+        assert_eq!(first.len(), 6);
+        assert_eq!(first, run("12"));
+        assert_ne!(first, run("13"));
+    }
+
+    #[test]
+    fn apng_export_is_byte_identical_for_a_fixed_seed() {
+        let args = Args::parse_from(["procgen-art", "--simulate", "--seed", "5", "--width", "8", "--height", "3"]);
+        let export = |name: &str| {
+            let path = env::temp_dir().join(format!("procgen-apng-{name}-{}.png", process::id()));
+            run_apng(&args, &path, 4).unwrap();
+            let bytes = fs::read(&path).unwrap();
+            fs::remove_file(&path).unwrap();
+            bytes
+        };
+        let first = export("a");
+        assert!(first.starts_with(b"\x89PNG\r\n\x1a\n"));
+        assert!(first.windows(4).any(|chunk| chunk == b"acTL"));
+        assert_eq!(first, export("b"));
+    }
+
+    #[test]
+    fn validate_reports_a_bad_snapshot_as_an_error() {
+        let args = Args::parse_from(["procgen-art", "--simulate", "--seed", "2", "--width", "6", "--height", "2"]);
+        let mut payloads = Vec::new();
+        write_snapshots(&args, |payload| {
+            payloads.push(to_json(payload, true, JsonCase::Snake)?);
+            Ok(())
+        })
+        .unwrap();
+        let path = env::temp_dir().join(format!("procgen-validate-{}.json", process::id()));
+        fs::write(&path, &payloads[0]).unwrap();
+        assert!(run_validate(&path).is_ok());
+        fs::write(&path, payloads[0].replace("\"height\":2", "\"height\":3")).unwrap();
+        let err = run_validate(&path).err();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(err.map(|err| err.kind()), Some(io::ErrorKind::InvalidData));
+    }
+
+    #[test]
+    fn the_library_alone_renders_what_the_binary_prints() {
+        let args = Args::parse_from(["procgen-art", "--simulate", "--seed", "5", "--width", "24", "--height", "5", "--no-flow"]);
+        let mut printed = Vec::new();
+        write_snapshots(&args, |payload| {
+            printed.push(payload.frame.to_canvas().rows);
+            Ok(())
+        })
+        .unwrap();
+        let mut source = MockSource::new(5);
+        // The binary spends its first sample on choosing the seed and renders from the next one.
+        gather_metrics(&mut source);
+        let metrics = gather_metrics(&mut source);
+        let canvas = render_frame(&metrics, &mut StdRng::seed_from_u64(5), &RenderOptions::new(24, 5, "plasma"));
+        assert_eq!(printed, [canvas.rows]);
+    }
+}
@@ -0,0 +1,115 @@
+use crate::metrics::{Metrics, MetricsSource};
+use std::{fs, io, path::Path};
+
+pub fn parse_metrics_csv(text: &str) -> Result<Vec<Metrics>, String> {
+    let mut lines = text.lines().filter(|line| !line.trim().is_empty());
+    let header: Vec<&str> = lines.next().ok_or("CSV has no header row")?.split(',').map(str::trim).collect();
+    lines
+        .enumerate()
+        .map(|(idx, line)| {
+            let mut metrics = Metrics {
+                cpu_usage: 0.0,
+                cpu_cores: Vec::new(),
+                load_avg: 0.0,
+                total_memory: 0,
+                used_memory: 0,
+                disk_usage: Vec::new(),
+                network_rx: 0,
+                network_tx: 0,
+                interfaces: Vec::new(),
+                entropy: 0,
+                temperature: None,
+            };
+            for (column, value) in header.iter().zip(line.split(',').map(str::trim)) {
+                let invalid = || format!("row {}: invalid {column} '{value}'", idx + 1);
+                match *column {
+                    "cpu_usage" => metrics.cpu_usage = value.parse().map_err(|_| invalid())?,
+                    "load_avg" => metrics.load_avg = value.parse().map_err(|_| invalid())?,
+                    "total_memory" => metrics.total_memory = value.parse().map_err(|_| invalid())?,
+                    "used_memory" => metrics.used_memory = value.parse().map_err(|_| invalid())?,
+                    "network_rx" => metrics.network_rx = value.parse().map_err(|_| invalid())?,
+                    "network_tx" => metrics.network_tx = value.parse().map_err(|_| invalid())?,
+                    "entropy" => metrics.entropy = value.parse().map_err(|_| invalid())?,
+                    "temperature" if !value.is_empty() => {
+                        metrics.temperature = Some(value.parse().map_err(|_| invalid())?)
+                    }
+                    _ => {}
+                }
+            }
+            check_range(&metrics).map_err(|err| format!("row {}: {err}", idx + 1))?;
+            if !header.contains(&"entropy") {
+                metrics = metrics.with_entropy();
+            }
+            Ok(metrics)
+        })
+        .collect()
+}
+
+fn check_range(metrics: &Metrics) -> Result<(), String> {
+    if !(0.0..=100.0).contains(&metrics.cpu_usage) {
+        return Err(format!("cpu_usage {} is outside 0-100", metrics.cpu_usage));
+    }
+    if !(metrics.load_avg.is_finite() && metrics.load_avg >= 0.0) {
+        return Err(format!("load_avg {} must be a non-negative number", metrics.load_avg));
+    }
+    if metrics.used_memory > metrics.total_memory {
+        return Err(format!("used_memory {} exceeds total_memory {}", metrics.used_memory, metrics.total_memory));
+    }
+    if metrics.temperature.is_some_and(|celsius| !celsius.is_finite()) {
+        return Err("temperature must be a finite number".to_string());
+    }
+    Ok(())
+}
+
+pub struct CsvSource {
+    rows: Vec<Metrics>,
+    next: usize,
+}
+
+impl CsvSource {
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let rows = parse_metrics_csv(&fs::read_to_string(path)?).map_err(io::Error::other)?;
+        if rows.is_empty() {
+            return Err(io::Error::other(format!("{} has no metric rows", path.display())));
+        }
+        Ok(CsvSource { rows, next: 0 })
+    }
+}
+
+impl MetricsSource for CsvSource {
+    fn sample(&mut self) -> Metrics {
+        let metrics = self.rows[self.next.min(self.rows.len() - 1)].clone();
+        self.next += 1;
+        metrics
+    }
+
+    fn frames(&self) -> Option<u64> {
+        Some(self.rows.len() as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn two_rows_become_two_frames() {
+        let text = "cpu_usage,total_memory,used_memory,network_rx\n10,100,25,5\n90,100,75,7\n";
+        let mut source = CsvSource { rows: parse_metrics_csv(text).unwrap(), next: 0 };
+        assert_eq!(source.frames(), Some(2));
+        let (first, second) = (source.sample(), source.sample());
+        assert_eq!((first.cpu_usage, first.used_memory, first.network_rx), (10.0, 25, 5));
+        assert_eq!((second.cpu_usage, second.used_memory, second.network_rx), (90.0, 75, 7));
+    }
+
+    #[test]
+    fn rejects_out_of_range_rows() {
+        for row in ["150,100,10", "-1,100,10", "NaN,100,10", "50,100,200"] {
+            let Err(err) = parse_metrics_csv(&format!("cpu_usage,total_memory,used_memory\n50,100,10\n{row}\n")) else {
+                panic!("{row} should be rejected");
+            };
+            assert!(err.starts_with("row 2: "), "{row}: {err}");
+        }
+        assert!(parse_metrics_csv("load_avg\n-0.5\n").is_err());
+    }
+}
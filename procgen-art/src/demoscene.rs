@@ -0,0 +1,33 @@
+use crate::metrics::{DiskMetrics, Metrics, MetricsSource};
+
+pub const DEMOSCENE_LOOP: u64 = 240;
+pub const DEMOSCENE_CYCLE_TICKS: u64 = 8;
+
+pub fn demoscene_metrics() -> Metrics {
+    Metrics {
+        cpu_usage: 60.0,
+        cpu_cores: vec![60.0; 4],
+        load_avg: 1.0,
+        total_memory: 16 << 30,
+        used_memory: 8 << 30,
+        disk_usage: vec![DiskMetrics { name: "demo0".to_string(), total_space: 1 << 30, available_space: 1 << 29 }],
+        network_rx: 120_000,
+        network_tx: 30_000,
+        interfaces: Vec::new(),
+        entropy: 0,
+        temperature: None,
+    }
+    .with_entropy()
+}
+
+pub fn demoscene_phase(tick: u64) -> f32 {
+    (tick % DEMOSCENE_LOOP) as f32 / DEMOSCENE_LOOP as f32
+}
+
+pub struct ConstantSource(pub Metrics);
+
+impl MetricsSource for ConstantSource {
+    fn sample(&mut self) -> Metrics {
+        self.0.clone()
+    }
+}
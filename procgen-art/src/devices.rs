@@ -0,0 +1,87 @@
+use crate::metrics::{Metrics, MetricsSource};
+
+pub const DEFAULT_DEVICE_WEIGHT: f64 = 1.0;
+
+pub fn parse_device_weight(value: &str) -> Result<(String, f64), String> {
+    let (name, weight) = value.split_once('=').ok_or_else(|| format!("expected NAME=WEIGHT, got '{value}'"))?;
+    let weight: f64 = weight.parse().map_err(|_| format!("invalid weight '{weight}'"))?;
+    if name.is_empty() || !weight.is_finite() || weight < 0.0 {
+        return Err(format!("expected a device name and a non-negative weight, got '{value}'"));
+    }
+    Ok((name.to_string(), weight))
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct DeviceWeights {
+    pub disks: Vec<(String, f64)>,
+    pub interfaces: Vec<(String, f64)>,
+}
+
+fn weight_of(weights: &[(String, f64)], name: &str) -> f64 {
+    weights.iter().rev().find(|(device, _)| device == name).map_or(DEFAULT_DEVICE_WEIGHT, |(_, weight)| *weight)
+}
+
+impl DeviceWeights {
+    pub fn is_empty(&self) -> bool {
+        self.disks.is_empty() && self.interfaces.is_empty()
+    }
+
+    pub fn apply(&self, metrics: &mut Metrics) {
+        for disk in &mut metrics.disk_usage {
+            let weight = weight_of(&self.disks, &disk.name);
+            disk.total_space = (disk.total_space as f64 * weight) as u64;
+            disk.available_space = (disk.available_space as f64 * weight) as u64;
+        }
+        if metrics.interfaces.is_empty() {
+            return;
+        }
+        let (mut received, mut transmitted) = (0.0, 0.0);
+        for interface in &metrics.interfaces {
+            let weight = weight_of(&self.interfaces, &interface.name);
+            received += interface.received as f64 * weight;
+            transmitted += interface.transmitted as f64 * weight;
+        }
+        (metrics.network_rx, metrics.network_tx) = (received as u64, transmitted as u64);
+    }
+}
+
+pub struct WeightedSource {
+    inner: Box<dyn MetricsSource + Send>,
+    weights: DeviceWeights,
+}
+
+impl WeightedSource {
+    pub fn new(inner: Box<dyn MetricsSource + Send>, weights: DeviceWeights) -> Self {
+        WeightedSource { inner, weights }
+    }
+}
+
+impl MetricsSource for WeightedSource {
+    fn sample(&mut self) -> Metrics {
+        let mut metrics = self.inner.sample();
+        self.weights.apply(&mut metrics);
+        metrics
+    }
+
+    fn frames(&self) -> Option<u64> {
+        self.inner.frames()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::{InterfaceMetrics, MockSource};
+
+    #[test]
+    fn zero_weight_drops_an_interface_from_the_network_signal() {
+        let mut metrics = MockSource::new(1).sample();
+        metrics.interfaces = vec![
+            InterfaceMetrics { name: "eth0".to_string(), received: 1_000, transmitted: 400 },
+            InterfaceMetrics { name: "docker0".to_string(), received: 9_000, transmitted: 6_000 },
+        ];
+        let weights = DeviceWeights { interfaces: vec![("docker0".to_string(), 0.0)], ..DeviceWeights::default() };
+        weights.apply(&mut metrics);
+        assert_eq!((metrics.network_rx, metrics.network_tx), (1_000, 400));
+    }
+}
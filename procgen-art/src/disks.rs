@@ -0,0 +1,86 @@
+use crate::{
+    metrics::{DiskMetrics, NormalizedMetrics},
+    render::{stamp, Canvas},
+};
+
+pub const DISK_STYLE: &str = "disks";
+pub const FULLNESS_BANDS: usize = 3;
+
+pub fn fullness(disk: &DiskMetrics) -> Option<f32> {
+    if disk.total_space == 0 {
+        return None;
+    }
+    Some((1.0 - disk.available_space as f32 / disk.total_space as f32).clamp(0.0, 1.0))
+}
+
+pub fn fullness_band(ratio: f32) -> usize {
+    ((ratio.clamp(0.0, 1.0) * FULLNESS_BANDS as f32) as usize).min(FULLNESS_BANDS - 1)
+}
+
+pub fn disk_canvas(disks: &[DiskMetrics], ramp: &str, inputs: NormalizedMetrics, width: u16, height: u16) -> Canvas {
+    let (width, height) = (width as usize, height as usize);
+    let bands = disks.len().max(1);
+    let empty = ramp.chars().next().unwrap_or(' ');
+    let full = ramp.chars().last().unwrap_or('#');
+    let levels: Vec<Option<f32>> = disks.iter().map(fullness).collect();
+    let mut rows = Vec::with_capacity(height);
+    let mut intensity = Vec::with_capacity(height);
+    for y in 0..height {
+        let mut row = String::with_capacity(width);
+        let mut values = Vec::with_capacity(width);
+        for x in 0..width {
+            let band = x * bands / width;
+            let edge = width / bands >= 2 && (x + 1) * bands / width != band;
+            let level = levels.get(band).copied().flatten();
+            let filled = !edge && level.is_some_and(|ratio| (ratio * height as f32).round() as usize >= height - y);
+            row.push(if filled { full } else { empty });
+            values.push(match level {
+                Some(ratio) if !edge => fullness_band(ratio) as f32 / (FULLNESS_BANDS - 1) as f32,
+                _ => 0.0,
+            });
+        }
+        rows.push(row);
+        intensity.push(values);
+    }
+    if let Some(top) = rows.first_mut() {
+        for (band, disk) in disks.iter().enumerate() {
+            let label = match levels[band] {
+                Some(ratio) => format!("{} {:.0}%", disk.name, ratio * 100.0),
+                None => format!("{} n/a", disk.name),
+            };
+            let (start, end) = (band * width / bands, (band + 1) * width / bands);
+            let label: String = label.chars().take(end.saturating_sub(start + 1)).collect();
+            stamp(top, start, &label);
+        }
+    }
+    Canvas {
+        rows,
+        intensity,
+        overlay_row: usize::MAX,
+        alerts: Vec::new(),
+        inputs,
+        sanitized: 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn disk(total_space: u64, available_space: u64) -> DiskMetrics {
+        DiskMetrics { name: "sda".to_string(), total_space, available_space }
+    }
+
+    #[test]
+    fn fullness_picks_a_band_and_skips_empty_disks() {
+        assert_eq!(fullness(&disk(100, 75)), Some(0.25));
+        assert_eq!(fullness(&disk(100, 0)), Some(1.0));
+        assert_eq!(fullness(&disk(100, 150)), Some(0.0));
+        assert_eq!(fullness(&disk(0, 0)), None);
+        let bands: Vec<usize> = [0.0, 0.3, 0.34, 0.7, 1.0].iter().map(|&ratio| fullness_band(ratio)).collect();
+        assert_eq!(bands, [0, 0, 1, 2, 2]);
+        let canvas = disk_canvas(&[disk(0, 0)], " #", NormalizedMetrics::default(), 8, 3);
+        assert!(canvas.rows[0].starts_with("sda n/a"));
+        assert!(canvas.intensity.iter().flatten().all(|&value| value == 0.0));
+    }
+}
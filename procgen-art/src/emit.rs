@@ -0,0 +1,135 @@
+use crate::metrics::Metrics;
+use serde::Serialize;
+use std::{
+    io::{self, Write},
+    net::{SocketAddr, TcpStream, ToSocketAddrs, UdpSocket},
+    time::{Duration, Instant},
+};
+
+pub const CONNECT_TIMEOUT: Duration = Duration::from_millis(200);
+pub const MIN_BACKOFF: Duration = Duration::from_millis(250);
+pub const MAX_BACKOFF: Duration = Duration::from_secs(8);
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum EmitTarget {
+    Udp(String),
+    Tcp(String),
+}
+
+pub fn parse_emit(value: &str) -> Result<EmitTarget, String> {
+    let (target, addr): (fn(String) -> EmitTarget, &str) = if let Some(addr) = value.strip_prefix("udp://") {
+        (EmitTarget::Udp, addr)
+    } else if let Some(addr) = value.strip_prefix("tcp://") {
+        (EmitTarget::Tcp, addr)
+    } else {
+        return Err(format!("expected udp://host:port or tcp://host:port, got '{value}'"));
+    };
+    match addr.rsplit_once(':') {
+        Some((host, port)) if !host.is_empty() && port.parse::<u16>().is_ok() => Ok(target(addr.to_string())),
+        _ => Err(format!("invalid address '{addr}', expected host:port")),
+    }
+}
+
+pub fn next_backoff(current: Duration) -> Duration {
+    (current * 2).clamp(MIN_BACKOFF, MAX_BACKOFF)
+}
+
+enum Link {
+    Udp(UdpSocket, SocketAddr),
+    Tcp(TcpStream),
+}
+
+pub struct Emitter {
+    target: EmitTarget,
+    link: Option<Link>,
+    backoff: Duration,
+    retry_at: Option<Instant>,
+}
+
+impl Emitter {
+    pub fn new(target: EmitTarget) -> Self {
+        Emitter { target, link: None, backoff: Duration::ZERO, retry_at: None }
+    }
+
+    fn connect(&self) -> io::Result<Link> {
+        let addr = match &self.target {
+            EmitTarget::Udp(addr) | EmitTarget::Tcp(addr) => addr.to_socket_addrs()?.next(),
+        }
+        .ok_or_else(|| io::Error::other("address did not resolve"))?;
+        Ok(match self.target {
+            EmitTarget::Udp(_) => {
+                let local = if addr.is_ipv4() { "0.0.0.0:0" } else { "[::]:0" };
+                Link::Udp(UdpSocket::bind(local)?, addr)
+            }
+            EmitTarget::Tcp(_) => {
+                let stream = TcpStream::connect_timeout(&addr, CONNECT_TIMEOUT)?;
+                stream.set_write_timeout(Some(CONNECT_TIMEOUT))?;
+                Link::Tcp(stream)
+            }
+        })
+    }
+
+    fn try_send(&mut self, payload: &[u8]) -> io::Result<()> {
+        if self.link.is_none() {
+            self.link = Some(self.connect()?);
+        }
+        match self.link.as_mut() {
+            Some(Link::Udp(socket, addr)) => socket.send_to(payload, *addr).map(drop),
+            Some(Link::Tcp(stream)) => stream.write_all(payload),
+            None => Ok(()),
+        }
+    }
+
+    pub fn send(&mut self, metrics: &Metrics, now: Instant) {
+        if self.retry_at.is_some_and(|at| now < at) {
+            return;
+        }
+        let Ok(mut payload) = serde_json::to_vec(metrics) else { return };
+        payload.push(b'\n');
+        match self.try_send(&payload) {
+            Ok(()) => {
+                self.backoff = Duration::ZERO;
+                self.retry_at = None;
+            }
+            Err(_) => {
+                self.link = None;
+                self.backoff = next_backoff(self.backoff);
+                self.retry_at = Some(now + self.backoff);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::{MetricsSource, MockSource};
+    use std::{
+        io::{BufRead, BufReader},
+        net::TcpListener,
+    };
+
+    #[test]
+    fn udp_and_tcp_emitters_deliver_the_metrics_json() {
+        let metrics = MockSource::new(8).sample();
+        let expected = format!("{}\n", serde_json::to_string(&metrics).unwrap());
+
+        let receiver = UdpSocket::bind("127.0.0.1:0").unwrap();
+        receiver.set_read_timeout(Some(Duration::from_secs(2))).unwrap();
+        let mut emitter = Emitter::new(EmitTarget::Udp(receiver.local_addr().unwrap().to_string()));
+        emitter.send(&metrics, Instant::now());
+        let mut buf = [0u8; 4096];
+        let len = receiver.recv(&mut buf).unwrap();
+        assert_eq!(String::from_utf8_lossy(&buf[..len]), expected);
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut emitter = Emitter::new(EmitTarget::Tcp(listener.local_addr().unwrap().to_string()));
+        emitter.send(&metrics, Instant::now());
+        let (stream, _) = listener.accept().unwrap();
+        stream.set_read_timeout(Some(Duration::from_secs(2))).unwrap();
+        let mut line = String::new();
+        BufReader::new(stream).read_line(&mut line).unwrap();
+        assert_eq!(line, expected);
+    }
+}
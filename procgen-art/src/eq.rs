@@ -0,0 +1,75 @@
+use crate::{metrics::NormalizedMetrics, render::Canvas};
+
+pub const EQ_STYLE: &str = "eq";
+pub const EQ_DECAY: f32 = 0.08;
+
+pub fn decay_level(previous: f32, sample: f32) -> f32 {
+    sample.max(previous - EQ_DECAY).clamp(0.0, 1.0)
+}
+
+#[derive(Default)]
+pub struct Equalizer {
+    levels: Vec<f32>,
+}
+
+impl Equalizer {
+    pub fn update(&mut self, cores: &[f32]) {
+        self.levels.resize(cores.len(), 0.0);
+        for (level, usage) in self.levels.iter_mut().zip(cores) {
+            *level = decay_level(*level, usage / 100.0);
+        }
+    }
+
+    pub fn canvas(&self, ramp: &str, inputs: NormalizedMetrics, width: u16, height: u16) -> Canvas {
+        let (width, height) = (width as usize, height as usize);
+        let bars = self.levels.len().max(1);
+        let gap = width / bars >= 2;
+        let empty = ramp.chars().next().unwrap_or(' ');
+        let full = ramp.chars().last().unwrap_or('#');
+        let mut rows = Vec::with_capacity(height);
+        let mut intensity = Vec::with_capacity(height);
+        for y in 0..height {
+            let cell_height = (height - y) as f32 / height as f32;
+            let mut row = String::with_capacity(width);
+            let mut values = Vec::with_capacity(width);
+            for x in 0..width {
+                let bar = x * bars / width;
+                let edge = gap && (x + 1) * bars / width != bar;
+                let level = self.levels.get(bar).copied().unwrap_or(0.0);
+                let filled = !edge && (level * height as f32).round() as usize >= height - y;
+                row.push(if filled { full } else { empty });
+                values.push(if filled { cell_height } else { 0.0 });
+            }
+            rows.push(row);
+            intensity.push(values);
+        }
+        Canvas {
+            rows,
+            intensity,
+            overlay_row: usize::MAX,
+            alerts: Vec::new(),
+            inputs,
+            sanitized: 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bars_fall_gradually_as_load_drops() {
+        let mut level = decay_level(0.0, 0.9);
+        assert_eq!(level, 0.9);
+        let mut heights = Vec::new();
+        for sample in [0.8, 0.5, 0.1, 0.0] {
+            level = decay_level(level, sample);
+            heights.push(level);
+        }
+        let expected = [0.82, 0.74, 0.66, 0.58];
+        assert!(heights.iter().zip(expected).all(|(height, want)| (height - want).abs() < 1e-6));
+        assert_eq!(decay_level(0.05, 0.0), 0.0);
+        assert_eq!(decay_level(0.2, 0.7), 0.7);
+    }
+}
@@ -0,0 +1,95 @@
+use std::{
+    fs::{File, OpenOptions},
+    io::{self, ErrorKind, Write},
+    path::PathBuf,
+    sync::mpsc::{self, Receiver},
+    thread,
+};
+
+#[cfg(unix)]
+pub fn open_fd(fd: u32) -> io::Result<File> {
+    OpenOptions::new().append(true).open(format!("/dev/fd/{fd}"))
+}
+
+#[cfg(not(unix))]
+pub fn open_fd(fd: u32) -> io::Result<File> {
+    Err(io::Error::new(ErrorKind::Unsupported, format!("cannot write to file descriptor {fd} on this platform")))
+}
+
+pub struct FifoSink {
+    path: PathBuf,
+    pipe: Option<File>,
+    opening: Option<Receiver<io::Result<File>>>,
+}
+
+impl FifoSink {
+    pub fn new(path: PathBuf) -> Self {
+        FifoSink { path, pipe: None, opening: None }
+    }
+
+    fn connect(&mut self) -> io::Result<()> {
+        let opening = self.opening.get_or_insert_with(|| {
+            let (tx, rx) = mpsc::channel();
+            let path = self.path.clone();
+            thread::spawn(move || {
+                let _ = tx.send(OpenOptions::new().write(true).open(path));
+            });
+            rx
+        });
+        if let Ok(opened) = opening.try_recv() {
+            self.opening = None;
+            self.pipe = Some(opened?);
+        }
+        Ok(())
+    }
+
+    pub fn write_frame(&mut self, frame: &str) -> io::Result<()> {
+        if self.pipe.is_none() {
+            self.connect()?;
+        }
+        let Some(pipe) = self.pipe.as_mut() else {
+            return Ok(());
+        };
+        match pipe.write_all(frame.as_bytes()).and_then(|()| pipe.flush()) {
+            Err(err) if err.kind() == ErrorKind::BrokenPipe => {
+                self.pipe = None;
+                Ok(())
+            }
+            written => written,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        env, fs,
+        io::Read,
+        process::{self, Command},
+        time::Duration,
+    };
+
+    #[cfg(unix)]
+    #[test]
+    fn a_reader_going_away_drops_the_pipe_instead_of_failing() {
+        let path = env::temp_dir().join(format!("procgen-fifo-{}", process::id()));
+        let _ = fs::remove_file(&path);
+        assert!(Command::new("mkfifo").arg(&path).status().unwrap().success());
+        let mut sink = FifoSink::new(path.clone());
+        sink.write_frame("nobody is listening\n").unwrap();
+        let mut reader = File::open(&path).unwrap();
+        while sink.pipe.is_none() {
+            sink.connect().unwrap();
+            thread::sleep(Duration::from_millis(1));
+        }
+        sink.write_frame("frame\n").unwrap();
+        let mut received = [0; 6];
+        reader.read_exact(&mut received).unwrap();
+        assert_eq!(&received, b"frame\n");
+        drop(reader);
+        sink.write_frame("lost\n").unwrap();
+        assert!(sink.pipe.is_none());
+        fs::remove_file(&path).unwrap();
+    }
+}
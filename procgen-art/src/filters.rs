@@ -0,0 +1,169 @@
+use crate::render::{art_char, Canvas};
+use clap::ValueEnum;
+use serde::Serialize;
+
+pub trait Filter {
+    fn apply(&self, canvas: &mut Canvas, ramp: &str);
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, ValueEnum, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FilterKind {
+    Symmetry,
+    Invert,
+    Blur,
+}
+
+impl Filter for FilterKind {
+    fn apply(&self, canvas: &mut Canvas, ramp: &str) {
+        let field = match self {
+            FilterKind::Symmetry => mirror(&canvas.intensity),
+            FilterKind::Invert => invert(&canvas.intensity),
+            FilterKind::Blur => box_blur(&canvas.intensity),
+        };
+        replace_field(canvas, ramp, field);
+    }
+}
+
+pub fn mirror(field: &[Vec<f32>]) -> Vec<Vec<f32>> {
+    field
+        .iter()
+        .map(|row| (0..row.len()).map(|x| row[x.min(row.len() - 1 - x)]).collect())
+        .collect()
+}
+
+pub fn invert(field: &[Vec<f32>]) -> Vec<Vec<f32>> {
+    field.iter().map(|row| row.iter().map(|&value| 1.0 - value.clamp(0.0, 1.0)).collect()).collect()
+}
+
+pub fn box_blur(field: &[Vec<f32>]) -> Vec<Vec<f32>> {
+    let height = field.len();
+    (0..height)
+        .map(|y| {
+            (0..field[y].len())
+                .map(|x| {
+                    let (mut sum, mut count) = (0.0, 0);
+                    for row in &field[y.saturating_sub(1)..(y + 2).min(height)] {
+                        for nx in x.saturating_sub(1)..x + 2 {
+                            if let Some(&value) = row.get(nx) {
+                                sum += value;
+                                count += 1;
+                            }
+                        }
+                    }
+                    sum / count as f32
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn replace_field(canvas: &mut Canvas, ramp: &str, field: Vec<Vec<f32>>) {
+    for (y, values) in field.into_iter().enumerate() {
+        let Some(row) = canvas.rows.get_mut(y).filter(|_| y != canvas.overlay_row) else { continue };
+        *row = values.iter().map(|&value| art_char(ramp, value)).collect();
+        canvas.intensity[y] = values;
+    }
+}
+
+pub const SCANLINE_DIM: f32 = 0.3;
+
+pub fn is_scanline(y: usize, every: u16, offset: u64) -> bool {
+    let every = every as u64;
+    every > 1 && y as u64 % every == (every - 1 + offset) % every
+}
+
+pub fn apply_scanlines(canvas: &mut Canvas, every: u16, offset: u64, ramp: &str) {
+    let field = canvas
+        .intensity
+        .iter()
+        .enumerate()
+        .map(|(y, row)| {
+            let dim = if is_scanline(y, every, offset) { SCANLINE_DIM } else { 1.0 };
+            row.iter().map(|&value| value * dim).collect()
+        })
+        .collect();
+    replace_field(canvas, ramp, field);
+}
+
+pub const GRID_SPACING: usize = 10;
+
+pub fn grid_mark(x: usize, y: usize) -> Option<char> {
+    match (x.is_multiple_of(GRID_SPACING), y.is_multiple_of(GRID_SPACING)) {
+        (true, true) => Some('+'),
+        (true, false) | (false, true) => Some('·'),
+        (false, false) => None,
+    }
+}
+
+pub fn apply_grid_overlay(canvas: &mut Canvas) {
+    for (y, row) in canvas.rows.iter_mut().enumerate() {
+        *row = row.chars().enumerate().map(|(x, ch)| grid_mark(x, y).unwrap_or(ch)).collect();
+    }
+}
+
+pub fn apply_filters(canvas: &mut Canvas, filters: &[FilterKind], ramp: &str) {
+    for filter in filters {
+        filter.apply(canvas, ramp);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        metrics::{MetricsSource, MockSource},
+        render::{effective_ramp, render_frame, RenderOptions},
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn inverting_twice_returns_the_original_frame() {
+        let metrics = MockSource::new(3).sample();
+        let opts = RenderOptions::new(24, 8, "plasma");
+        let original = render_frame(&metrics, &mut StdRng::seed_from_u64(5), &opts);
+        let mut canvas = render_frame(&metrics, &mut StdRng::seed_from_u64(5), &opts);
+        let ramp = effective_ramp(&opts, &original.inputs);
+        apply_filters(&mut canvas, &[FilterKind::Invert], ramp);
+        assert_ne!(canvas.rows, original.rows);
+        apply_filters(&mut canvas, &[FilterKind::Invert], ramp);
+        assert_eq!(canvas.rows, original.rows);
+        for (row, expected) in canvas.intensity.iter().zip(&original.intensity) {
+            for (value, want) in row.iter().zip(expected) {
+                assert!((value - want.clamp(0.0, 1.0)).abs() < 1e-6);
+            }
+        }
+    }
+
+    #[test]
+    fn scanlines_two_dims_every_other_row() {
+        let opts = RenderOptions { overlay: false, ..RenderOptions::new(12, 6, "plasma") };
+        let original = render_frame(&MockSource::new(4).sample(), &mut StdRng::seed_from_u64(4), &opts);
+        let mut canvas = render_frame(&MockSource::new(4).sample(), &mut StdRng::seed_from_u64(4), &opts);
+        apply_scanlines(&mut canvas, 2, 0, effective_ramp(&opts, &original.inputs));
+        for (y, (row, before)) in canvas.intensity.iter().zip(&original.intensity).enumerate() {
+            let dim = if y % 2 == 1 { SCANLINE_DIM } else { 1.0 };
+            assert!(row.iter().zip(before).all(|(value, was)| (value - was * dim).abs() < 1e-6), "row {y}");
+        }
+        assert_eq!(canvas.rows[0], original.rows[0]);
+        assert!(!is_scanline(0, 2, 0) && is_scanline(0, 2, 1));
+    }
+
+    #[test]
+    fn grid_overlay_marks_every_tenth_row_and_column() {
+        let mut canvas = render_frame(&MockSource::new(4).sample(), &mut StdRng::seed_from_u64(4), &RenderOptions::new(21, 12, "plasma"));
+        let original = canvas.rows.clone();
+        apply_grid_overlay(&mut canvas);
+        for (y, row) in canvas.rows.iter().enumerate() {
+            for (x, (ch, was)) in row.chars().zip(original[y].chars()).enumerate() {
+                let expected = match (x % GRID_SPACING == 0, y % GRID_SPACING == 0) {
+                    (true, true) => '+',
+                    (true, false) | (false, true) => '·',
+                    (false, false) => was,
+                };
+                assert_eq!(ch, expected, "cell ({x}, {y})");
+            }
+        }
+        assert_eq!(canvas.rows[10].chars().filter(|&ch| ch == '+').count(), 3);
+    }
+}
@@ -0,0 +1,60 @@
+use crate::render::{stamp, Canvas};
+use std::time::Instant;
+
+const SMOOTHING: f32 = 0.2;
+
+#[derive(Default)]
+pub struct FpsMeter {
+    last: Option<Instant>,
+    fps: f32,
+    frames: u64,
+}
+
+impl FpsMeter {
+    pub fn tick(&mut self, now: Instant) -> f32 {
+        self.frames += 1;
+        if let Some(last) = self.last.replace(now) {
+            let elapsed = now.duration_since(last).as_secs_f32();
+            if elapsed > 0.0 {
+                self.fps = smooth_fps(self.fps, 1.0 / elapsed);
+            }
+        }
+        self.fps
+    }
+
+    pub fn label(&self) -> String {
+        format!("{:.1} fps | frame {}", self.fps, self.frames)
+    }
+}
+
+pub fn smooth_fps(previous: f32, instant: f32) -> f32 {
+    if previous == 0.0 {
+        instant
+    } else {
+        previous + (instant - previous) * SMOOTHING
+    }
+}
+
+pub fn stamp_fps(canvas: &mut Canvas, text: &str) {
+    let last = canvas.rows.len().saturating_sub(1);
+    let row = if canvas.overlay_row == last { 0 } else { last };
+    if let Some(line) = canvas.rows.get_mut(row) {
+        stamp(line, 0, text);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn fps_smooths_over_a_series_of_timestamps() {
+        let start = Instant::now();
+        let mut meter = FpsMeter::default();
+        let readings: Vec<f32> = [0, 100, 150, 200, 200].iter().map(|&ms| meter.tick(start + Duration::from_millis(ms))).collect();
+        let expected = [0.0, 10.0, 12.0, 13.6, 13.6];
+        assert!(readings.iter().zip(expected).all(|(fps, want)| (fps - want).abs() < 1e-3), "{readings:?}");
+        assert_eq!(meter.label(), "13.6 fps | frame 5");
+    }
+}
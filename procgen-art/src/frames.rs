@@ -0,0 +1,49 @@
+use crate::{
+    metrics::{gather_metrics, Metrics, MetricsSource},
+    render::Canvas,
+};
+use std::{ops::ControlFlow, thread, time::Duration};
+
+/// Samples `source` every `interval`, renders each sample and hands the frame to `on_frame`
+/// until it returns [`ControlFlow::Break`]. Returns the number of frames rendered.
+///
+/// ```
+/// use procgen_art::{
+///     frames::run_frames,
+///     metrics::MockSource,
+///     render::{render_frame, RenderOptions},
+/// };
+/// use rand::{rngs::StdRng, SeedableRng};
+/// use std::{ops::ControlFlow, time::Duration};
+///
+/// let mut rng = StdRng::seed_from_u64(1);
+/// let opts = RenderOptions::new(20, 4, "plasma");
+/// let mut seen = 0;
+/// let frames = run_frames(&mut MockSource::new(1), Duration::ZERO, |metrics| render_frame(metrics, &mut rng, &opts), |_, canvas| {
+///     assert_eq!(canvas.rows.len(), 4);
+///     seen += 1;
+///     if seen == 3 {
+///         ControlFlow::Break(())
+///     } else {
+///         ControlFlow::Continue(())
+///     }
+/// });
+/// assert_eq!((frames, seen), (3, 3));
+/// ```
+pub fn run_frames<S, R, F>(source: &mut S, interval: Duration, mut render: R, mut on_frame: F) -> u64
+where
+    S: MetricsSource + ?Sized,
+    R: FnMut(&Metrics) -> Canvas,
+    F: FnMut(&Metrics, &mut Canvas) -> ControlFlow<()>,
+{
+    let mut frames = 0;
+    loop {
+        let metrics = gather_metrics(source);
+        let mut canvas = render(&metrics);
+        frames += 1;
+        if on_frame(&metrics, &mut canvas).is_break() {
+            return frames;
+        }
+        thread::sleep(interval);
+    }
+}
@@ -0,0 +1,108 @@
+use crate::{
+    legend::draw_legend,
+    metrics::{Metrics, NormalizedMetrics},
+};
+use clap::ValueEnum;
+use crossterm::{
+    cursor, queue,
+    style::{Color, Print, ResetColor, SetForegroundColor},
+    terminal::{Clear, ClearType},
+};
+use std::io::{self, stdout, Write};
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum GaugeMetric {
+    Cpu,
+    Mem,
+    Net,
+    Load,
+}
+
+impl GaugeMetric {
+    pub fn value(self, metrics: &Metrics) -> f32 {
+        let inputs = NormalizedMetrics::from_metrics(metrics);
+        match self {
+            GaugeMetric::Cpu => inputs.cpu,
+            GaugeMetric::Mem => inputs.memory,
+            GaugeMetric::Net => inputs.network,
+            GaugeMetric::Load => {
+                let cores = metrics.cpu_cores.len().max(1) as f64;
+                (metrics.load_avg / cores).clamp(0.0, 1.0) as f32
+            }
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            GaugeMetric::Cpu => "CPU",
+            GaugeMetric::Mem => "MEM",
+            GaugeMetric::Net => "NET",
+            GaugeMetric::Load => "LOAD",
+        }
+    }
+}
+
+pub fn gauge_fill(value: f32, width: u16) -> usize {
+    (value.clamp(0.0, 1.0) * width as f32).round() as usize
+}
+
+pub fn gauge_color(value: f32) -> Color {
+    match value {
+        v if v < 0.5 => Color::Green,
+        v if v < 0.8 => Color::Yellow,
+        _ => Color::Red,
+    }
+}
+
+fn gauge_bar(value: f32, width: u16) -> String {
+    let filled = gauge_fill(value, width);
+    format!("{}{}", "█".repeat(filled), "░".repeat(width as usize - filled))
+}
+
+/// One-line form for `--once`, which prints into the scrollback instead of taking over the screen.
+pub fn gauge_line(metric: GaugeMetric, value: f32, width: u16) -> String {
+    let label = format!("{} {:.0}% ", metric.label(), value * 100.0);
+    format!("{label}{}", gauge_bar(value, width.saturating_sub(label.len() as u16)))
+}
+
+pub fn display_gauge(metric: GaugeMetric, value: f32, width: u16, height: u16, legend: bool) -> io::Result<()> {
+    let mut out = stdout();
+    let bar = gauge_bar(value, width);
+    let label = format!("{} {:.0}%", metric.label(), value * 100.0);
+    let band = (height / 3).max(1);
+    let top = height.saturating_sub(band) / 2;
+    queue!(out, Clear(ClearType::All), SetForegroundColor(gauge_color(value)))?;
+    for row in top..top + band {
+        queue!(out, cursor::MoveTo(0, row), Print(&bar))?;
+    }
+    let col = (width as usize).saturating_sub(label.len()) / 2;
+    queue!(out, cursor::MoveTo(col as u16, top.saturating_sub(2)), Print(label), ResetColor)?;
+    if legend && height > band + 3 {
+        draw_legend(&mut out, height - 2, width)?;
+    }
+    out.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gauge_fill_scales_to_the_canvas_width() {
+        assert_eq!(gauge_fill(0.0, 80), 0);
+        assert_eq!(gauge_fill(0.5, 80), 40);
+        assert_eq!(gauge_fill(0.333, 10), 3);
+        assert_eq!(gauge_fill(1.0, 80), 80);
+        assert_eq!(gauge_fill(1.7, 80), 80);
+        assert_eq!(gauge_fill(-0.2, 80), 0);
+    }
+
+    #[test]
+    fn the_once_line_fits_the_width_with_its_label_first() {
+        let line = gauge_line(GaugeMetric::Cpu, 0.5, 30);
+        assert!(line.starts_with("CPU 50% "), "{line:?}");
+        assert_eq!(line.chars().count(), 30);
+        assert_eq!(line.matches('█').count(), gauge_fill(0.5, 22));
+        assert_eq!(gauge_line(GaugeMetric::Load, 1.0, 4), "LOAD 100% ");
+    }
+}
@@ -0,0 +1,210 @@
+use crate::sixel::stop_index;
+use clap::ValueEnum;
+use image::{ImageFormat, Rgb, RgbImage};
+use serde::Serialize;
+use std::{
+    env,
+    io::{self, Cursor},
+};
+
+const KITTY_CHUNK: usize = 4096;
+pub const LABEL_HEIGHT: u32 = 7;
+const GLYPH_WIDTH: u32 = 4;
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, ValueEnum, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ImageProtocol {
+    Kitty,
+    Iterm2,
+    Auto,
+}
+
+impl ImageProtocol {
+    pub fn resolve(self) -> Option<ImageProtocol> {
+        match self {
+            ImageProtocol::Auto => detect_protocol(),
+            explicit => Some(explicit),
+        }
+    }
+}
+
+pub fn detect_protocol() -> Option<ImageProtocol> {
+    let term = env::var("TERM").unwrap_or_default();
+    let program = env::var("TERM_PROGRAM").unwrap_or_default();
+    if env::var_os("KITTY_WINDOW_ID").is_some() || term.contains("kitty") || program == "ghostty" {
+        Some(ImageProtocol::Kitty)
+    } else if ["iTerm.app", "WezTerm"].contains(&program.as_str()) {
+        Some(ImageProtocol::Iterm2)
+    } else {
+        None
+    }
+}
+
+pub fn rasterize(field: &[Vec<f32>], colors: &[(u8, u8, u8)], scale_x: usize, scale_y: usize) -> RgbImage {
+    let rows = field.len() * scale_y;
+    let cols = field.first().map_or(0, Vec::len) * scale_x;
+    RgbImage::from_fn(cols as u32, rows as u32, |x, y| {
+        let intensity = field[y as usize / scale_y].get(x as usize / scale_x).copied().unwrap_or(0.0);
+        let (r, g, b) = colors.get(stop_index(intensity, colors.len().max(1))).copied().unwrap_or((0, 0, 0));
+        Rgb([r, g, b])
+    })
+}
+
+pub fn render_png(field: &[Vec<f32>], colors: &[(u8, u8, u8)], scale_x: usize, scale_y: usize) -> Vec<u8> {
+    encode_png(&rasterize(field, colors, scale_x, scale_y))
+}
+
+pub fn encode_png(image: &RgbImage) -> Vec<u8> {
+    let mut png = Cursor::new(Vec::new());
+    let _ = image.write_to(&mut png, ImageFormat::Png);
+    png.into_inner()
+}
+
+/// 3x5 bitmaps, one row per entry with the high bit on the left, for the characters panel labels use.
+fn glyph(ch: char) -> [u8; 5] {
+    match ch {
+        '0' => [7, 5, 5, 5, 7],
+        '1' => [2, 6, 2, 2, 7],
+        '2' => [7, 1, 7, 4, 7],
+        '3' => [7, 1, 7, 1, 7],
+        '4' => [5, 5, 7, 1, 1],
+        '5' => [7, 4, 7, 1, 7],
+        '6' => [7, 4, 7, 5, 7],
+        '7' => [7, 1, 1, 1, 1],
+        '8' => [7, 5, 7, 5, 7],
+        '9' => [7, 5, 7, 1, 7],
+        '#' => [5, 7, 5, 7, 5],
+        _ => [0; 5],
+    }
+}
+
+/// Draws `text` in white on a black box whose top-left corner is (`x`, `y`), clipped to `max_width` pixels.
+pub fn draw_label(image: &mut RgbImage, x: u32, y: u32, max_width: u32, text: &str) {
+    let width = (text.chars().count() as u32 * GLYPH_WIDTH + 1).min(max_width);
+    for dy in 0..LABEL_HEIGHT {
+        for dx in 0..width {
+            if let Some(pixel) = image.get_pixel_mut_checked(x + dx, y + dy) {
+                *pixel = Rgb([0, 0, 0]);
+            }
+        }
+    }
+    for (i, ch) in text.chars().enumerate() {
+        for (row, bits) in glyph(ch).into_iter().enumerate() {
+            for col in 0..3 {
+                let dx = 1 + i as u32 * GLYPH_WIDTH + col;
+                if bits & (4 >> col) != 0 && dx < width {
+                    if let Some(pixel) = image.get_pixel_mut_checked(x + dx, y + 1 + row as u32) {
+                        *pixel = Rgb([255, 255, 255]);
+                    }
+                }
+            }
+        }
+    }
+}
+
+pub fn blend_fields(from: &[Vec<f32>], to: &[Vec<f32>], t: f32) -> Vec<Vec<f32>> {
+    from.iter().zip(to).map(|(a, b)| a.iter().zip(b).map(|(a, b)| a + (b - a) * t).collect()).collect()
+}
+
+pub fn interpolate_fields(fields: &[Vec<Vec<f32>>], factor: u32) -> Vec<Vec<Vec<f32>>> {
+    let factor = factor.max(1);
+    (0..fields.len())
+        .flat_map(|idx| {
+            let (from, to) = (&fields[idx], &fields[(idx + 1) % fields.len()]);
+            (0..factor).map(move |step| blend_fields(from, to, step as f32 / factor as f32))
+        })
+        .collect()
+}
+
+pub fn encode_apng(fields: &[Vec<Vec<f32>>], colors: &[(u8, u8, u8)], scale_x: usize, scale_y: usize, delay_ms: u64) -> io::Result<Vec<u8>> {
+    let frames: Vec<RgbImage> = fields.iter().map(|field| rasterize(field, colors, scale_x, scale_y)).collect();
+    let (width, height) = frames.first().map_or((1, 1), |frame| frame.dimensions());
+    let mut out = Vec::new();
+    let mut encoder = png::Encoder::new(&mut out, width.max(1), height.max(1));
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.set_animated(frames.len().max(1) as u32, 0).map_err(io::Error::other)?;
+    encoder.set_frame_delay(delay_ms.min(u16::MAX as u64) as u16, 1000).map_err(io::Error::other)?;
+    let mut writer = encoder.write_header().map_err(io::Error::other)?;
+    for frame in &frames {
+        writer.write_image_data(frame.as_raw()).map_err(io::Error::other)?;
+    }
+    writer.finish().map_err(io::Error::other)?;
+    Ok(out)
+}
+
+pub fn base64(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let word = chunk.iter().enumerate().fold(0u32, |word, (idx, byte)| word | ((*byte as u32) << (16 - 8 * idx)));
+        for idx in 0..4 {
+            if idx <= chunk.len() {
+                out.push(BASE64[((word >> (18 - 6 * idx)) & 0x3f) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+pub fn encode_kitty(png: &[u8]) -> String {
+    let payload = base64(png);
+    let chunks: Vec<&[u8]> = payload.as_bytes().chunks(KITTY_CHUNK).collect();
+    let mut out = String::with_capacity(payload.len() + chunks.len() * 24);
+    for (idx, chunk) in chunks.iter().enumerate() {
+        let more = u8::from(idx + 1 < chunks.len());
+        let header = if idx == 0 { format!("a=T,f=100,i=1,q=2,m={more}") } else { format!("m={more}") };
+        out.push_str(&format!("\x1b_G{header};{}\x1b\\", String::from_utf8_lossy(chunk)));
+    }
+    out
+}
+
+pub fn encode_iterm2(png: &[u8]) -> String {
+    format!("\x1b]1337;File=inline=1;size={};preserveAspectRatio=0:{}\x07", png.len(), base64(png))
+}
+
+pub fn encode_image(protocol: ImageProtocol, png: &[u8]) -> String {
+    match protocol {
+        ImageProtocol::Iterm2 => encode_iterm2(png),
+        _ => encode_kitty(png),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kitty_wraps_a_small_png_in_one_apc_sequence() {
+        let png = render_png(&[vec![0.0, 1.0]], &[(0, 0, 0), (255, 255, 255)], 1, 1);
+        let escape = encode_kitty(&png);
+        assert_eq!(escape, format!("\x1b_Ga=T,f=100,i=1,q=2,m=0;{}\x1b\\", base64(&png)));
+        assert_eq!(base64(b"Man"), "TWFu");
+        assert_eq!(base64(b"Ma"), "TWE=");
+    }
+
+    #[test]
+    fn kitty_chunks_large_payloads() {
+        let big = vec![7u8; KITTY_CHUNK];
+        let escape = encode_kitty(&big);
+        let chunks: Vec<&str> = escape.split("\x1b\\").filter(|chunk| !chunk.is_empty()).collect();
+        assert_eq!(chunks.len(), 2);
+        assert!(chunks[0].starts_with("\x1b_Ga=T,f=100,i=1,q=2,m=1;"));
+        assert!(chunks[1].starts_with("\x1b_Gm=0;"));
+    }
+
+    #[test]
+    fn factor_two_doubles_frames_with_midpoint_blends() {
+        let fields = vec![vec![vec![0.0, 0.2]], vec![vec![1.0, 0.6]], vec![vec![0.4, 0.4]]];
+        let smooth = interpolate_fields(&fields, 2);
+        assert_eq!(smooth.len(), 6);
+        assert_eq!([&smooth[0], &smooth[2], &smooth[4]], [&fields[0], &fields[1], &fields[2]]);
+        let close = |a: &[Vec<f32>], b: &[f32]| a[0].iter().zip(b).all(|(a, b)| (a - b).abs() < 1e-6);
+        assert!(close(&smooth[1], &[0.5, 0.4]));
+        assert!(close(&smooth[3], &[0.7, 0.5]));
+        assert!(close(&smooth[5], &[0.2, 0.3]));
+        assert_eq!(interpolate_fields(&fields, 1), fields);
+    }
+}
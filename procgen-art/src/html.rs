@@ -0,0 +1,61 @@
+use crate::{
+    color::ansi_rgb,
+    render::{palette, Canvas},
+};
+use std::fmt::Write;
+
+pub fn escape_html(ch: char) -> Option<&'static str> {
+    match ch {
+        '<' => Some("&lt;"),
+        '>' => Some("&gt;"),
+        '&' => Some("&amp;"),
+        '"' => Some("&quot;"),
+        '\'' => Some("&#39;"),
+        _ => None,
+    }
+}
+
+pub fn render_html(canvas: &Canvas, style: &str) -> String {
+    let colors = palette(style);
+    let mut out = String::from("<pre style=\"background:#000;line-height:1;font-family:monospace\">\n");
+    for (y, row) in canvas.rows.iter().enumerate() {
+        let values = canvas.intensity.get(y).map_or(&[][..], Vec::as_slice);
+        for (x, ch) in row.chars().enumerate() {
+            let idx = match values.get(x) {
+                Some(intensity) => (intensity.clamp(0.0, 1.0) * (colors.len() - 1) as f32).round() as usize,
+                None => y % colors.len(),
+            };
+            let (r, g, b) = ansi_rgb(colors[idx]);
+            let _ = write!(out, "<span style=\"color:#{r:02x}{g:02x}{b:02x}\">");
+            match escape_html(ch) {
+                Some(entity) => out.push_str(entity),
+                None => out.push(ch),
+            }
+            out.push_str("</span>");
+        }
+        out.push('\n');
+    }
+    out.push_str("</pre>\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        metrics::{MetricsSource, MockSource},
+        render::{render_frame, RenderOptions},
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn one_span_per_cell_with_escaped_text() {
+        let mut canvas = render_frame(&MockSource::new(2).sample(), &mut StdRng::seed_from_u64(2), &RenderOptions::new(10, 3, "plasma"));
+        canvas.rows[0] = "<a&b>     ".to_string();
+        let html = render_html(&canvas, "plasma");
+        assert_eq!(html.matches("<span ").count(), 10 * 3);
+        assert_eq!(html.matches("</span>").count(), 10 * 3);
+        assert!(html.contains(">&lt;</span>") && html.contains(">&amp;</span>") && html.contains(">&gt;</span>"));
+        assert!(!html.contains("><</span>") && !html.contains(">&</span>"));
+    }
+}
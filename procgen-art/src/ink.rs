@@ -0,0 +1,104 @@
+use crate::{
+    metrics::NormalizedMetrics,
+    render::{art_char, Canvas},
+};
+use rand::{rngs::StdRng, Rng};
+
+pub const INK_STYLE: &str = "ink";
+pub const INK_DIFFUSION: f32 = 0.2;
+pub const INK_FADE: f32 = 0.97;
+
+pub fn diffuse(grid: &[Vec<f32>]) -> Vec<Vec<f32>> {
+    let height = grid.len();
+    let width = grid.first().map_or(0, Vec::len);
+    let at = |x: usize, y: usize, dx: isize, dy: isize| {
+        let x = (x as isize + dx).rem_euclid(width as isize) as usize;
+        let y = (y as isize + dy).rem_euclid(height as isize) as usize;
+        grid[y][x]
+    };
+    (0..height)
+        .map(|y| {
+            (0..width)
+                .map(|x| {
+                    let neighbours = at(x, y, -1, 0) + at(x, y, 1, 0) + at(x, y, 0, -1) + at(x, y, 0, 1);
+                    grid[y][x] * (1.0 - INK_DIFFUSION) + neighbours * INK_DIFFUSION / 4.0
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn advect(grid: &[Vec<f32>], frame: u64, cpu: f32) -> Vec<Vec<f32>> {
+    grid.iter()
+        .enumerate()
+        .map(|(y, row)| {
+            let shift = ((y as f32 * 0.3 + frame as f32 * 0.1).sin() * cpu * 2.0).round() as isize;
+            let width = row.len() as isize;
+            (0..width).map(|x| row[(x - shift).rem_euclid(width) as usize]).collect()
+        })
+        .collect()
+}
+
+#[derive(Default)]
+pub struct InkField {
+    grid: Vec<Vec<f32>>,
+    frame: u64,
+}
+
+impl InkField {
+    pub fn step(&mut self, inputs: &NormalizedMetrics, rng: &mut StdRng, width: u16, height: u16) {
+        let (width, height) = (width as usize, height as usize);
+        if self.grid.len() != height || self.grid.first().map_or(0, Vec::len) != width {
+            self.grid = vec![vec![0.0; width]; height];
+            self.frame = 0;
+        }
+        if width == 0 || height == 0 {
+            return;
+        }
+        self.grid = diffuse(&advect(&self.grid, self.frame, inputs.cpu));
+        for row in &mut self.grid {
+            for value in row.iter_mut() {
+                *value *= INK_FADE;
+            }
+        }
+        let drops = 1 + (inputs.cpu * 5.0) as usize;
+        for _ in 0..drops {
+            let (x, y) = (rng.gen_range(0..width), rng.gen_range(0..height));
+            self.grid[y][x] = (self.grid[y][x] + inputs.memory + 0.3).min(1.0);
+        }
+        self.frame += 1;
+    }
+
+    pub fn canvas(&self, ramp: &str, inputs: NormalizedMetrics) -> Canvas {
+        Canvas {
+            rows: self
+                .grid
+                .iter()
+                .map(|row| row.iter().map(|&value| art_char(ramp, value)).collect())
+                .collect(),
+            intensity: self.grid.clone(),
+            overlay_row: usize::MAX,
+            alerts: Vec::new(),
+            inputs,
+            sanitized: 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn one_diffusion_step_spreads_an_impulse_to_its_neighbours() {
+        let mut grid = vec![vec![0.0; 5]; 5];
+        grid[2][2] = 1.0;
+        let next = diffuse(&grid);
+        assert_eq!(next[2][2], 1.0 - INK_DIFFUSION);
+        for (x, y) in [(1, 2), (3, 2), (2, 1), (2, 3)] {
+            assert_eq!(next[y][x], INK_DIFFUSION / 4.0);
+        }
+        assert_eq!(next[1][1], 0.0);
+        assert!((next.iter().flatten().sum::<f32>() - 1.0).abs() < 1e-6);
+    }
+}
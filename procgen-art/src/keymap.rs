@@ -0,0 +1,118 @@
+use crossterm::event::KeyCode;
+use serde::Deserialize;
+use std::{collections::BTreeMap, fs, io, path::Path};
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Action {
+    Quit,
+    Freeze,
+    CycleDepth,
+    NextStyle,
+    PanLeft,
+    PanRight,
+    PanUp,
+    PanDown,
+}
+
+impl Action {
+    pub fn label(self) -> &'static str {
+        match self {
+            Action::Quit => "quit",
+            Action::Freeze => "freeze",
+            Action::CycleDepth => "cycle-depth",
+            Action::NextStyle => "next-style",
+            Action::PanLeft => "pan-left",
+            Action::PanRight => "pan-right",
+            Action::PanUp => "pan-up",
+            Action::PanDown => "pan-down",
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct KeyMap {
+    bindings: Vec<(KeyCode, Action)>,
+}
+
+impl Default for KeyMap {
+    fn default() -> Self {
+        KeyMap {
+            bindings: vec![
+                (KeyCode::Char('q'), Action::Quit),
+                (KeyCode::Esc, Action::Quit),
+                (KeyCode::Char('m'), Action::Freeze),
+                (KeyCode::Char('c'), Action::CycleDepth),
+                (KeyCode::Char('s'), Action::NextStyle),
+                (KeyCode::Left, Action::PanLeft),
+                (KeyCode::Right, Action::PanRight),
+                (KeyCode::Up, Action::PanUp),
+                (KeyCode::Down, Action::PanDown),
+            ],
+        }
+    }
+}
+
+pub fn parse_key(name: &str) -> Result<KeyCode, String> {
+    let mut chars = name.chars();
+    match (chars.next(), chars.next()) {
+        (Some(ch), None) => Ok(KeyCode::Char(ch)),
+        _ => match name.to_ascii_lowercase().as_str() {
+            "esc" => Ok(KeyCode::Esc),
+            "enter" => Ok(KeyCode::Enter),
+            "space" => Ok(KeyCode::Char(' ')),
+            "tab" => Ok(KeyCode::Tab),
+            "left" => Ok(KeyCode::Left),
+            "right" => Ok(KeyCode::Right),
+            "up" => Ok(KeyCode::Up),
+            "down" => Ok(KeyCode::Down),
+            _ => Err(format!("unknown key '{name}'")),
+        },
+    }
+}
+
+pub fn key_name(code: KeyCode) -> String {
+    match code {
+        KeyCode::Char(' ') => "space".to_string(),
+        KeyCode::Char(ch) => ch.to_string(),
+        KeyCode::Esc => "esc".to_string(),
+        KeyCode::Enter => "enter".to_string(),
+        KeyCode::Tab => "tab".to_string(),
+        KeyCode::Left => "left".to_string(),
+        KeyCode::Right => "right".to_string(),
+        KeyCode::Up => "up".to_string(),
+        KeyCode::Down => "down".to_string(),
+        other => format!("{other:?}"),
+    }
+}
+
+impl KeyMap {
+    pub fn with_overrides(overrides: &BTreeMap<Action, Vec<String>>) -> Result<Self, String> {
+        let mut bindings: Vec<(KeyCode, Action)> = KeyMap::default()
+            .bindings
+            .into_iter()
+            .filter(|(_, action)| !overrides.contains_key(action))
+            .collect();
+        for (&action, keys) in overrides {
+            for key in keys {
+                bindings.push((parse_key(key)?, action));
+            }
+        }
+        for (idx, (key, action)) in bindings.iter().enumerate() {
+            if let Some((_, other)) = bindings[idx + 1..].iter().find(|(other_key, _)| other_key == key) {
+                return Err(format!("key {} is bound to both {} and {}", key_name(*key), action.label(), other.label()));
+            }
+        }
+        Ok(KeyMap { bindings })
+    }
+
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let overrides: BTreeMap<Action, Vec<String>> =
+            serde_json::from_str(&fs::read_to_string(path)?).map_err(io::Error::other)?;
+        KeyMap::with_overrides(&overrides).map_err(io::Error::other)
+    }
+
+    pub fn action(&self, code: KeyCode) -> Option<Action> {
+        self.bindings.iter().find(|(key, _)| *key == code).map(|(_, action)| *action)
+    }
+}
@@ -0,0 +1,96 @@
+use crate::{gauge::gauge_color, render::stamp};
+use crossterm::{
+    cursor, queue,
+    style::{Print, ResetColor, SetBackgroundColor},
+};
+use std::io::{self, Write};
+
+pub fn legend_ticks(width: usize) -> usize {
+    (width / 12).clamp(2, 6)
+}
+
+pub fn legend_labels(width: usize, min: f32, max: f32) -> String {
+    let mut row = " ".repeat(width);
+    let ticks = legend_ticks(width);
+    for tick in 0..ticks {
+        let t = tick as f32 / (ticks - 1) as f32;
+        let label = format!("{:.0}%", min + (max - min) * t);
+        let col = (t * width.saturating_sub(1) as f32).round() as usize;
+        let start = col.saturating_sub(label.len() / 2).min(width.saturating_sub(label.len()));
+        stamp(&mut row, start, &label);
+    }
+    row
+}
+
+pub const AXIS_GUTTER: usize = 5;
+pub const TIME_MARK_SPACING: usize = 12;
+
+pub fn axis_ticks(height: usize, min: f32, max: f32) -> Vec<(usize, String)> {
+    let ticks = if height >= 9 { 5 } else { 3 }.min(height.max(1));
+    (0..ticks)
+        .map(|tick| {
+            let t = if ticks > 1 { tick as f32 / (ticks - 1) as f32 } else { 1.0 };
+            let row = ((1.0 - t) * height.saturating_sub(1) as f32).round() as usize;
+            (row, format!("{:>3.0}%", min + (max - min) * t))
+        })
+        .collect()
+}
+
+pub fn time_labels(width: usize, seconds_per_col: f32) -> String {
+    let mut row = " ".repeat(width);
+    for back in (0..width).step_by(TIME_MARK_SPACING) {
+        let label = match back {
+            0 => "now".to_string(),
+            _ => format!("-{:.0}s", back as f32 * seconds_per_col),
+        };
+        stamp(&mut row, (width - 1 - back).saturating_sub(label.len().saturating_sub(1)), &label);
+    }
+    row
+}
+
+pub fn add_axis(rows: Vec<String>, seconds_per_col: f32) -> Vec<String> {
+    let width = rows.first().map_or(0, |row| row.chars().count());
+    let ticks = axis_ticks(rows.len(), 0.0, 100.0);
+    let mut framed: Vec<String> = rows
+        .into_iter()
+        .enumerate()
+        .map(|(y, row)| {
+            let label = ticks.iter().find(|(tick_row, _)| *tick_row == y).map_or("", |(_, label)| label.as_str());
+            format!("{label:<gutter$}{row}", gutter = AXIS_GUTTER)
+        })
+        .collect();
+    framed.push(format!("{:AXIS_GUTTER$}{}", "", time_labels(width, seconds_per_col)));
+    framed
+}
+
+pub fn draw_legend<W: Write>(out: &mut W, row: u16, width: u16) -> io::Result<()> {
+    let span = width.saturating_sub(1).max(1) as f32;
+    queue!(out, cursor::MoveTo(0, row))?;
+    for x in 0..width {
+        queue!(out, SetBackgroundColor(gauge_color(x as f32 / span)), Print(' '))?;
+    }
+    let labels = legend_labels(width as usize, 0.0, 100.0);
+    queue!(out, ResetColor, cursor::MoveTo(0, row + 1), Print(labels))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn legend_labels_sit_under_their_stops() {
+        assert_eq!(legend_labels(24, 0.0, 100.0), format!("0%{}100%", " ".repeat(18)));
+        assert_eq!(legend_labels(36, 0.0, 100.0), format!("0%{}50%{}100%", " ".repeat(15), " ".repeat(12)));
+        assert_eq!(legend_labels(24, 20.0, 80.0), format!("20%{}80%", " ".repeat(18)));
+        assert_eq!(legend_ticks(200), 6);
+    }
+
+    #[test]
+    fn axis_ticks_span_the_plot_from_bottom_to_top() {
+        let labels = |ticks: Vec<(usize, String)>| ticks.into_iter().map(|(row, label)| (row, label.trim().to_string())).collect::<Vec<_>>();
+        assert_eq!(labels(axis_ticks(9, 0.0, 100.0)), vec![(8, "0%".into()), (6, "25%".into()), (4, "50%".into()), (2, "75%".into()), (0, "100%".into())]);
+        assert_eq!(labels(axis_ticks(5, 20.0, 60.0)), vec![(4, "20%".into()), (2, "40%".into()), (0, "60%".into())]);
+        assert_eq!(axis_ticks(1, 0.0, 100.0), vec![(0, "100%".to_string())]);
+    }
+}
@@ -0,0 +1,45 @@
+pub fn parse_ratio(value: &str) -> Result<f32, String> {
+    let ratio = match value.split_once(':') {
+        Some((w, h)) => {
+            let w: f32 = w.trim().parse().map_err(|_| format!("invalid ratio width '{w}'"))?;
+            let h: f32 = h.trim().parse().map_err(|_| format!("invalid ratio height '{h}'"))?;
+            w / h
+        }
+        None => value.parse().map_err(|_| format!("invalid ratio '{value}'"))?,
+    };
+    if !ratio.is_finite() || ratio <= 0.0 {
+        return Err("ratio must be positive, e.g. 16:9 or 2.5".to_string());
+    }
+    Ok(ratio)
+}
+
+pub fn letterbox(width: u16, height: u16, ratio: f32) -> (u16, u16, u16, u16) {
+    let (inner_width, inner_height) = if width as f32 / height.max(1) as f32 > ratio {
+        (((height as f32 * ratio).round() as u16).clamp(1, width.max(1)), height)
+    } else {
+        (width, ((width as f32 / ratio).round() as u16).clamp(1, height.max(1)))
+    };
+    ((width - inner_width.min(width)) / 2, (height - inner_height.min(height)) / 2, inner_width, inner_height)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn letterbox_centres_the_target_ratio() {
+        assert_eq!(letterbox(100, 20, 2.0), (30, 0, 40, 20));
+        assert_eq!(letterbox(80, 40, 4.0), (0, 10, 80, 20));
+        assert_eq!(letterbox(80, 40, 2.0), (0, 0, 80, 40));
+        assert_eq!(letterbox(3, 1, 0.01), (1, 0, 1, 1));
+    }
+
+    #[test]
+    fn ratios_parse_from_pairs_or_decimals() {
+        assert_eq!(parse_ratio("2:1"), Ok(2.0));
+        assert_eq!(parse_ratio("2.5"), Ok(2.5));
+        assert!(parse_ratio("16:0").is_err());
+        assert!(parse_ratio("-1").is_err());
+        assert_eq!(parse_ratio("wide"), Err("invalid ratio 'wide'".to_string()));
+    }
+}
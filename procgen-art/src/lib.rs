@@ -0,0 +1,78 @@
+//! Terminal generative art seeded by live system metrics.
+//!
+//! Sample the host with a [`metrics::SystemSource`], then render a frame of text rows:
+//!
+//! ```
+//! use procgen_art::{
+//!     metrics::{gather_metrics, SystemSource},
+//!     render::{render_frame, RenderOptions},
+//! };
+//! use rand::{rngs::StdRng, SeedableRng};
+//! use sysinfo::{System, SystemExt};
+//!
+//! let mut source = SystemSource::from_system(System::new_all());
+//! let metrics = gather_metrics(&mut source);
+//! let canvas = render_frame(&metrics, &mut StdRng::seed_from_u64(7), &RenderOptions::new(40, 12, "plasma"));
+//! assert_eq!(canvas.rows.len(), 12);
+//! assert!(canvas.rows.iter().all(|row| row.chars().count() == 40));
+//! ```
+//!
+//! [`scene::draw`] adds the stateful styles (rain, ink, equalizer, ...) and the post-processing filters on top.
+
+pub mod anomaly;
+pub mod ascii;
+pub mod atomic;
+pub mod background;
+pub mod bench;
+pub mod braille;
+pub mod burnin;
+pub mod calibrate;
+pub mod clock;
+pub mod color;
+pub mod csv;
+pub mod demoscene;
+pub mod devices;
+pub mod disks;
+pub mod emit;
+pub mod eq;
+pub mod fifo;
+pub mod filters;
+pub mod fps;
+pub mod frames;
+pub mod gauge;
+pub mod graphics;
+pub mod html;
+pub mod ink;
+pub mod keymap;
+pub mod legend;
+pub mod letterbox;
+pub mod live;
+pub mod log;
+pub mod mask;
+pub mod memmap;
+pub mod metrics;
+pub mod panels;
+pub mod phase;
+pub mod process;
+pub mod prometheus;
+pub mod pulse;
+pub mod rain;
+pub mod record;
+pub mod render;
+pub mod scene;
+pub mod seedfile;
+pub mod shapes;
+pub mod sixel;
+pub mod snapshot;
+pub mod solid;
+pub mod spectrum;
+pub mod stats;
+pub mod styles;
+pub mod substrate;
+pub mod summary;
+pub mod template;
+pub mod termenv;
+pub mod theme;
+pub mod trails;
+pub mod transition;
+pub mod validate;
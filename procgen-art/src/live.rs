@@ -0,0 +1,619 @@
+use crate::{
+    braille::{plot_canvas, History, PlotMetric},
+    color::{ansi_rgb, at_depth, band, cycle_palette, gradient, hue_rotate, legible_text, stepped_palette, tint, ColorDepth, TINT_STRENGTH},
+    demoscene::{demoscene_phase, DEMOSCENE_CYCLE_TICKS},
+    graphics::{encode_image, render_png, ImageProtocol},
+    keymap::{Action, KeyMap},
+    legend::{add_axis, legend_labels, AXIS_GUTTER},
+    log::warn,
+    metrics::NormalizedMetrics,
+    render::{biased_index, dither_stop, palette, palette_stops, stamp, Canvas},
+    sixel::{encode_sixel, SCALE_X, SCALE_Y},
+    styles::style_spec,
+};
+use crossterm::{
+    cursor,
+    event::{DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyModifiers},
+    execute, queue,
+    style::{Attribute, Color, Print, ResetColor, SetAttribute, SetBackgroundColor, SetForegroundColor},
+    terminal::{disable_raw_mode, enable_raw_mode, size, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use std::{
+    io::{self, stdout, Stdout, Write},
+    panic,
+    time::Duration,
+};
+
+#[derive(Default)]
+pub struct LiveState {
+    pub frozen: bool,
+    pub quit: bool,
+    pub depth: ColorDepth,
+    pub mono_locked: bool,
+    pub depth_notice: u8,
+    pub switch_style: bool,
+    pub pan: (f32, f32),
+    pub keys: KeyMap,
+}
+
+impl LiveState {
+    pub fn handle_key(&mut self, key: KeyEvent) {
+        if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+            self.quit = true;
+            return;
+        }
+        match self.keys.action(key.code) {
+            Some(Action::Quit) => self.quit = true,
+            Some(Action::CycleDepth) => {
+                if !self.mono_locked {
+                    self.depth = self.depth.next();
+                }
+                self.depth_notice = DEPTH_NOTICE_FRAMES;
+            }
+            Some(Action::Freeze) => self.frozen = !self.frozen,
+            Some(Action::NextStyle) => self.switch_style = true,
+            Some(Action::PanLeft) => self.pan.0 -= PAN_STEP,
+            Some(Action::PanRight) => self.pan.0 += PAN_STEP,
+            Some(Action::PanUp) => self.pan.1 -= PAN_STEP,
+            Some(Action::PanDown) => self.pan.1 += PAN_STEP,
+            None => {}
+        }
+    }
+
+    pub fn stamp_notice(&mut self, canvas: &mut Canvas) {
+        if self.depth_notice == 0 {
+            return;
+        }
+        if let Some(top) = canvas.rows.first_mut() {
+            stamp(top, 0, &format!(" {} ", self.depth.label()));
+        }
+        self.depth_notice -= 1;
+    }
+}
+
+pub const DEPTH_NOTICE_FRAMES: u8 = 6;
+pub const INTERPOLATE_STEPS: u64 = 4;
+pub const PAN_STEP: f32 = 0.1;
+pub const SCREENSAVER_INTERVAL_MS: u64 = 1000;
+pub const IDLE_HUE_STEP: f32 = 3.0;
+
+pub fn is_idle(since_input: Duration, timeout: Option<Duration>) -> bool {
+    timeout.is_some_and(|timeout| since_input >= timeout)
+}
+
+pub fn is_input_event(event: &Event) -> bool {
+    matches!(event, Event::Key(_) | Event::Mouse(_) | Event::Paste(_))
+}
+
+pub fn stamp_sanitized(canvas: &mut Canvas, width: usize) {
+    let text = format!(" sanitized {} ", canvas.sanitized);
+    if let Some(top) = canvas.rows.first_mut() {
+        stamp(top, width.saturating_sub(text.len()), &text);
+    }
+}
+
+pub fn plot_frame(history: &History, metric: PlotMetric, inputs: NormalizedMetrics, width: u16, height: u16, axis_span: Option<f32>, legend: bool) -> Canvas {
+    if let Some(span) = axis_span.filter(|_| width as usize > AXIS_GUTTER && height > 1) {
+        let mut plot = plot_canvas(history, metric, inputs, width - AXIS_GUTTER as u16, height - 1);
+        plot.rows = add_axis(plot.rows, span);
+        return plot;
+    }
+    let mut plot = plot_canvas(history, metric, inputs, width, height);
+    if let Some(last) = plot.rows.last_mut().filter(|_| legend) {
+        *last = legend_labels(width as usize, 0.0, 100.0);
+    }
+    plot
+}
+
+#[derive(Clone, Copy, Default)]
+pub struct PaletteEffects {
+    pub demoscene: Option<u64>,
+    pub idle: Option<u64>,
+    pub tint: Option<Color>,
+    pub steps: Option<u16>,
+}
+
+pub fn apply_palette_effects(mut colors: Option<Vec<Color>>, style: &str, depth: ColorDepth, effects: PaletteEffects) -> Option<Vec<Color>> {
+    let rich = matches!(depth, ColorDepth::Ansi256 | ColorDepth::TrueColor);
+    let take = |colors: &mut Option<Vec<Color>>| colors.take().unwrap_or_else(|| palette(style));
+    if let Some(tick) = effects.demoscene {
+        let mut cycled = cycle_palette(&take(&mut colors), (tick / DEMOSCENE_CYCLE_TICKS) as usize);
+        if rich {
+            let degrees = demoscene_phase(tick) * 360.0;
+            cycled = cycled.into_iter().map(|color| hue_rotate(color, degrees)).collect();
+        }
+        colors = Some(cycled);
+    }
+    if let Some(tick) = effects.idle.filter(|_| rich) {
+        let degrees = (tick as f32 * IDLE_HUE_STEP) % 360.0;
+        colors = Some(take(&mut colors).into_iter().map(|color| hue_rotate(color, degrees)).collect());
+    }
+    if let Some(hue) = effects.tint.filter(|_| rich) {
+        colors = Some(tint(&take(&mut colors), hue, TINT_STRENGTH));
+    }
+    if let Some(steps) = effects.steps.filter(|_| rich) {
+        colors = Some(stepped_palette(&take(&mut colors), steps));
+    }
+    colors
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ScreenMode {
+    Alternate,
+    Inline,
+}
+
+#[cfg(windows)]
+pub fn enable_vt() -> bool {
+    crossterm::ansi_support::supports_ansi()
+}
+
+#[cfg(not(windows))]
+pub fn enable_vt() -> bool {
+    true
+}
+
+pub fn screen_mode(entered: io::Result<()>) -> ScreenMode {
+    match entered {
+        Ok(()) => ScreenMode::Alternate,
+        Err(err) => {
+            warn(&format!("alternate screen unavailable ({err}), rendering inline"));
+            ScreenMode::Inline
+        }
+    }
+}
+
+pub struct TerminalGuard<W: Write = Stdout> {
+    pub screen: ScreenMode,
+    mouse: bool,
+    out: W,
+}
+
+impl TerminalGuard {
+    pub fn enter(mouse: bool, inline: bool) -> io::Result<Self> {
+        enable_raw_mode()?;
+        let guard = TerminalGuard::setup(stdout(), mouse, inline)?;
+        restore_on_panic(stdout, guard.screen, guard.mouse);
+        Ok(guard)
+    }
+}
+
+/// Chains a panic hook that restores the terminal before the previous hook prints the message, which would
+/// otherwise land on the alternate screen in raw mode and vanish when the guard drops during unwinding.
+pub fn restore_on_panic<W: Write>(out: impl Fn() -> W + Send + Sync + 'static, screen: ScreenMode, mouse: bool) {
+    let previous = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        let _ = teardown(&mut out(), screen, mouse);
+        let _ = disable_raw_mode();
+        previous(info);
+    }));
+}
+
+impl<W: Write> TerminalGuard<W> {
+    pub fn setup(mut out: W, mouse: bool, inline: bool) -> io::Result<Self> {
+        let screen = if inline {
+            ScreenMode::Inline
+        } else {
+            screen_mode(execute!(out, EnterAlternateScreen))
+        };
+        let mut guard = TerminalGuard { screen, mouse: false, out };
+        if mouse {
+            execute!(guard.out, EnableMouseCapture)?;
+            guard.mouse = true;
+        }
+        Ok(guard)
+    }
+}
+
+pub fn teardown<W: Write>(out: &mut W, screen: ScreenMode, mouse: bool) -> io::Result<()> {
+    out.flush()?;
+    queue!(out, ResetColor, SetAttribute(Attribute::Reset), cursor::Show)?;
+    if mouse {
+        queue!(out, DisableMouseCapture)?;
+    }
+    if screen == ScreenMode::Alternate {
+        queue!(out, LeaveAlternateScreen)?;
+    }
+    out.flush()
+}
+
+impl<W: Write> Drop for TerminalGuard<W> {
+    fn drop(&mut self) {
+        let _ = teardown(&mut self.out, self.screen, self.mouse);
+        let _ = disable_raw_mode();
+    }
+}
+
+#[derive(Clone)]
+pub struct DisplayOptions {
+    pub smooth: bool,
+    pub sixel: bool,
+    pub image: Option<ImageProtocol>,
+    pub mono: bool,
+    pub no_clear: bool,
+    pub depth: ColorDepth,
+    pub palette_bias: bool,
+    pub palette: Option<Vec<Color>>,
+    pub banded: bool,
+    pub min_contrast: Option<f32>,
+    pub origin: (u16, u16),
+    pub screen: ScreenMode,
+}
+
+pub fn display_sixel<W: Write>(out: &mut W, canvas: &Canvas, style: &str) -> io::Result<()> {
+    let colors: Vec<_> = palette(style).into_iter().map(ansi_rgb).collect();
+    execute!(out, Print(encode_sixel(&canvas.intensity, &colors, SCALE_X, SCALE_Y)))?;
+    out.flush()
+}
+
+pub fn display_image<W: Write>(out: &mut W, canvas: &Canvas, style: &str, protocol: ImageProtocol) -> io::Result<()> {
+    let colors: Vec<_> = palette(style).into_iter().map(ansi_rgb).collect();
+    let png = render_png(&canvas.intensity, &colors, SCALE_X, SCALE_Y);
+    execute!(out, Print(encode_image(protocol, &png)))?;
+    out.flush()
+}
+
+pub fn clip_extent(width: usize, height: usize, terminal: (u16, u16), origin: (u16, u16)) -> (usize, usize) {
+    let cols = terminal.0.saturating_sub(origin.0) as usize;
+    let rows = terminal.1.saturating_sub(origin.1) as usize;
+    (width.min(cols), height.min(rows))
+}
+
+pub fn display_frame<W: Write>(out: &mut W, canvas: &Canvas, style: &str, display: &DisplayOptions) -> io::Result<()> {
+    let (left, top) = display.origin;
+    if display.sixel && !display.mono && !canvas.intensity.is_empty() {
+        execute!(out, cursor::MoveTo(left, top))?;
+        return display_sixel(out, canvas, style);
+    }
+    if let Some(protocol) = display.image.filter(|_| !display.mono && !canvas.intensity.is_empty()) {
+        execute!(out, cursor::MoveTo(left, top))?;
+        return display_image(out, canvas, style, protocol);
+    }
+    let inline = display.screen == ScreenMode::Inline;
+    if display.no_clear && !inline {
+        execute!(out, cursor::MoveTo(0, 0))?;
+    } else if !inline {
+        execute!(out, cursor::MoveTo(0, 0), Clear(ClearType::All))?;
+    }
+    let width = canvas.rows.iter().map(|row| row.chars().count()).max().unwrap_or(0);
+    let (width, visible) = clip_extent(width, canvas.rows.len(), size().ok().filter(|&(cols, rows)| cols > 0 && rows > 0).unwrap_or((u16::MAX, u16::MAX)), display.origin);
+    let visible = if inline { canvas.rows.len() } else { visible };
+    let colors: Vec<Color> = display
+        .palette
+        .clone()
+        .unwrap_or_else(|| palette(style))
+        .into_iter()
+        .map(|color| at_depth(color, display.depth))
+        .collect();
+    let attributes = if display.mono { &[][..] } else { style_spec(style).attributes };
+    for (idx, line) in canvas.rows.iter().enumerate().take(visible) {
+        let line: String = line.chars().take(width).collect();
+        if !inline {
+            execute!(out, cursor::MoveTo(left, top + idx as u16))?;
+        }
+        for attribute in attributes {
+            queue!(out, SetAttribute(*attribute))?;
+        }
+        let alert_row = idx == canvas.overlay_row && !canvas.alerts.is_empty();
+        let min_contrast = display.min_contrast.filter(|_| idx == canvas.overlay_row);
+        if display.mono {
+            execute!(out, Print(&line))?;
+        } else if display.palette_bias && !alert_row {
+            draw_cells(out, canvas, idx, width, min_contrast, |_, intensity| colors[biased_index(colors.len(), intensity, &canvas.inputs)])?;
+        } else if display.depth == ColorDepth::TrueColor && !alert_row && !canvas.intensity.is_empty() {
+            draw_cells(out, canvas, idx, width, min_contrast, |_, intensity| if display.banded { band(&colors, intensity) } else { gradient(&colors, intensity) })?;
+        } else if display.smooth && !alert_row {
+            draw_cells(out, canvas, idx, width, min_contrast, |x, intensity| {
+                let (lo, hi, frac) = palette_stops(colors.len(), intensity);
+                colors[dither_stop(lo, hi, frac, x, idx)]
+            })?;
+        } else {
+            let color = if alert_row {
+                at_depth(Color::Red, display.depth)
+            } else {
+                colors[idx % colors.len()]
+            };
+            if let Some(text) = min_contrast.and_then(|ratio| legible_text(color, ratio)) {
+                queue!(out, SetForegroundColor(text))?;
+            }
+            execute!(out, SetBackgroundColor(color), Print(&line), ResetColor)?;
+        }
+        if !attributes.is_empty() {
+            execute!(out, SetAttribute(Attribute::Reset))?;
+        }
+        if display.no_clear {
+            execute!(out, Print(" ".repeat(width - line.chars().count())))?;
+        }
+        if inline {
+            execute!(out, Print("\r\n"))?;
+        }
+    }
+    out.flush()?;
+    Ok(())
+}
+
+fn draw_cells<W: Write, F: Fn(usize, f32) -> Color>(out: &mut W, canvas: &Canvas, y: usize, cols: usize, min_contrast: Option<f32>, pick: F) -> io::Result<()> {
+    let mut current = None;
+    let values = canvas.intensity.get(y).map_or(&[][..], Vec::as_slice);
+    for (x, ch) in canvas.rows[y].chars().take(cols).enumerate() {
+        let color = pick(x, values.get(x).copied().unwrap_or(0.0));
+        if current != Some(color) {
+            queue!(out, SetBackgroundColor(color))?;
+            if let Some(ratio) = min_contrast {
+                queue!(out, SetForegroundColor(legible_text(color, ratio).unwrap_or(Color::Reset)))?;
+            }
+            current = Some(color);
+        }
+        queue!(out, Print(ch))?;
+    }
+    queue!(out, ResetColor)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        metrics::{MetricsSource, MockSource},
+        render::{render_frame, RenderOptions},
+    };
+    use crossterm::{
+        event::{MouseEvent, MouseEventKind},
+        Command,
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+    use std::{
+        collections::BTreeMap,
+        sync::{Arc, Mutex},
+    };
+
+    fn ansi(command: impl Command) -> String {
+        let mut text = String::new();
+        command.write_ansi(&mut text).unwrap();
+        text
+    }
+
+    fn canvas() -> Canvas {
+        render_frame(&MockSource::new(3).sample(), &mut StdRng::seed_from_u64(3), &RenderOptions::new(12, 4, "plasma"))
+    }
+
+    fn options() -> DisplayOptions {
+        DisplayOptions {
+            smooth: false,
+            sixel: false,
+            image: None,
+            mono: false,
+            no_clear: false,
+            depth: ColorDepth::TrueColor,
+            palette_bias: false,
+            palette: None,
+            banded: false,
+            min_contrast: None,
+            origin: (0, 0),
+            screen: ScreenMode::Alternate,
+        }
+    }
+
+    fn frame(canvas: &Canvas, display: &DisplayOptions) -> String {
+        let mut out = Vec::new();
+        display_frame(&mut out, canvas, "plasma", display).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn guard_drop_restores_the_terminal() {
+        let mut out = Vec::new();
+        drop(TerminalGuard::setup(&mut out, true, false).unwrap());
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.starts_with(&ansi(EnterAlternateScreen)));
+        for command in [ansi(cursor::Show), ansi(DisableMouseCapture), ansi(LeaveAlternateScreen)] {
+            assert!(text.contains(&command), "missing {command:?} in {text:?}");
+        }
+    }
+
+    #[test]
+    fn teardown_resets_before_leaving_the_screen() {
+        let mut out = Vec::new();
+        teardown(&mut out, ScreenMode::Alternate, true).unwrap();
+        let expected = [ansi(ResetColor), ansi(SetAttribute(Attribute::Reset)), ansi(cursor::Show), ansi(DisableMouseCapture), ansi(LeaveAlternateScreen)].concat();
+        assert_eq!(String::from_utf8(out).unwrap(), expected);
+    }
+
+    #[test]
+    fn mono_emits_no_colour_escapes() {
+        let canvas = canvas();
+        assert!(frame(&canvas, &options()).contains("\x1b[48;"));
+        let text = frame(&canvas, &DisplayOptions { mono: true, ..options() });
+        assert!(!text.contains("\x1b[38;") && !text.contains("\x1b[48;"), "{text:?}");
+        assert!(canvas.rows.iter().all(|row| text.contains(row.as_str())));
+    }
+
+    #[test]
+    fn inline_mode_never_clears() {
+        let canvas = canvas();
+        let clear = ansi(Clear(ClearType::All));
+        assert!(frame(&canvas, &options()).contains(&clear));
+        let text = frame(&canvas, &DisplayOptions { screen: ScreenMode::Inline, ..options() });
+        assert!(!text.contains(&clear));
+        assert_eq!(text.matches("\r\n").count(), canvas.rows.len());
+    }
+
+    #[test]
+    fn no_clear_pads_short_rows_instead_of_clearing() {
+        let mut canvas = canvas();
+        canvas.rows[1] = "abcde".to_string();
+        let text = frame(&canvas, &DisplayOptions { no_clear: true, mono: true, ..options() });
+        for kind in [ClearType::All, ClearType::FromCursorDown, ClearType::CurrentLine, ClearType::UntilNewLine] {
+            assert!(!text.contains(&ansi(Clear(kind))), "{kind:?} in {text:?}");
+        }
+        assert!(text.contains(&format!("abcde{}", " ".repeat(7))));
+    }
+
+    #[test]
+    fn inline_guard_never_touches_the_alternate_screen() {
+        let mut out = Vec::new();
+        let guard = TerminalGuard::setup(&mut out, false, true).unwrap();
+        assert_eq!(guard.screen, ScreenMode::Inline);
+        drop(guard);
+        let text = String::from_utf8(out).unwrap();
+        assert!(!text.contains(&ansi(EnterAlternateScreen)) && !text.contains(&ansi(LeaveAlternateScreen)), "{text:?}");
+    }
+
+    #[test]
+    fn m_toggles_the_metrics_freeze() {
+        let mut state = LiveState::default();
+        let press = KeyEvent::new(KeyCode::Char('m'), KeyModifiers::NONE);
+        state.handle_key(press);
+        assert!(state.frozen && !state.quit);
+        state.handle_key(press);
+        assert!(!state.frozen);
+        state.handle_key(KeyEvent::new(KeyCode::Char('x'), KeyModifiers::NONE));
+        assert!(!state.frozen);
+    }
+
+    #[test]
+    fn any_input_event_ends_the_screensaver() {
+        let key = Event::Key(KeyEvent::new(KeyCode::Char('a'), KeyModifiers::NONE));
+        let mouse = Event::Mouse(MouseEvent { kind: MouseEventKind::Moved, column: 3, row: 4, modifiers: KeyModifiers::NONE });
+        for event in [key, mouse, Event::Paste("hi".to_string())] {
+            assert!(is_input_event(&event));
+        }
+        for event in [Event::Resize(80, 24), Event::FocusGained, Event::FocusLost] {
+            assert!(!is_input_event(&event));
+        }
+    }
+
+    #[test]
+    fn a_failed_alternate_screen_falls_back_to_inline() {
+        assert_eq!(screen_mode(Ok(())), ScreenMode::Alternate);
+        assert_eq!(screen_mode(Err(io::Error::new(io::ErrorKind::Unsupported, "no smcup"))), ScreenMode::Inline);
+    }
+
+    #[test]
+    fn bold_styles_emit_and_reset_the_attribute_per_row() {
+        let canvas = canvas();
+        let mut out = Vec::new();
+        display_frame(&mut out, &canvas, "ember", &options()).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        let bold = ansi(SetAttribute(Attribute::Bold));
+        assert_eq!(text.matches(&bold).count(), canvas.rows.len());
+        assert!(text.split(&bold).skip(1).all(|row| row.contains(&ansi(SetAttribute(Attribute::Reset)))));
+        assert!(!frame(&canvas, &options()).contains(&bold));
+    }
+
+    #[test]
+    fn remapped_keys_dispatch_their_action() {
+        let overrides = BTreeMap::from([(Action::Freeze, vec!["f".to_string()]), (Action::Quit, vec!["x".to_string()])]);
+        let mut state = LiveState { keys: KeyMap::with_overrides(&overrides).unwrap(), ..LiveState::default() };
+        let press = |code: KeyCode| KeyEvent::new(code, KeyModifiers::NONE);
+        state.handle_key(press(KeyCode::Char('m')));
+        assert!(!state.frozen);
+        state.handle_key(press(KeyCode::Char('f')));
+        assert!(state.frozen);
+        state.handle_key(press(KeyCode::Char('q')));
+        assert!(!state.quit);
+        state.handle_key(press(KeyCode::Char('x')));
+        assert!(state.quit);
+    }
+
+    #[test]
+    fn oversized_canvases_clip_to_the_terminal() {
+        assert_eq!(clip_extent(200, 60, (80, 24), (0, 0)), (80, 24));
+        assert_eq!(clip_extent(200, 60, (80, 24), (10, 4)), (70, 20));
+        assert_eq!(clip_extent(40, 10, (80, 24), (10, 4)), (40, 10));
+        assert_eq!(clip_extent(40, 10, (80, 24), (90, 30)), (0, 0));
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn unix_consoles_always_take_the_vt_path() {
+        assert!(enable_vt());
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn windows_vt_decision_follows_ansi_support() {
+        assert_eq!(enable_vt(), crossterm::ansi_support::supports_ansi());
+    }
+
+    #[test]
+    fn four_palette_steps_draw_at_most_four_truecolor_backgrounds() {
+        let effects = PaletteEffects { steps: Some(4), ..PaletteEffects::default() };
+        let palette = apply_palette_effects(None, "plasma", ColorDepth::TrueColor, effects);
+        let canvas = render_frame(&MockSource::new(3).sample(), &mut StdRng::seed_from_u64(3), &RenderOptions::new(40, 6, "plasma"));
+        let text = frame(&canvas, &DisplayOptions { palette, banded: true, ..options() });
+        let mut backgrounds: Vec<&str> = text.split("\x1b[48;2;").skip(1).map(|rest| &rest[..rest.find('m').unwrap()]).collect();
+        backgrounds.sort_unstable();
+        backgrounds.dedup();
+        assert!((2..=4).contains(&backgrounds.len()), "{backgrounds:?}");
+    }
+
+    #[test]
+    fn idle_starts_once_the_timeout_passes_without_input() {
+        let timeout = Some(Duration::from_secs(60));
+        assert!(!is_idle(Duration::from_secs(59), timeout));
+        assert!(is_idle(Duration::from_secs(60), timeout));
+        assert!(is_idle(Duration::from_secs(600), timeout));
+        assert!(!is_idle(Duration::from_secs(600), None));
+    }
+
+    #[test]
+    fn forced_mono_pins_the_depth_cycle() {
+        let press = KeyEvent::new(KeyCode::Char('c'), KeyModifiers::NONE);
+        let mut state = LiveState { depth: ColorDepth::Mono, mono_locked: true, ..LiveState::default() };
+        for _ in 0..4 {
+            state.handle_key(press);
+            assert_eq!(state.depth, ColorDepth::Mono);
+        }
+        assert_eq!(state.depth_notice, DEPTH_NOTICE_FRAMES);
+        let mut free = LiveState { depth: ColorDepth::Mono, ..LiveState::default() };
+        free.handle_key(press);
+        assert_ne!(free.depth, ColorDepth::Mono);
+    }
+
+    #[derive(Clone, Default)]
+    struct Shared(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Shared {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn a_panic_restores_the_terminal_before_the_message() {
+        let out = Shared::default();
+        let writer = out.clone();
+        restore_on_panic(move || writer.clone(), ScreenMode::Alternate, true);
+        let caught = panic::catch_unwind(|| panic!("boom"));
+        drop(panic::take_hook());
+        assert!(caught.is_err());
+        let text = String::from_utf8(out.0.lock().unwrap().clone()).unwrap();
+        for command in [ansi(cursor::Show), ansi(DisableMouseCapture), ansi(LeaveAlternateScreen)] {
+            assert!(text.contains(&command), "missing {command:?} in {text:?}");
+        }
+    }
+
+    #[test]
+    fn truecolor_shades_each_cell_and_sixteen_colours_one_block_per_row() {
+        let canvas = render_frame(&MockSource::new(3).sample(), &mut StdRng::seed_from_u64(3), &RenderOptions { overlay: false, ..RenderOptions::new(12, 4, "plasma") });
+        let colors = palette("plasma");
+        let text = frame(&canvas, &options());
+        for (y, values) in canvas.intensity.iter().enumerate() {
+            let start = text.find(&ansi(cursor::MoveTo(0, y as u16))).unwrap();
+            let end = text.find(&ansi(cursor::MoveTo(0, y as u16 + 1))).unwrap_or(text.len());
+            let drawn: Vec<&str> = text[start..end].split("\x1b[48;2;").skip(1).map(|rest| &rest[..rest.find('m').unwrap()]).collect();
+            let mut expected: Vec<String> = values.iter().map(|&intensity| ansi_rgb(gradient(&colors, intensity))).map(|(r, g, b)| format!("{r};{g};{b}")).collect();
+            expected.dedup();
+            assert!(expected.len() > 1, "row {y} is one flat colour");
+            assert_eq!(drawn, expected, "row {y}");
+        }
+        let sixteen = frame(&canvas, &DisplayOptions { depth: ColorDepth::Ansi16, ..options() });
+        assert!(!sixteen.contains("\x1b[48;2;"));
+        assert_eq!(sixteen.matches("\x1b[48;5;").count(), canvas.rows.len());
+    }
+}
@@ -0,0 +1,46 @@
+use serde::Serialize;
+use std::{
+    io::{stderr, IsTerminal},
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+static LOG_JSON: AtomicBool = AtomicBool::new(false);
+
+#[derive(Serialize)]
+pub struct LogLine<'a> {
+    pub level: &'a str,
+    pub message: &'a str,
+}
+
+pub fn set_log_json(enabled: bool) {
+    LOG_JSON.store(enabled, Ordering::Relaxed);
+}
+
+pub fn format_log(level: &str, message: &str, json: bool, interactive: bool) -> String {
+    if json {
+        serde_json::to_string(&LogLine { level, message }).unwrap_or_default()
+    } else if interactive {
+        format!("{level}: {message}")
+    } else {
+        format!("level={level} message={message:?}")
+    }
+}
+
+pub fn warn(message: &str) {
+    eprintln!("{}", format_log("warning", message, LOG_JSON.load(Ordering::Relaxed), stderr().is_terminal()));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_warnings_parse_with_level_and_message() {
+        let line = format_log("warning", "palette \"x\" missing", true, true);
+        let value: serde_json::Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(value["level"], "warning");
+        assert_eq!(value["message"], "palette \"x\" missing");
+        assert_eq!(format_log("warning", "slow", false, false), "level=warning message=\"slow\"");
+        assert_eq!(format_log("warning", "slow", false, true), "warning: slow");
+    }
+}
@@ -1,296 +1,376 @@
-use clap::{Parser, Subcommand};
-use crossterm::{cursor, execute, style::{Color, Print, ResetColor, SetBackgroundColor}, terminal::{Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode}};
-use indicatif::{ProgressBar, ProgressStyle};
-use rand::{rngs::StdRng, Rng, SeedableRng};
-use serde::Serialize;
-use std::{cmp::min, io::{stdout, Write}, thread, time::Duration};
-use sysinfo::{CpuExt, DiskExt, NetworkExt, System, SystemExt};
-
-const LINE_LIMIT: usize = 500;
-
-#[derive(Parser)]
-#[command(name = "procgen-art", about = "Terminal generative art driven by system metrics", version)]
-struct Args {
-    #[arg(long, default_value_t = 500, help = "Frame interval in milliseconds"]
-    interval: u64,
-    #[arg(long, default_value = "plasma", value_parser = ["plasma", "waves", "ember"], help = "Art style")]
-    style: String,
-    #[arg(long, help = "Render once and exit")]
-    once: bool,
-    #[arg(long, help = "Output JSON snapshot instead of live art")]
-    json: bool,
-    #[arg(long, default_value_t = 80, help = "Width of canvas")]
-    width: u16,
-    #[arg(long, default_value_t = 24, help = "Height of canvas")]
-    height: u16,
-    #[arg(long, help = "Seed override for deterministic art")]
-    seed: Option<u64>,
-    #[arg(long, help = "Enforce 500-line executable budget")]
-    strict: bool,
-}
+mod budget;
+mod cli;
+mod commands;
+mod options;
+mod sinks;
 
-#[derive(Subcommand)]
-enum Command {
-    #[command(about = "Launch the generative art canvas")]
-    Run,
-    #[command(about = "Print current system metrics in JSON")]
-    Metrics,
-}
-
-#[derive(Serialize, Clone)]
-struct Metrics {
-    cpu_usage: f32,
-    load_avg: f64,
-    total_memory: u64,
-    used_memory: u64,
-    disk_usage: Vec<DiskMetrics>,
-    network_rx: u64,
-    network_tx: u64,
-    entropy: u64,
-}
-
-#[derive(Serialize, Clone)]
-struct DiskMetrics {
-    name: String,
-    total_space: u64,
-    available_space: u64,
-}
-
-#[derive(Serialize)]
-struct Snapshot {
-    metrics: Metrics,
-    frame: Vec<String>,
-    width: u16,
-    height: u16,
-    style: String,
-}
-
-fn ensure_line_budget() {
-    if count_executable_lines(include_str!("main.rs")) > LINE_LIMIT {
-        eprintln!("warning: executable lines exceeded {}", LINE_LIMIT);
-        std::process::exit(3);
-    }
-}
-
-fn count_executable_lines(source: &str) -> usize {
-    source
-        .lines()
-        .filter(|line| {
-            let trimmed = line.trim();
-            !trimmed.is_empty() && !trimmed.starts_with("//")
-        })
-        .count()
-}
-
-fn gather_metrics(sys: &mut System) -> Metrics {
-    sys.refresh_cpu();
-    sys.refresh_memory();
-    sys.refresh_disks_list();
-    sys.refresh_networks();
-
-    let cpu_usage = sys.global_cpu_info().cpu_usage();
-    let load_avg = sys.load_average().one;
-    let total_memory = sys.total_memory();
-    let used_memory = sys.used_memory();
-    let network_rx = sys
-        .networks()
-        .values()
-        .map(|data| data.received())
-        .sum();
-    let network_tx = sys
-        .networks()
-        .values()
-        .map(|data| data.transmitted())
-        .sum();
-    let disk_usage = sys
-        .disks()
-        .iter()
-        .map(|disk| DiskMetrics {
-            name: disk.name().to_string_lossy().to_string(),
-            total_space: disk.total_space(),
-            available_space: disk.available_space(),
-        })
-        .collect();
-
-    let entropy = (cpu_usage * 100.0) as u64
-        + used_memory
-        + network_rx
-        + network_tx
-        + disk_usage.iter().map(|disk| disk.total_space()).sum::<u64>();
+use budget::{ensure_line_budget, LINE_LIMIT};
+use clap::{parser::ValueSource, ArgMatches, CommandFactory, FromArgMatches};
+use cli::{Args, Command};
+use commands::{
+    run_apng, run_ascii_art, run_bench, run_calibrate, run_compare_seeds, run_config, run_gauge, run_metrics, run_playback, run_seamless, run_seed_grid, run_snapshot, run_stream, run_summary, run_transition, run_validate,
+};
+use crossterm::{
+    event::{self, Event},
+    style::Color,
+    terminal,
+};
+use indicatif::{ProgressBar, ProgressStyle};
+use options::{apply_style_defaults, capped_size, capture, decorate, flow_for, render_options, rng_state, seeded_source, validate_args};
+use procgen_art::{
+    anomaly::{hold_ticks, mark_anomalies, AnomalyDetector},
+    background::BackgroundSource,
+    braille::History,
+    burnin::{burnin_brightness, burnin_offset},
+    color::{crossfade, dominant_tint, palette_dump, ColorDepth},
+    demoscene::demoscene_phase,
+    disks::DISK_STYLE,
+    fps::{stamp_fps, FpsMeter},
+    graphics::ImageProtocol,
+    keymap::KeyMap,
+    letterbox::letterbox,
+    live::{apply_palette_effects, display_frame, enable_vt, is_idle, is_input_event, plot_frame, stamp_sanitized, DisplayOptions, LiveState, PaletteEffects, ScreenMode, TerminalGuard, INTERPOLATE_STEPS, SCREENSAVER_INTERVAL_MS},
+    log::{set_log_json, warn},
+    metrics::{gather_metrics, is_busy, lerp_metrics, MetricsSource, MetricsSummary, NormalizedMetrics},
+    phase::{phase_tick, unix_millis, until_next_tick},
+    pulse::pulse_multiplier,
+    render::{effective_ramp, morph_key, palette, Canvas, Morph},
+    scene::{draw, Scene},
+    seedfile::SeedWatch,
+    sixel::sixel_supported,
+    snapshot::{print_snapshot, Snapshot},
+    solid::SOLID_STYLE,
+    styles::{auto_palette, next_style},
+    summary::Thresholds,
+    termenv::detect_depth,
+    theme::{set_theme_palette, Theme},
+    trails::apply_trails,
+};
+use rand::{rngs::StdRng, SeedableRng};
+use sinks::Sinks;
+use std::{
+    io::{self, stdout},
+    time::{Duration, Instant, SystemTime},
+};
 
-    Metrics {
-        cpu_usage,
-        load_avg,
-        total_memory,
-        used_memory,
-        disk_usage,
-        network_rx,
-        network_tx,
-        entropy,
+fn run_live(args: &Args) -> io::Result<()> {
+    let (source, mut latest, mut seed) = seeded_source(args)?;
+    let mut rng = StdRng::seed_from_u64(seed);
+    let sixel = args.sixel && sixel_supported();
+    if args.sixel && !sixel {
+        warn("terminal does not look Sixel-capable, using ASCII");
     }
-}
-
-fn palette(style: &str) -> Vec<Color> {
-    match style {
-        "waves" => vec![Color::Blue, Color::Cyan, Color::Black],
-        "ember" => vec![Color::DarkRed, Color::Red, Color::DarkYellow, Color::Yellow],
-        _ => vec![Color::Magenta, Color::DarkMagenta, Color::Blue, Color::Black],
+    let image = args.image_protocol.and_then(ImageProtocol::resolve);
+    if args.image_protocol.is_some() && image.is_none() {
+        warn("no inline image protocol detected, using ASCII");
     }
-}
-
-fn art_char(style: &str, intensity: f32) -> char {
-    let ramps = match style {
-        "waves" => " .-~*~=",
-        "ember" => "`^";",
-        _ => " .:+*#%@",
-    };
-    let idx = ((intensity.clamp(0.0, 1.0)) * (ramps.len() as f32 - 1.0)).round() as usize;
-    ramps.chars().nth(min(idx, ramps.len() - 1)).unwrap_or('*')
-}
-
-fn render_frame(metrics: &Metrics, rng: &mut StdRng, width: u16, height: u16, style: &str) -> Vec<String> {
-    let colors = palette(style);
-    let mut frame = Vec::with_capacity(height as usize);
-    let cpu = metrics.cpu_usage / 100.0;
-    let memory = metrics.used_memory as f32 / metrics.total_memory as f32;
-    let network = ((metrics.network_rx + metrics.network_tx) as f32).ln().max(0.0) / 15.0;
-    let base_seed = metrics.entropy;
-
-    for y in 0..height {
-        let mut row = String::with_capacity(width as usize);
-        for x in 0..width {
-            let noise = rng.gen::<f32>();
-            let swirl = ((x as f32 / width as f32) * cpu + (y as f32 / height as f32) * memory + noise * network).sin();
-            let intensity = ((swirl + 1.0) / 2.0 * memory + cpu).fract();
-            row.push(art_char(style, intensity));
+    if let Some((columns, rows)) = terminal::size().ok().filter(|&(columns, rows)| columns > 0 && rows > 0) {
+        if args.panels.is_none() && args.letterbox.is_none() && (args.width > columns || args.height > rows) {
+            warn(&format!("{}x{} canvas is larger than the {columns}x{rows} terminal, clipping", args.width, args.height));
         }
-        frame.push(row);
     }
-
-    let mut overlay = frame.clone();
-    if !overlay.is_empty() {
-        let text = format!(
-            "CPU {:>5.1}% | MEM {:>5.1}% | NET {:>7.1}k/s",
-            metrics.cpu_usage,
-            memory * 100.0,
-            (metrics.network_rx + metrics.network_tx) as f32 / 1024.0
-        );
-        let y = (height / 2) as usize;
-        let start = if text.len() < width as usize {
-            (width as usize - text.len()) / 2
-        } else {
-            0
-        };
-        if y < overlay.len() {
-            let line = &mut overlay[y];
-            for (i, ch) in text.chars().enumerate() {
-                if start + i < line.len() {
-                    line.replace_range(start + i..start + i + 1, &ch.to_string());
-                }
-            }
-        }
+    let plain = !enable_vt();
+    if plain {
+        warn("console does not accept VT escape codes, rendering plain inline frames");
     }
+    let depth = args.color.resolve(detect_depth());
+    let mono = args.mono || plain || depth == ColorDepth::Mono;
+    let terminal = TerminalGuard::enter(args.screensaver, args.inline || plain)?;
+    let mut display = DisplayOptions {
+        smooth: args.smooth,
+        sixel,
+        image,
+        mono,
+        no_clear: args.no_clear,
+        depth,
+        palette_bias: args.palette_bias,
+        palette: None,
+        banded: args.palette_steps.is_some(),
+        min_contrast: args.min_contrast,
+        origin: (0, 0),
+        screen: terminal.screen,
+    };
+    let interval = if args.screensaver {
+        args.interval.max(SCREENSAVER_INTERVAL_MS)
+    } else {
+        args.interval
+    };
+    let spinner = if display.screen == ScreenMode::Inline {
+        ProgressBar::hidden()
+    } else {
+        ProgressBar::new_spinner()
+    };
+    let template = if mono { "{spinner} {msg}" } else { "{spinner:.green} {msg}" };
+    spinner.set_style(ProgressStyle::with_template(template).unwrap());
+    spinner.set_message("Generating art...");
 
-    if base_seed % 7 == 0 && !overlay.is_empty() {
-        overlay[0] = format!("Style: {style} | Frames seeded by entropy {base_seed}");
+    let mut opts = render_options(args)?;
+    let mut scene = Scene::new(seed, args.panels);
+    if args.panels.is_some() {
+        let (width, height) = terminal::size()?;
+        (opts.width, opts.height) = capped_size(args, width, height);
     }
-
-    overlay
-}
-
-fn display_frame(frame: &[String], style: &str) -> crossterm::Result<()> {
-    execute!(stdout(), cursor::MoveTo(0, 0), Clear(ClearType::All))?;
-    let colors = palette(style);
-    for (idx, line) in frame.iter().enumerate() {
-        let color = colors[idx % colors.len()];
-        execute!(
-            stdout(),
-            SetBackgroundColor(color),
-            cursor::MoveTo(0, idx as u16),
-            Print(line),
-            ResetColor
-        )?;
+    if let Some(ratio) = args.letterbox {
+        let (width, height) = terminal::size()?;
+        let (left, top, inner_width, inner_height) = letterbox(width, height, ratio);
+        (opts.width, opts.height) = capped_size(args, inner_width, inner_height);
+        display.origin = (left, top);
     }
-    stdout().flush()?;
-    Ok(())
-}
-
-fn snapshot(metrics: Metrics, frame: Vec<String>, width: u16, height: u16, style: &str) {
-    let payload = Snapshot {
-        metrics,
-        frame,
-        width,
-        height,
-        style: style.to_string(),
+    let mut seed_watch = args.seed_from_file.as_deref().map(SeedWatch::new);
+    let mut history = History::new(opts.width as usize * 2);
+    let mut summaries = args.history.map(|depth| History::new(depth as usize));
+    let mut state = LiveState {
+        depth: if mono { ColorDepth::Mono } else { depth },
+        mono_locked: args.mono || plain,
+        keys: args.keymap.as_deref().map_or_else(|| Ok(KeyMap::default()), KeyMap::load)?,
+        ..LiveState::default()
     };
-    println!("{}", serde_json::to_string_pretty(&payload).unwrap());
-}
-
-fn run_live(args: &Args) -> crossterm::Result<()> {
-    let mut sys = System::new_all();
-    let mut rng = match args.seed {
-        Some(seed) => StdRng::seed_from_u64(seed),
-        None => StdRng::seed_from_u64(gather_metrics(&mut sys).entropy),
+    let mut tick = args.start_tick.unwrap_or(0);
+    let mut final_snapshot = None;
+    let mut trail = Vec::new();
+    let mut fade: Option<(Vec<Color>, u8)> = None;
+    let mut fps = FpsMeter::default();
+    let mut anomalies = AnomalyDetector::default();
+    let mut held: Option<Canvas> = None;
+    let mut spike_hold: u32 = 0;
+    let mut rotated_at = Instant::now();
+    let (started, origin) = (Instant::now(), display.origin);
+    let mut morph_tick = 0;
+    let mut last_input = Instant::now();
+    let steps = if args.interpolate { INTERPOLATE_STEPS } else { 1 };
+    let frame_interval = (interval / steps).max(1);
+    let mut sinks = Sinks::open(args, frame_interval)?;
+    let mut sys: Box<dyn MetricsSource> = if args.simulate || args.metrics_csv.is_some() {
+        source
+    } else {
+        Box::new(BackgroundSource::spawn(source, latest.clone(), Duration::from_millis(interval)))
     };
-    enable_raw_mode()?;
-    execute!(stdout(), EnterAlternateScreen)?;
-    let mut spinner = ProgressBar::new_spinner();
-    spinner.set_style(ProgressStyle::with_template("{spinner:.green} {msg}").unwrap());
-    spinner.set_message("Generating art...");
-
+    let (mut previous, mut phase) = (latest.clone(), steps);
     loop {
-        let metrics = gather_metrics(&mut sys);
-        let frame = render_frame(&metrics, &mut rng, args.width, args.height, &args.style);
-        display_frame(&frame, &args.style)?;
+        let metrics = if phase < steps {
+            lerp_metrics(&previous, &latest, phase as f32 / steps as f32)
+        } else {
+            latest.clone()
+        };
+        if let Some(next) = seed_watch.as_mut().and_then(SeedWatch::poll) {
+            seed = next;
+            rng = StdRng::seed_from_u64(seed);
+            scene.panels = Scene::new(seed, args.panels).panels;
+        }
+        opts.frozen = state.frozen;
+        opts.pan = (args.pan_x + state.pan.0 / args.zoom, args.pan_y + state.pan.1 / args.zoom);
+        if args.phase_lock {
+            tick = phase_tick(unix_millis(SystemTime::now()), frame_interval);
+        }
+        let state_now = rng_state(args, seed, tick);
+        if let Some(state_now) = state_now {
+            rng = state_now.rng();
+        }
+        if args.demoscene {
+            rng = StdRng::seed_from_u64(seed);
+            opts.loop_phase = Some(demoscene_phase(tick));
+        }
+        if let Some(target) = args.morph_to {
+            if morph_tick < args.morph_frames {
+                opts.morph = Some(Morph { key: morph_key(target), t: morph_tick as f32 / args.morph_frames as f32 });
+                morph_tick += 1;
+            } else if opts.morph.take().is_some() {
+                seed = target;
+                rng = StdRng::seed_from_u64(seed);
+            }
+        }
+        opts.flow = flow_for(args, seed, tick).filter(|_| opts.morph.is_none() && opts.loop_phase.is_none());
+        if args.pulse {
+            opts.brightness = pulse_multiplier(tick, metrics.load_avg);
+        }
+        if args.anti_burnin {
+            let elapsed = started.elapsed().as_secs();
+            let (dx, dy) = burnin_offset(elapsed, args.burnin_shift, args.burnin_period);
+            display.origin = (origin.0 + dx, origin.1 + dy);
+            let base = if args.pulse { opts.brightness } else { 1.0 };
+            opts.brightness = base * burnin_brightness(elapsed, args.burnin_shift, args.burnin_period);
+        }
+        let frame_start = Instant::now();
+        if let Some(summaries) = summaries.as_mut() {
+            summaries.push(MetricsSummary::from_metrics(&metrics));
+        }
+        let recent = summaries.as_ref().map(History::samples);
+        let peak = anomalies.peak(&metrics);
+        let flagged = anomalies.check(&metrics, args.anomaly_sigma);
+        let holding = spike_hold > 0;
+        spike_hold = spike_hold.saturating_sub(1);
+        let still = held.take().filter(|_| holding || !is_busy(&metrics, args.only_when_busy));
+        let mut canvas = match (still, args.braille_plot) {
+            (Some(still), _) => still,
+            (None, Some(metric)) => {
+                let inputs = NormalizedMetrics::from_metrics(&metrics);
+                history.push(metric.sample(&inputs));
+                let span = Some(frame_interval as f32 * 2.0 / 1000.0).filter(|_| args.axis);
+                plot_frame(&history, metric, inputs, opts.width, opts.height, span, args.legend)
+            }
+            (None, None) => draw(&metrics, &mut rng, &mut scene, &opts),
+        };
+        if args.only_when_busy.is_some() || args.hold_on_spike.is_some() {
+            held = Some(canvas.clone());
+        }
+        if let Some(base) = args.hold_on_spike.filter(|_| !holding) {
+            spike_hold = hold_ticks(peak, args.anomaly_sigma, base);
+        }
+        if let Some(decay) = args.trails.filter(|decay| *decay > 0.0) {
+            let inputs = canvas.inputs;
+            apply_trails(&mut canvas, &trail, decay, effective_ramp(&opts, &inputs));
+            trail.clone_from(&canvas.intensity);
+        }
+        decorate(&mut canvas, args);
+        if args.highlight_anomalies {
+            mark_anomalies(&mut canvas, &flagged);
+        }
+        fps.tick(Instant::now());
+        if args.show_fps {
+            stamp_fps(&mut canvas, &fps.label());
+        }
+        if args.debug {
+            stamp_sanitized(&mut canvas, opts.width as usize);
+        }
+        display.depth = state.depth;
+        display.smooth = args.smooth || [DISK_STYLE, SOLID_STYLE].contains(&opts.style.as_str());
+        let base = auto_palette(metrics.temperature).filter(|_| args.auto_palette);
+        display.palette = base.map(<[Color]>::to_vec);
+        if let Some((from, elapsed)) = fade.take() {
+            if display.depth == ColorDepth::TrueColor && elapsed < args.fade_ticks {
+                let to = base.map_or_else(|| palette(&opts.style), <[Color]>::to_vec);
+                display.palette = Some(crossfade(&from, &to, (elapsed + 1) as f32 / args.fade_ticks as f32));
+                fade = Some((from, elapsed + 1));
+            }
+        }
+        let idle = is_idle(last_input.elapsed(), args.idle_timeout.map(Duration::from_secs));
+        let effects = PaletteEffects {
+            demoscene: Some(tick).filter(|_| args.demoscene),
+            idle: Some(tick).filter(|_| idle),
+            tint: dominant_tint(&canvas.inputs).filter(|_| args.tint),
+            steps: args.palette_steps,
+        };
+        display.palette = apply_palette_effects(display.palette.take(), &opts.style, display.depth, effects);
+        display.mono = state.depth == ColorDepth::Mono;
+        state.stamp_notice(&mut canvas);
+        display_frame(&mut stdout(), &canvas, &opts.style, &display)?;
+        let snapshot = || Snapshot { rng: state_now, history: recent.clone(), ..capture(args, &metrics, &canvas, &opts) };
+        sinks.write(&metrics, &canvas, &opts.style, snapshot, tick, frame_start.elapsed().as_micros() as u64)?;
         spinner.tick();
         if args.once {
-            snapshot(metrics, frame, args.width, args.height, &args.style);
+            final_snapshot = Some(snapshot());
+            break;
+        }
+        let wait = if args.phase_lock {
+            until_next_tick(unix_millis(SystemTime::now()), frame_interval)
+        } else if idle {
+            Duration::from_millis(frame_interval.max(SCREENSAVER_INTERVAL_MS))
+        } else {
+            Duration::from_millis(frame_interval)
+        };
+        tick = tick.wrapping_add(1);
+        if event::poll(wait)? {
+            let event = event::read()?;
+            if args.screensaver && is_input_event(&event) {
+                break;
+            }
+            if let Event::Key(key) = event {
+                last_input = Instant::now();
+                state.handle_key(key);
+            }
+        }
+        if state.quit {
             break;
         }
-        thread::sleep(Duration::from_millis(args.interval));
+        if args.rotate_styles.is_some_and(|secs| rotated_at.elapsed() >= Duration::from_secs(secs)) {
+            state.switch_style = true;
+        }
+        if std::mem::take(&mut state.switch_style) {
+            rotated_at = Instant::now();
+            let from = display.palette.clone().unwrap_or_else(|| palette(&opts.style));
+            opts.style = next_style(&opts.style).to_string();
+            fade = Some((from, 0));
+        }
+        if !state.frozen {
+            phase += 1;
+            if phase > steps {
+                previous = std::mem::replace(&mut latest, gather_metrics(sys.as_mut()));
+                phase = 1;
+            }
+        }
     }
 
     spinner.finish_and_clear();
-    execute!(stdout(), LeaveAlternateScreen)?;
-    disable_raw_mode()?;
-    Ok(())
-}
-
-fn run_snapshot(args: &Args) -> crossterm::Result<()> {
-    let mut sys = System::new_all();
-    let mut rng = match args.seed {
-        Some(seed) => StdRng::seed_from_u64(seed),
-        None => StdRng::seed_from_u64(gather_metrics(&mut sys).entropy),
-    };
-    let metrics = gather_metrics(&mut sys);
-    let frame = render_frame(&metrics, &mut rng, args.width, args.height, &args.style);
-    snapshot(metrics, frame, args.width, args.height, &args.style);
+    drop(terminal);
+    if let Some(payload) = &final_snapshot {
+        print_snapshot(payload, args.compact, args.json_case)?;
+    }
     Ok(())
 }
 
-fn run_metrics() {
-    let mut sys = System::new_all();
-    let metrics = gather_metrics(&mut sys);
-    println!("{}", serde_json::to_string_pretty(&metrics).unwrap());
+fn dispatch(mut args: Args, matches: &ArgMatches) -> io::Result<()> {
+    let checked = validate_args(&args).map_err(io::Error::other);
+    if args.check {
+        return checked.map(|()| println!("OK"));
+    }
+    checked?;
+    if let Some(path) = &args.theme {
+        let theme = Theme::load(path).map_err(|err| io::Error::other(format!("--theme: {err}")))?;
+        args.ramp = args.ramp.take().or(Some(theme.ramp));
+        set_theme_palette(theme.colors);
+    }
+    if args.dump_palette {
+        print!("{}", palette_dump(&palette(&args.style)));
+        return Ok(());
+    }
+    let fixed_interval = (matches.value_source("interval") != Some(ValueSource::DefaultValue)).then_some(args.interval);
+    if let Some(path) = &args.replay {
+        return run_playback(path, fixed_interval, &args.style, args.interval);
+    }
+    match args.command {
+        Some(Command::Metrics { oneline, ref format, prometheus, watch }) => return run_metrics(&args, oneline, format.as_deref(), prometheus, watch),
+        Some(Command::Playback { ref file }) => return run_playback(file, fixed_interval, &args.style, args.interval),
+        Some(Command::Summary { idle_cpu, busy_cpu, high_memory, heavy_network }) => {
+            return run_summary(&args, &Thresholds { idle_cpu, busy_cpu, high_memory, heavy_network_kib: heavy_network })
+        }
+        Some(Command::Validate { ref file }) => return run_validate(file),
+        Some(Command::AsciiArt { ref image, noise }) => return run_ascii_art(&args, image, noise),
+        Some(Command::CompareSeeds { count, by, top }) => return run_compare_seeds(&args, count, by, top),
+        Some(Command::Calibrate) => return run_calibrate(&args),
+        Some(Command::Bench { frames, all_styles }) => return run_bench(&args, frames, all_styles),
+        Some(Command::Gauge { metric }) => return run_gauge(&args, metric),
+        Some(Command::Config) => return run_config(&args),
+        Some(Command::SeedGrid { count, thumb_width, thumb_height, ref png }) => return run_seed_grid(&args, count, thumb_width, thumb_height, png.as_deref()),
+        Some(Command::Run) | None => {}
+    }
+    if let Some(path) = &args.stream_out {
+        return run_stream(&args, path);
+    }
+    if let (Some(path), Some(frames)) = (&args.apng, args.frames) {
+        return run_apng(&args, path, frames);
+    }
+    if let Some(transition) = &args.transition {
+        return run_transition(&args, transition);
+    }
+    if let Some(frames) = args.frames.filter(|_| args.seamless_loop) {
+        return run_seamless(&args, frames);
+    }
+    if args.json {
+        return run_snapshot(&args);
+    }
+    run_live(&args)
 }
 
 fn main() {
-    let args = Args::parse();
-    ensure_line_budget();
-    if args.strict {
-        ensure_line_budget();
-    }
-    if args.json {
-        if let Err(err) = run_snapshot(&args) {
-            eprintln!("error: {err}");
-            std::process::exit(2);
-        }
-        return;
+    let matches = Args::command().get_matches();
+    let mut args = Args::from_arg_matches(&matches).unwrap_or_else(|err| err.exit());
+    set_log_json(args.log_json);
+    apply_style_defaults(&mut args, &matches);
+    if let Some(limit) = args.max_lines.or(args.strict.then_some(LINE_LIMIT)) {
+        ensure_line_budget(limit);
     }
-    if let Err(err) = run_live(&args) {
+    if let Err(err) = dispatch(args, &matches) {
         eprintln!("error: {err}");
-        let _ = execute!(stdout(), LeaveAlternateScreen);
-        let _ = disable_raw_mode();
         std::process::exit(2);
     }
 }
@@ -0,0 +1,83 @@
+use crate::{render::Canvas, substrate::Substrate};
+use std::{fs, io, path::Path};
+
+pub const MASK_THRESHOLD: f32 = 0.5;
+
+pub struct Mask {
+    pub width: usize,
+    pub height: usize,
+    pub cells: Vec<bool>,
+}
+
+impl Mask {
+    pub fn parse(text: &str) -> Self {
+        let lines: Vec<&str> = text.lines().collect();
+        let width = lines.iter().map(|line| line.chars().count()).max().unwrap_or(0).max(1);
+        let height = lines.len().max(1);
+        let mut cells = vec![false; width * height];
+        for (y, line) in lines.iter().enumerate() {
+            for (x, ch) in line.chars().enumerate() {
+                cells[y * width + x] = !ch.is_whitespace() && ch != '.';
+            }
+        }
+        Mask { width, height, cells }
+    }
+
+    pub fn load(path: &Path, width: u16, height: u16) -> io::Result<Self> {
+        if path.extension().is_some_and(|ext| ext == "txt") {
+            return Ok(Mask::parse(&fs::read_to_string(path)?));
+        }
+        let substrate = Substrate::load(path, width, height).map_err(io::Error::other)?;
+        Ok(Mask {
+            width: substrate.width as usize,
+            height: substrate.height as usize,
+            cells: substrate.luma.iter().map(|&luma| luma >= MASK_THRESHOLD).collect(),
+        })
+    }
+
+    pub fn contains(&self, x: usize, y: usize, width: usize, height: usize) -> bool {
+        let sx = (x * self.width / width.max(1)).min(self.width - 1);
+        let sy = (y * self.height / height.max(1)).min(self.height - 1);
+        self.cells[sy * self.width + sx]
+    }
+}
+
+pub fn apply_mask(canvas: &mut Canvas, mask: &Mask) {
+    let height = canvas.rows.len();
+    for (y, row) in canvas.rows.iter_mut().enumerate().filter(|(y, _)| *y != canvas.overlay_row) {
+        let width = row.chars().count();
+        *row = row.chars().enumerate().map(|(x, ch)| if mask.contains(x, y, width, height) { ch } else { ' ' }).collect();
+        if let Some(values) = canvas.intensity.get_mut(y) {
+            for (x, value) in values.iter_mut().enumerate() {
+                if !mask.contains(x, y, width, height) {
+                    *value = 0.0;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        metrics::{MetricsSource, MockSource},
+        render::{render_frame, RenderOptions},
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn only_cells_inside_a_rectangular_mask_are_drawn() {
+        let opts = RenderOptions { overlay: false, ..RenderOptions::new(8, 4, "plasma") };
+        let original = render_frame(&MockSource::new(6).sample(), &mut StdRng::seed_from_u64(6), &opts);
+        let mut canvas = render_frame(&MockSource::new(6).sample(), &mut StdRng::seed_from_u64(6), &opts);
+        apply_mask(&mut canvas, &Mask::parse("....\n.##.\n"));
+        for (y, row) in canvas.rows.iter().enumerate() {
+            for (x, (ch, was)) in row.chars().zip(original.rows[y].chars()).enumerate() {
+                let inside = y >= 2 && (2..6).contains(&x);
+                assert_eq!(ch, if inside { was } else { ' ' }, "cell ({x}, {y})");
+                assert_eq!(canvas.intensity[y][x], if inside { original.intensity[y][x] } else { 0.0 });
+            }
+        }
+    }
+}
@@ -0,0 +1,61 @@
+use crate::{metrics::NormalizedMetrics, render::Canvas};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+pub const MEMMAP_STYLE: &str = "mem-map";
+pub const MEMMAP_JITTER: f32 = 6.0;
+const MEMMAP_SEED: u64 = 0x6d65_6d6d_6170;
+
+pub fn fill_order(cells: usize) -> Vec<usize> {
+    let mut rng = StdRng::seed_from_u64(MEMMAP_SEED);
+    let mut keyed: Vec<(f32, usize)> = (0..cells)
+        .map(|idx| (idx as f32 + rng.gen::<f32>() * MEMMAP_JITTER, idx))
+        .collect();
+    keyed.sort_by(|a, b| a.0.total_cmp(&b.0));
+    keyed.into_iter().map(|(_, idx)| idx).collect()
+}
+
+pub fn filled_cells(ratio: f32, cells: usize) -> usize {
+    ((ratio.clamp(0.0, 1.0) * cells as f32).round() as usize).min(cells)
+}
+
+pub fn memmap_canvas(ramp: &str, inputs: NormalizedMetrics, width: u16, height: u16) -> Canvas {
+    let (width, height) = (width as usize, height as usize);
+    let cells = width * height;
+    let empty = ramp.chars().next().unwrap_or('.');
+    let full = ramp.chars().last().unwrap_or('#');
+    let mut used = vec![false; cells];
+    for idx in fill_order(cells).into_iter().take(filled_cells(inputs.memory, cells)) {
+        used[idx] = true;
+    }
+    let rows = used.chunks(width.max(1)).map(|row| row.iter().map(|&cell| if cell { full } else { empty }).collect()).collect();
+    let intensity = used
+        .chunks(width.max(1))
+        .map(|row| row.iter().map(|&cell| if cell { inputs.memory } else { 0.0 }).collect())
+        .collect();
+    Canvas {
+        rows,
+        intensity,
+        overlay_row: usize::MAX,
+        alerts: Vec::new(),
+        inputs,
+        sanitized: 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn filled_cells_track_the_memory_ratio() {
+        for memory in [0.0, 0.1, 0.37, 0.5, 0.99, 1.0] {
+            let inputs = NormalizedMetrics { memory, ..NormalizedMetrics::default() };
+            let canvas = memmap_canvas(".#", inputs, 30, 7);
+            let filled = canvas.rows.iter().flat_map(|row| row.chars()).filter(|&ch| ch == '#').count();
+            assert_eq!(filled, (memory * 210.0).round() as usize, "memory {memory}");
+        }
+        let mut order = fill_order(50);
+        order.sort_unstable();
+        assert_eq!(order, (0..50).collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,604 @@
+use clap::ValueEnum;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+use sysinfo::{ComponentExt, CpuExt, DiskExt, NetworkExt, NetworksExt, System, SystemExt};
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Metrics {
+    pub cpu_usage: f32,
+    pub cpu_cores: Vec<f32>,
+    pub load_avg: f64,
+    pub total_memory: u64,
+    pub used_memory: u64,
+    pub disk_usage: Vec<DiskMetrics>,
+    pub network_rx: u64,
+    pub network_tx: u64,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub interfaces: Vec<InterfaceMetrics>,
+    pub entropy: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct DiskMetrics {
+    pub name: String,
+    pub total_space: u64,
+    pub available_space: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct InterfaceMetrics {
+    pub name: String,
+    pub received: u64,
+    pub transmitted: u64,
+}
+
+#[derive(Serialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct NormalizedMetrics {
+    /// `cpu_usage / 100`.
+    pub cpu: f32,
+    /// `used_memory / total_memory` clamped to 0-1, 0 when total is unknown.
+    pub memory: f32,
+    /// `ln(1 + rx + tx) / 15`, so ~3 MB of traffic reads as full.
+    pub network: f32,
+    /// Used fraction across all disks, 0 when no capacity is reported.
+    pub disk: f32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct MetricsSummary {
+    pub cpu: f32,
+    pub memory: f32,
+    pub network: f32,
+    pub disk: f32,
+    pub load_avg: f64,
+}
+
+impl MetricsSummary {
+    pub fn from_metrics(metrics: &Metrics) -> Self {
+        let NormalizedMetrics { cpu, memory, network, disk } = NormalizedMetrics::from_metrics(metrics);
+        MetricsSummary { cpu, memory, network, disk, load_avg: metrics.load_avg }
+    }
+}
+
+impl NormalizedMetrics {
+    pub fn from_metrics(metrics: &Metrics) -> Self {
+        let memory = if metrics.total_memory == 0 {
+            0.0
+        } else {
+            metrics.used_memory as f32 / metrics.total_memory as f32
+        };
+        let network = (metrics.network_rx.saturating_add(metrics.network_tx) as f32).ln_1p() / 15.0;
+        let total = metrics.disk_usage.iter().map(|disk| disk.total_space).fold(0u64, u64::saturating_add);
+        let available = metrics.disk_usage.iter().map(|disk| disk.available_space).fold(0u64, u64::saturating_add);
+        let disk = if total == 0 {
+            0.0
+        } else {
+            1.0 - available as f32 / total as f32
+        };
+        NormalizedMetrics {
+            cpu: (metrics.cpu_usage / 100.0).clamp(0.0, 1.0),
+            memory: memory.clamp(0.0, 1.0),
+            network: network.clamp(0.0, 1.0),
+            disk: disk.clamp(0.0, 1.0),
+        }
+    }
+}
+
+fn finite_or_zero(value: f32) -> f32 {
+    if value.is_finite() {
+        value.max(0.0)
+    } else {
+        0.0
+    }
+}
+
+pub fn summarize_cpus(cores: &[f32], global: f32) -> (f32, Vec<f32>) {
+    if cores.is_empty() {
+        return (0.0, Vec::new());
+    }
+    let cores: Vec<f32> = cores.iter().copied().map(finite_or_zero).collect();
+    let usage = if global.is_finite() {
+        global.max(0.0)
+    } else {
+        cores.iter().sum::<f32>() / cores.len() as f32
+    };
+    (usage, cores)
+}
+
+fn lerp(a: f64, b: f64, t: f32) -> f64 {
+    a + (b - a) * t as f64
+}
+
+pub fn lerp_metrics(from: &Metrics, to: &Metrics, t: f32) -> Metrics {
+    let t = t.clamp(0.0, 1.0);
+    let lerp_u64 = |a: u64, b: u64| lerp(a as f64, b as f64, t).round() as u64;
+    Metrics {
+        cpu_usage: lerp(from.cpu_usage as f64, to.cpu_usage as f64, t) as f32,
+        cpu_cores: to
+            .cpu_cores
+            .iter()
+            .enumerate()
+            .map(|(idx, &core)| lerp(from.cpu_cores.get(idx).copied().unwrap_or(core) as f64, core as f64, t) as f32)
+            .collect(),
+        load_avg: lerp(from.load_avg, to.load_avg, t),
+        total_memory: lerp_u64(from.total_memory, to.total_memory),
+        used_memory: lerp_u64(from.used_memory, to.used_memory),
+        disk_usage: to.disk_usage.clone(),
+        network_rx: lerp_u64(from.network_rx, to.network_rx),
+        network_tx: lerp_u64(from.network_tx, to.network_tx),
+        interfaces: to.interfaces.clone(),
+        entropy: to.entropy,
+        temperature: match (from.temperature, to.temperature) {
+            (Some(a), Some(b)) => Some(lerp(a as f64, b as f64, t) as f32),
+            (_, latest) => latest,
+        },
+    }
+}
+
+pub trait MetricsSource {
+    fn sample(&mut self) -> Metrics;
+
+    fn frames(&self) -> Option<u64> {
+        None
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SeedWeights {
+    pub cpu: f32,
+    pub memory: u64,
+    pub network: u64,
+    pub disk: u64,
+}
+
+impl Default for SeedWeights {
+    fn default() -> Self {
+        SeedWeights { cpu: 100.0, memory: 1, network: 1, disk: 1 }
+    }
+}
+
+/// Mixes the metrics into a seed, scaling each term by its weight so embedders can choose
+/// which readings drive the pattern.
+///
+/// ```
+/// use procgen_art::metrics::{gather_metrics, seed_from_metrics, MockSource, SeedWeights};
+///
+/// let metrics = gather_metrics(&mut MockSource::new(3));
+/// let balanced = seed_from_metrics(&metrics, &SeedWeights::default());
+/// let memory_only = seed_from_metrics(&metrics, &SeedWeights { cpu: 0.0, memory: 1, network: 0, disk: 0 });
+/// assert_eq!(memory_only, metrics.used_memory);
+/// assert_ne!(balanced, memory_only);
+/// ```
+pub fn seed_from_metrics(metrics: &Metrics, weights: &SeedWeights) -> u64 {
+    let disk = metrics.disk_usage.iter().map(|disk| disk.total_space).fold(0u64, u64::saturating_add);
+    ((metrics.cpu_usage * weights.cpu) as u64)
+        .wrapping_add(metrics.used_memory.wrapping_mul(weights.memory))
+        .wrapping_add(metrics.network_rx.wrapping_add(metrics.network_tx).wrapping_mul(weights.network))
+        .wrapping_add(disk.wrapping_mul(weights.disk))
+}
+
+impl Metrics {
+    pub fn with_entropy(mut self) -> Self {
+        self.entropy = seed_from_metrics(&self, &SeedWeights::default());
+        self
+    }
+}
+
+pub fn sort_disks(disks: &mut [DiskMetrics]) {
+    disks.sort_by(|a, b| (&a.name, a.total_space, a.available_space).cmp(&(&b.name, b.total_space, b.available_space)));
+}
+
+pub fn is_busy(metrics: &Metrics, threshold: Option<f32>) -> bool {
+    threshold.is_none_or(|pct| metrics.cpu_usage >= pct)
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, ValueEnum, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Subsystem {
+    Cpu,
+    #[value(alias = "mem")]
+    Memory,
+    #[value(alias = "disk")]
+    Disks,
+    #[value(aliases = ["net", "network"])]
+    Networks,
+    #[value(alias = "temp")]
+    Sensors,
+}
+
+impl Subsystem {
+    pub fn refresh_every(self) -> u64 {
+        match self {
+            Subsystem::Cpu => 1,
+            Subsystem::Memory => 2,
+            Subsystem::Disks => 10,
+            Subsystem::Networks => 1,
+            Subsystem::Sensors => 10,
+        }
+    }
+
+    pub fn due(self, tick: u64) -> bool {
+        tick.is_multiple_of(self.refresh_every())
+    }
+}
+
+pub fn traffic_totals(interfaces: &[InterfaceMetrics]) -> (u64, u64) {
+    interfaces.iter().fold((0, 0), |(rx, tx), interface| (rx.saturating_add(interface.received), tx.saturating_add(interface.transmitted)))
+}
+
+pub fn due_subsystems(subsystems: &[Subsystem], tick: u64) -> Vec<Subsystem> {
+    Subsystem::value_variants()
+        .iter()
+        .copied()
+        .filter(|subsystem| (subsystems.is_empty() || subsystems.contains(subsystem)) && subsystem.due(tick))
+        .collect()
+}
+
+pub fn mask_metrics(metrics: &mut Metrics, subsystems: &[Subsystem]) {
+    let wanted = |subsystem| subsystems.is_empty() || subsystems.contains(&subsystem);
+    if !wanted(Subsystem::Cpu) {
+        (metrics.cpu_usage, metrics.load_avg) = (0.0, 0.0);
+        metrics.cpu_cores.fill(0.0);
+    }
+    if !wanted(Subsystem::Memory) {
+        (metrics.total_memory, metrics.used_memory) = (0, 0);
+    }
+    if !wanted(Subsystem::Disks) {
+        metrics.disk_usage.clear();
+    }
+    if !wanted(Subsystem::Networks) {
+        (metrics.network_rx, metrics.network_tx) = (0, 0);
+        metrics.interfaces.clear();
+    }
+    if !wanted(Subsystem::Sensors) {
+        metrics.temperature = None;
+    }
+}
+
+pub struct CpuWindow {
+    window: Duration,
+    samples: VecDeque<(Instant, f32)>,
+}
+
+impl CpuWindow {
+    pub fn new(window: Duration) -> Self {
+        CpuWindow { window, samples: VecDeque::new() }
+    }
+
+    pub fn push(&mut self, now: Instant, usage: f32) -> f32 {
+        self.samples.push_back((now, usage));
+        while self.samples.len() > 1 && self.samples.front().is_some_and(|(at, _)| now.duration_since(*at) > self.window) {
+            self.samples.pop_front();
+        }
+        self.samples.iter().map(|(_, usage)| usage).sum::<f32>() / self.samples.len() as f32
+    }
+}
+
+pub struct SystemSource {
+    sys: System,
+    tick: u64,
+    subsystems: Vec<Subsystem>,
+    cpu_window: Option<CpuWindow>,
+}
+
+impl SystemSource {
+    pub fn new(subsystems: Vec<Subsystem>) -> Self {
+        if subsystems.is_empty() {
+            return Self { sys: System::new_all(), tick: 0, subsystems, cpu_window: None };
+        }
+        let mut sys = System::new();
+        if subsystems.contains(&Subsystem::Networks) {
+            sys.refresh_networks_list();
+        }
+        if subsystems.contains(&Subsystem::Sensors) {
+            sys.refresh_components_list();
+        }
+        Self { sys, tick: 0, subsystems, cpu_window: None }
+    }
+
+    pub fn from_system(sys: System) -> Self {
+        Self { sys, tick: 0, subsystems: Vec::new(), cpu_window: None }
+    }
+
+    pub fn with_cpu_window(mut self, window: Option<Duration>) -> Self {
+        self.cpu_window = window.map(CpuWindow::new);
+        self
+    }
+
+    fn refresh(&mut self) {
+        let tick = self.tick;
+        self.tick = self.tick.wrapping_add(1);
+        for subsystem in due_subsystems(&self.subsystems, tick) {
+            match subsystem {
+                Subsystem::Cpu => self.sys.refresh_cpu(),
+                Subsystem::Memory => self.sys.refresh_memory(),
+                Subsystem::Disks => self.sys.refresh_disks_list(),
+                Subsystem::Networks => self.sys.refresh_networks(),
+                Subsystem::Sensors => self.sys.refresh_components(),
+            }
+        }
+    }
+}
+
+impl MetricsSource for SystemSource {
+    fn sample(&mut self) -> Metrics {
+        self.refresh();
+        let sys = &self.sys;
+        let cores: Vec<f32> = sys.cpus().iter().map(|cpu| cpu.cpu_usage()).collect();
+        let global = if cores.is_empty() { 0.0 } else { sys.global_cpu_info().cpu_usage() };
+        let (mut cpu_usage, cpu_cores) = summarize_cpus(&cores, global);
+        if let Some(window) = self.cpu_window.as_mut() {
+            cpu_usage = window.push(Instant::now(), cpu_usage);
+        }
+        let load_avg = sys.load_average().one;
+        let load_avg = if load_avg.is_finite() { load_avg.max(0.0) } else { 0.0 };
+        let total_memory = sys.total_memory();
+        let used_memory = sys.used_memory();
+        let mut interfaces: Vec<InterfaceMetrics> = sys
+            .networks()
+            .iter()
+            .map(|(name, data)| InterfaceMetrics {
+                name: name.clone(),
+                received: data.received(),
+                transmitted: data.transmitted(),
+            })
+            .collect();
+        interfaces.sort_by(|a, b| a.name.cmp(&b.name));
+        let (network_rx, network_tx) = traffic_totals(&interfaces);
+        let mut disk_usage: Vec<DiskMetrics> = sys
+            .disks()
+            .iter()
+            .map(|disk| DiskMetrics {
+                name: disk.name().to_string_lossy().to_string(),
+                total_space: disk.total_space(),
+                available_space: disk.available_space(),
+            })
+            .collect();
+        sort_disks(&mut disk_usage);
+        let temperature = sys
+            .components()
+            .iter()
+            .map(|component| component.temperature())
+            .filter(|temp| temp.is_finite())
+            .reduce(f32::max);
+
+        let mut metrics = Metrics {
+            cpu_usage,
+            cpu_cores,
+            load_avg,
+            total_memory,
+            used_memory,
+            disk_usage,
+            network_rx,
+            network_tx,
+            interfaces,
+            entropy: 0,
+            temperature,
+        };
+        mask_metrics(&mut metrics, &self.subsystems);
+        metrics.with_entropy()
+    }
+}
+
+pub struct MockSource {
+    rng: StdRng,
+    tick: u64,
+}
+
+impl MockSource {
+    pub const CORES: usize = 4;
+    pub const TOTAL_MEMORY: u64 = 16 << 30;
+    pub const DISK_SPACE: u64 = 512 << 30;
+
+    pub fn new(seed: u64) -> Self {
+        Self { rng: StdRng::seed_from_u64(seed), tick: 0 }
+    }
+}
+
+impl MetricsSource for MockSource {
+    fn sample(&mut self) -> Metrics {
+        self.tick += 1;
+        let wave = (self.tick as f32 * 0.2).sin();
+        let cores: Vec<f32> = (0..Self::CORES)
+            .map(|_| (50.0 + 35.0 * wave + self.rng.gen_range(-10.0..10.0)).clamp(0.0, 100.0))
+            .collect();
+        let global = cores.iter().sum::<f32>() / cores.len() as f32;
+        let (cpu_usage, cpu_cores) = summarize_cpus(&cores, global);
+        let used_fraction = 0.5 + 0.2 * (self.tick as f64 * 0.05).sin();
+        let disk_fraction = 0.3 + 0.01 * (self.tick % 40) as f64;
+        let (network_rx, network_tx) = (self.rng.gen_range(0..200_000), self.rng.gen_range(0..50_000));
+        Metrics {
+            cpu_usage,
+            cpu_cores,
+            load_avg: (global / 25.0) as f64,
+            total_memory: Self::TOTAL_MEMORY,
+            used_memory: (Self::TOTAL_MEMORY as f64 * used_fraction) as u64,
+            disk_usage: vec![DiskMetrics {
+                name: "mock0".to_string(),
+                total_space: Self::DISK_SPACE,
+                available_space: (Self::DISK_SPACE as f64 * (1.0 - disk_fraction)) as u64,
+            }],
+            network_rx,
+            network_tx,
+            interfaces: vec![InterfaceMetrics {
+                name: "mock0".to_string(),
+                received: network_rx,
+                transmitted: network_tx,
+            }],
+            entropy: 0,
+            temperature: Some(40.0 + 35.0 * (0.5 + wave / 2.0)),
+        }
+        .with_entropy()
+    }
+}
+
+pub fn gather_metrics<S: MetricsSource + ?Sized>(source: &mut S) -> Metrics {
+    source.sample()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        render::{render_frame, RenderOptions},
+        scene::{draw, Scene},
+    };
+
+    #[test]
+    fn saturated_network_counters_do_not_overflow() {
+        let mut metrics = MockSource::new(1).sample();
+        (metrics.network_rx, metrics.network_tx) = (u64::MAX, u64::MAX);
+        assert_eq!(NormalizedMetrics::from_metrics(&metrics).network, 1.0);
+        let quiet = Metrics { network_rx: 0, network_tx: 0, ..metrics.clone() };
+        assert_ne!(seed_from_metrics(&metrics, &SeedWeights::default()), seed_from_metrics(&quiet, &SeedWeights::default()));
+    }
+
+    #[test]
+    fn degenerate_memory_and_width_render_without_nan() {
+        let metrics = Metrics { total_memory: 0, used_memory: 4 << 30, ..MockSource::new(2).sample() };
+        assert_eq!(NormalizedMetrics::from_metrics(&metrics).memory, 0.0);
+        let canvas = render_frame(&metrics, &mut StdRng::seed_from_u64(2), &RenderOptions::new(32, 8, "plasma"));
+        assert!(canvas.intensity.iter().flatten().all(|value| value.is_finite()));
+        assert!(canvas.rows.iter().any(|row| row.contains("MEM")));
+        assert!(canvas.rows.iter().all(|row| !row.contains("NaN") && !row.contains("inf")), "{:?}", canvas.rows);
+        let empty = render_frame(&metrics, &mut StdRng::seed_from_u64(2), &RenderOptions::new(0, 4, "plasma"));
+        assert!(empty.rows.iter().all(String::is_empty));
+    }
+
+    #[test]
+    fn the_same_disks_always_sort_the_same_way() {
+        let disk = |name: &str, total_space: u64, available_space: u64| DiskMetrics { name: name.to_string(), total_space, available_space };
+        let gathered = [disk("overlay", 20, 5), disk("nvme0n1", 500, 100), disk("overlay", 10, 5), disk("overlay", 10, 2)];
+        let order = |mut disks: Vec<DiskMetrics>| {
+            sort_disks(&mut disks);
+            disks.into_iter().map(|disk| (disk.name, disk.total_space, disk.available_space)).collect::<Vec<_>>()
+        };
+        let first = order(gathered.to_vec());
+        assert_eq!(first, order(gathered.iter().rev().cloned().collect()));
+        assert_eq!(first[0], ("nvme0n1".to_string(), 500, 100));
+        assert_eq!(first[1..], [("overlay".to_string(), 10, 2), ("overlay".to_string(), 10, 5), ("overlay".to_string(), 20, 5)]);
+    }
+
+    #[test]
+    fn normalization_at_representative_values() {
+        let disk = |total_space: u64, available_space: u64| DiskMetrics { name: "sda".to_string(), total_space, available_space };
+        let metrics = |cpu_usage: f32, used_memory: u64, network: u64, disks: Vec<DiskMetrics>| Metrics {
+            cpu_usage,
+            used_memory,
+            total_memory: 400,
+            network_rx: network,
+            network_tx: 0,
+            disk_usage: disks,
+            ..MockSource::new(1).sample()
+        };
+        let idle = NormalizedMetrics::from_metrics(&metrics(0.0, 0, 0, Vec::new()));
+        assert_eq!((idle.cpu, idle.memory, idle.network, idle.disk), (0.0, 0.0, 0.0, 0.0));
+        let half = NormalizedMetrics::from_metrics(&metrics(50.0, 100, 1 << 20, vec![disk(100, 60), disk(100, 0)]));
+        assert_eq!((half.cpu, half.memory, half.disk), (0.5, 0.25, 0.7));
+        assert!((half.network - ((1u64 << 20) as f32).ln_1p() / 15.0).abs() < 1e-6);
+        assert!((0.9..1.0).contains(&half.network));
+        let pegged = NormalizedMetrics::from_metrics(&metrics(250.0, 800, 10 << 30, vec![disk(10, 0)]));
+        assert_eq!((pegged.cpu, pegged.memory, pegged.network, pegged.disk), (1.0, 1.0, 1.0, 1.0));
+    }
+
+    #[test]
+    fn no_cpus_still_renders_a_frame() {
+        let (cpu_usage, cpu_cores) = summarize_cpus(&[], 37.0);
+        assert_eq!((cpu_usage, cpu_cores.len()), (0.0, 0));
+        assert_eq!(summarize_cpus(&[f32::NAN, 40.0], f32::NAN), (20.0, vec![0.0, 40.0]));
+        let metrics = Metrics { cpu_usage, cpu_cores, ..MockSource::new(3).sample() };
+        for style in ["plasma", "eq", "spectrum"] {
+            let opts = RenderOptions::new(16, 5, style);
+            let canvas = draw(&metrics, &mut StdRng::seed_from_u64(3), &mut Scene::new(3, None), &opts);
+            assert_eq!(canvas.rows.len(), 5, "{style}");
+            assert!(canvas.rows.iter().all(|row| row.chars().count() == 16), "{style}");
+        }
+    }
+
+    #[test]
+    fn mock_source_replays_the_same_sequence_for_a_seed() {
+        let run = |seed: u64| {
+            let mut source = MockSource::new(seed);
+            (0..5).map(|_| serde_json::to_string(&source.sample()).unwrap()).collect::<Vec<_>>()
+        };
+        assert_eq!(run(11), run(11));
+        assert_ne!(run(11), run(12));
+    }
+
+    #[test]
+    fn refresh_schedule_across_ticks() {
+        let due_ticks = |subsystem: Subsystem| (0..20).filter(|&tick| subsystem.due(tick)).collect::<Vec<u64>>();
+        assert_eq!(due_ticks(Subsystem::Cpu), (0..20).collect::<Vec<_>>());
+        assert_eq!(due_ticks(Subsystem::Networks), (0..20).collect::<Vec<_>>());
+        assert_eq!(due_ticks(Subsystem::Memory), (0..20).step_by(2).collect::<Vec<_>>());
+        assert_eq!(due_ticks(Subsystem::Disks), vec![0, 10]);
+        assert_eq!(due_ticks(Subsystem::Sensors), vec![0, 10]);
+    }
+
+    #[test]
+    fn lerp_metrics_hits_both_ends_and_the_midpoint() {
+        let mut source = MockSource::new(6);
+        let (from, to) = (source.sample(), source.sample());
+        let at = |t: f32| lerp_metrics(&from, &to, t);
+        assert_eq!(serde_json::to_string(&at(0.0).cpu_cores).unwrap(), serde_json::to_string(&from.cpu_cores).unwrap());
+        assert_eq!((at(0.0).used_memory, at(0.0).network_rx), (from.used_memory, from.network_rx));
+        assert_eq!(serde_json::to_string(&at(1.0)).unwrap(), serde_json::to_string(&to).unwrap());
+        let mid = at(0.5);
+        assert!((mid.cpu_usage - (from.cpu_usage + to.cpu_usage) / 2.0).abs() < 1e-3);
+        assert!((mid.load_avg - (from.load_avg + to.load_avg) / 2.0).abs() < 1e-9);
+        assert_eq!(mid.network_tx, ((from.network_tx + to.network_tx) as f64 / 2.0).round() as u64);
+        assert!((mid.temperature.unwrap() - (from.temperature.unwrap() + to.temperature.unwrap()) / 2.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn busy_gate_opens_at_the_threshold() {
+        let mut metrics = MockSource::new(1).sample();
+        metrics.cpu_usage = 30.0;
+        assert!(!is_busy(&metrics, Some(50.0)));
+        metrics.cpu_usage = 50.0;
+        assert!(is_busy(&metrics, Some(50.0)));
+        metrics.cpu_usage = 80.0;
+        assert!(is_busy(&metrics, Some(50.0)));
+        metrics.cpu_usage = 0.0;
+        assert!(is_busy(&metrics, None));
+    }
+
+    #[test]
+    fn cpu_only_skips_the_disk_and_network_refreshes() {
+        let refreshed: Vec<Subsystem> = (0..20).flat_map(|tick| due_subsystems(&[Subsystem::Cpu], tick)).collect();
+        assert_eq!(refreshed, vec![Subsystem::Cpu; 20]);
+        assert_eq!(due_subsystems(&[], 0), Subsystem::value_variants());
+        let metrics = SystemSource::new(vec![Subsystem::Cpu]).sample();
+        assert!(metrics.disk_usage.is_empty() && metrics.interfaces.is_empty());
+    }
+
+    #[test]
+    fn cpu_window_averages_samples_inside_the_window() {
+        let start = Instant::now();
+        let mut window = CpuWindow::new(Duration::from_secs(2));
+        let at = |secs: u64| start + Duration::from_secs(secs);
+        assert_eq!(window.push(at(0), 10.0), 10.0);
+        assert_eq!(window.push(at(1), 30.0), 20.0);
+        assert_eq!(window.push(at(2), 50.0), 30.0);
+        assert_eq!(window.push(at(4), 90.0), 70.0);
+        assert_eq!(window.push(at(10), 40.0), 40.0);
+    }
+
+    #[test]
+    fn huge_traffic_and_disk_totals_saturate_instead_of_overflowing() {
+        let interface = |name: &str| InterfaceMetrics { name: name.to_string(), received: u64::MAX - 1, transmitted: u64::MAX / 2 + 1 };
+        assert_eq!(traffic_totals(&[interface("eth0"), interface("eth1")]), (u64::MAX, u64::MAX));
+        let mut metrics = MockSource::new(1).sample();
+        let disk = |name: &str| DiskMetrics { name: name.to_string(), total_space: u64::MAX, available_space: u64::MAX / 2 };
+        metrics.disk_usage = vec![disk("a"), disk("b")];
+        let inputs = NormalizedMetrics::from_metrics(&metrics);
+        assert!((0.0..=1.0).contains(&inputs.disk));
+        seed_from_metrics(&metrics, &SeedWeights::default());
+    }
+}
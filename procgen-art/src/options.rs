@@ -0,0 +1,489 @@
+use crate::cli::{Args, Command};
+use clap::{parser::ValueSource, ArgMatches};
+use procgen_art::{
+    clock::{clock_text, stamp_clock},
+    csv::CsvSource,
+    demoscene::{demoscene_metrics, ConstantSource},
+    devices::{DeviceWeights, WeightedSource},
+    filters::{apply_grid_overlay, FilterKind},
+    keymap::KeyMap,
+    log::warn,
+    mask::Mask,
+    metrics::{gather_metrics, Metrics, MetricsSource, MockSource, SystemSource},
+    phase::RngState,
+    rain::RainTuning,
+    render::{cap_canvas, morph_key, replace_unsupported, Canvas, Flow, Influence, RenderOptions, DEFAULT_FALLBACK},
+    seedfile::read_or_create_seed,
+    snapshot::{fnv1a, frame_checksum, frame_data, FrameFormat, Snapshot, BUILD_COMMIT},
+    stats::FrameStats,
+    styles::{random_style, style_spec},
+    substrate::Substrate,
+    termenv::capture_env,
+    theme::Theme,
+};
+use rand::{rngs::OsRng, RngCore};
+use std::{io, path::Path, sync::Arc, time::Duration};
+
+pub const MIN_INTERVAL_MS: u64 = 1;
+
+pub fn apply_style_defaults(args: &mut Args, matches: &ArgMatches) {
+    if args.random_style {
+        let seed = args.seed.or_else(|| args.seed_phrase.as_deref().map(phrase_seed));
+        args.style = random_style(seed.unwrap_or_else(|| OsRng.next_u64())).to_string();
+    }
+    let spec = style_spec(&args.style);
+    let defaulted = |id: &str| matches.value_source(id) == Some(ValueSource::DefaultValue);
+    if defaulted("interval") {
+        args.interval = spec.interval_ms;
+    }
+    if defaulted("width") {
+        args.width = spec.width;
+    }
+    if defaulted("height") {
+        args.height = spec.height;
+    }
+    if args.demoscene && !args.filters.contains(&FilterKind::Symmetry) {
+        args.filters.push(FilterKind::Symmetry);
+    }
+    if args.interval < MIN_INTERVAL_MS {
+        warn(&format!("--interval {} would busy-spin, using {MIN_INTERVAL_MS}ms", args.interval));
+        args.interval = MIN_INTERVAL_MS;
+    }
+}
+
+pub fn capped_size(args: &Args, width: u16, height: u16) -> (u16, u16) {
+    let (capped_width, capped_height) = cap_canvas(width, height, args.max_cells);
+    if (capped_width, capped_height) != (width, height) {
+        warn(&format!("{width}x{height} exceeds --max-cells, rendering at {capped_width}x{capped_height}"));
+    }
+    (capped_width, capped_height)
+}
+
+pub fn device_weights(args: &Args) -> DeviceWeights {
+    DeviceWeights { disks: args.disk_weights.clone(), interfaces: args.net_weights.clone() }
+}
+
+pub fn render_options(args: &Args) -> io::Result<RenderOptions> {
+    let (width, height) = capped_size(args, args.width, args.height);
+    let substrate = match &args.bg_image {
+        Some(path) => Some(Arc::new(Substrate::load(path, width, height).map_err(io::Error::other)?)),
+        None => None,
+    };
+    let mask = match &args.mask {
+        Some(path) => Some(Arc::new(Mask::load(path, width, height)?)),
+        None => None,
+    };
+    Ok(RenderOptions {
+        width,
+        height,
+        style: args.style.clone(),
+        overlay_pos: args.overlay_pos,
+        alert_cpu: args.alert_cpu,
+        alert_mem: args.alert_mem,
+        watermark: args.watermark,
+        overlay: !args.demoscene,
+        substrate,
+        frozen: false,
+        brightness: 1.0,
+        ramp: args.ramp.clone(),
+        ramp_by_load: args.ramp_by_load,
+        levels: args.levels,
+        loop_phase: None,
+        overlay_scale: args.overlay_scale,
+        overlay_lines: args.overlay_lines.clone(),
+        rain: RainTuning { speed: args.rain_speed, density: args.rain_density },
+        filters: args.filters.clone(),
+        zoom: args.zoom,
+        pan: (args.pan_x, args.pan_y),
+        scanlines: args.scanlines,
+        scanline_roll: args.scanline_roll,
+        morph: None,
+        flow: None,
+        noise_key: args.noise_seed.map(morph_key),
+        mask,
+        center: (args.center_x.is_some() || args.center_y.is_some())
+            .then(|| (args.center_x.unwrap_or(0.5), args.center_y.unwrap_or(0.5))),
+        influence: Influence {
+            cpu: if args.no_cpu_influence { 0.0 } else { 1.0 },
+            memory: if args.no_mem_influence { 0.0 } else { 1.0 },
+            network: if args.no_net_influence { 0.0 } else { 1.0 },
+        },
+    })
+}
+
+pub fn phrase_seed(phrase: &str) -> u64 {
+    fnv1a(phrase.as_bytes())
+}
+
+pub fn resolve_seed(args: &Args) -> io::Result<Option<u64>> {
+    if args.true_random {
+        return Ok(Some(OsRng.next_u64()));
+    }
+    if let Some(path) = &args.seed_from_file {
+        return read_or_create_seed(path).map(Some);
+    }
+    Ok(args.seed.or_else(|| args.seed_phrase.as_deref().map(phrase_seed)))
+}
+
+fn require_file(flag: &str, path: &Path) -> Result<(), String> {
+    if path.is_file() {
+        Ok(())
+    } else {
+        Err(format!("{flag}: {} is not a readable file", path.display()))
+    }
+}
+
+fn require_parent(flag: &str, path: &Path) -> Result<(), String> {
+    match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() && !dir.is_dir() => {
+            Err(format!("{flag}: directory {} does not exist", dir.display()))
+        }
+        _ => Ok(()),
+    }
+}
+
+pub fn validate_args(args: &Args) -> Result<(), String> {
+    if args.width == 0 || args.height == 0 {
+        return Err(format!("canvas must be at least 1x1, got {}x{}", args.width, args.height));
+    }
+    for (flag, value) in [("--alert-cpu", args.alert_cpu), ("--alert-mem", args.alert_mem)] {
+        if value.is_some_and(|pct| !(0.0..=100.0).contains(&pct)) {
+            return Err(format!("{flag} must be between 0 and 100"));
+        }
+    }
+    if let Some(path) = &args.bg_image {
+        require_file("--bg-image", path)?;
+    }
+    if let Some(path) = &args.mask {
+        require_file("--mask", path)?;
+    }
+    if let Some(path) = &args.seed_from_file {
+        require_parent("--seed-from-file", path)?;
+    }
+    if let Some(path) = &args.record {
+        require_parent("--record", path)?;
+    }
+    if let Some(path) = &args.stream_out {
+        require_parent("--stream-out", path)?;
+    }
+    if let Some(path) = &args.metrics_out {
+        require_parent("--metrics-out", path)?;
+    }
+    if let Some(path) = &args.frame_out {
+        require_parent("--frame-out", path)?;
+    }
+    if let Some(path) = &args.html {
+        require_parent("--html", path)?;
+    }
+    if let Some(path) = args.fifo.as_deref().filter(|path| !path.exists()) {
+        return Err(format!("--fifo: {} does not exist (create it with mkfifo)", path.display()));
+    }
+    if let Some(path) = &args.metrics_csv {
+        require_file("--metrics-csv", path)?;
+    }
+    if let Some(path) = &args.keymap {
+        require_file("--keymap", path)?;
+        KeyMap::load(path).map_err(|err| format!("--keymap: {err}"))?;
+    }
+    if let Some(path) = &args.theme {
+        require_file("--theme", path)?;
+        Theme::load(path).map_err(|err| format!("--theme: {err}"))?;
+    }
+    if let Some(Command::Playback { file }) = &args.command {
+        require_file("playback", file)?;
+    }
+    if let Some(Command::Validate { file }) = &args.command {
+        require_file("validate", file)?;
+    }
+    Ok(())
+}
+
+pub fn metrics_source(args: &Args) -> io::Result<Box<dyn MetricsSource + Send>> {
+    let source: Box<dyn MetricsSource + Send> = match &args.metrics_csv {
+        Some(path) => Box::new(CsvSource::load(path)?),
+        None if args.demoscene => Box::new(ConstantSource(demoscene_metrics())),
+        None if args.simulate => Box::new(MockSource::new(args.seed.unwrap_or(0))),
+        None => Box::new(SystemSource::new(args.subsystems.clone()).with_cpu_window(args.cpu_window.map(Duration::from_millis))),
+    };
+    let weights = device_weights(args);
+    Ok(if weights.is_empty() { source } else { Box::new(WeightedSource::new(source, weights)) })
+}
+
+pub fn decorate(canvas: &mut Canvas, args: &Args) {
+    if args.clock {
+        stamp_clock(canvas, &clock_text(&chrono::Local::now(), &args.clock_format));
+    }
+    if let Some(fallback) = args.fallback_glyph.or(args.ascii_only.then_some(DEFAULT_FALLBACK)) {
+        replace_unsupported(&mut canvas.rows, fallback);
+    }
+    if args.grid_overlay {
+        apply_grid_overlay(canvas);
+    }
+}
+
+pub fn seeded_source(args: &Args) -> io::Result<(Box<dyn MetricsSource + Send>, Metrics, u64)> {
+    let mut sys = metrics_source(args)?;
+    let metrics = gather_metrics(sys.as_mut());
+    let seed = resolve_seed(args)?.unwrap_or(metrics.entropy);
+    if args.metrics_csv.is_some() {
+        sys = metrics_source(args)?;
+    }
+    Ok((sys, metrics, seed))
+}
+
+pub fn flow_for(args: &Args, seed: u64, tick: u64) -> Option<Flow> {
+    (!args.no_flow).then(|| Flow { key: morph_key(seed), frame: tick })
+}
+
+pub fn frame_format(args: &Args) -> FrameFormat {
+    if args.rich_json {
+        FrameFormat::Cells
+    } else if args.codepoints {
+        FrameFormat::Codepoints
+    } else {
+        FrameFormat::Text
+    }
+}
+
+pub fn capture(args: &Args, metrics: &Metrics, canvas: &Canvas, opts: &RenderOptions) -> Snapshot {
+    Snapshot {
+        metrics: metrics.clone(),
+        frame: frame_data(canvas, &opts.style, frame_format(args)),
+        width: opts.width,
+        height: opts.height,
+        style: opts.style.clone(),
+        stats: args.stats.then(|| FrameStats::from_rows(&canvas.rows)),
+        tick: None,
+        rng: None,
+        checksum: args.checksum.then(|| frame_checksum(&canvas.rows)),
+        commit: args.stamp_commit.then(|| BUILD_COMMIT.to_string()),
+        env: args.capture_env.then(capture_env),
+        palette: None,
+        history: None,
+    }
+}
+
+pub fn rng_state(args: &Args, seed: u64, tick: u64) -> Option<RngState> {
+    (args.rng_state || args.phase_lock || args.start_tick.is_some()).then_some(RngState { seed, tick })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::{CommandFactory, FromArgMatches, Parser};
+    use procgen_art::{
+        color::ColorDepth,
+        live::{display_frame, DisplayOptions, ScreenMode},
+        render::render_frame,
+        snapshot::{to_json, JsonCase},
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn args(argv: &[&str]) -> Args {
+        Args::parse_from(["procgen-art"].iter().chain(argv))
+    }
+
+    fn styled(argv: &[&str]) -> Args {
+        let matches = Args::command().get_matches_from(["procgen-art"].iter().chain(argv));
+        let mut args = Args::from_arg_matches(&matches).unwrap();
+        apply_style_defaults(&mut args, &matches);
+        args
+    }
+
+    #[test]
+    fn the_same_phrase_gives_the_same_seed_and_frame() {
+        let seed = resolve_seed(&args(&["--seed-phrase", "aurora borealis"])).unwrap().unwrap();
+        assert_eq!(seed, phrase_seed("aurora borealis"));
+        assert_ne!(seed, phrase_seed("aurora Borealis"));
+        let frame = || {
+            let args = args(&["--simulate", "--seed-phrase", "aurora borealis", "--width", "16", "--height", "4"]);
+            let (_, metrics, seed) = seeded_source(&args).unwrap();
+            render_frame(&metrics, &mut StdRng::seed_from_u64(seed), &render_options(&args).unwrap()).rows
+        };
+        assert_eq!(frame(), frame());
+    }
+
+    #[test]
+    fn check_rejects_bad_args_with_a_descriptive_message() {
+        assert_eq!(validate_args(&args(&["--check"])), Ok(()));
+        assert_eq!(validate_args(&args(&["--check", "--width", "0"])), Err("canvas must be at least 1x1, got 0x24".to_string()));
+        assert_eq!(validate_args(&args(&["--check", "--alert-cpu", "120"])), Err("--alert-cpu must be between 0 and 100".to_string()));
+        let missing = args(&["--check", "--mask", "/nonexistent/mask.png"]);
+        assert_eq!(validate_args(&missing), Err("--mask: /nonexistent/mask.png is not a readable file".to_string()));
+    }
+
+    #[test]
+    fn style_defaults_fill_only_unset_flags() {
+        let ember = styled(&["--style", "ember"]);
+        assert_eq!((ember.interval, ember.width, ember.height), (200, 60, 24));
+        let pinned = styled(&["--style", "ember", "--interval", "750", "--width", "90"]);
+        assert_eq!((pinned.interval, pinned.width), (750, 90));
+    }
+
+    #[test]
+    fn once_displays_the_frame_it_snapshots() {
+        let args = args(&["--once", "--simulate", "--seed", "9", "--width", "16", "--height", "4"]);
+        let (_, metrics, seed) = seeded_source(&args).unwrap();
+        let opts = render_options(&args).unwrap();
+        let canvas = render_frame(&metrics, &mut StdRng::seed_from_u64(seed), &opts);
+        let display = DisplayOptions {
+            smooth: false,
+            sixel: false,
+            image: None,
+            mono: true,
+            no_clear: false,
+            depth: ColorDepth::Mono,
+            palette_bias: false,
+            palette: None,
+            banded: false,
+            min_contrast: None,
+            origin: (0, 0),
+            screen: ScreenMode::Inline,
+        };
+        let mut shown = Vec::new();
+        display_frame(&mut shown, &canvas, &opts.style, &display).unwrap();
+        let shown = String::from_utf8(shown).unwrap();
+        let snapshot = capture(&args, &metrics, &canvas, &opts);
+        assert_eq!(snapshot.frame.to_canvas().rows, canvas.rows);
+        assert_eq!(shown, canvas.rows.iter().map(|row| format!("{row}\r\n")).collect::<String>());
+    }
+
+    #[test]
+    fn noise_seed_varies_the_texture_while_the_art_seed_still_counts() {
+        let render = |argv: &[&str]| {
+            let args = args(&[&["--simulate", "--width", "16", "--height", "4", "--no-flow"], argv].concat());
+            let (_, metrics, seed) = seeded_source(&args).unwrap();
+            render_frame(&metrics, &mut StdRng::seed_from_u64(seed), &render_options(&args).unwrap()).intensity
+        };
+        let base = render(&["--seed", "21"]);
+        let textured = render(&["--seed", "21", "--noise-seed", "99"]);
+        assert_ne!(base, textured);
+        assert_eq!(render(&["--seed", "21", "--noise-seed", "99"]), textured);
+        assert_ne!(render(&["--seed", "22", "--noise-seed", "99"]), textured);
+        assert_eq!(render_options(&args(&["--noise-seed", "99"])).unwrap().noise_key, Some(morph_key(99)));
+    }
+
+    #[test]
+    fn dumped_config_reflects_cli_overrides() {
+        let config = |argv: &[&str]| serde_json::from_str::<serde_json::Value>(&to_json(&styled(argv), true, JsonCase::Snake).unwrap()).unwrap();
+        let defaults = config(&[]);
+        assert_eq!((defaults["style"].as_str(), defaults["interval"].as_u64()), (Some("plasma"), Some(500)));
+        let waves = config(&["--style", "waves"]);
+        assert_eq!((waves["style"].as_str(), waves["interval"].as_u64(), waves["width"].as_u64()), (Some("waves"), Some(300), Some(100)));
+        let pinned = config(&["--style", "waves", "--width", "40"]);
+        assert_eq!(pinned["width"].as_u64(), Some(40));
+    }
+
+    /// Parses `argv` as if `vars` were the whole environment: each `env =` name found in `vars` becomes that
+    /// argument's default, so flags still win, without touching the process environment other tests read.
+    fn args_with_env(vars: &[(&str, &'static str)], argv: &[&str]) -> Args {
+        let mut command = Args::command();
+        for arg in Args::command().get_arguments() {
+            let value = arg.get_env().and_then(|name| vars.iter().find(|(key, _)| name == *key)).map(|(_, value)| *value);
+            command = command.mut_arg(arg.get_id(), |arg| match value {
+                Some(value) => arg.env(None::<&str>).default_value(value),
+                None => arg.env(None::<&str>),
+            });
+        }
+        Args::from_arg_matches(&command.try_get_matches_from(["procgen-art"].iter().chain(argv)).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn env_vars_set_defaults_that_flags_override() {
+        assert_eq!(args_with_env(&[], &[]).fade_ticks, 8);
+        let vars = [("PROCGEN_ART_FADE_TICKS", "3")];
+        assert_eq!(args_with_env(&vars, &[]).fade_ticks, 3);
+        assert_eq!(args_with_env(&vars, &["--fade-ticks", "5"]).fade_ticks, 5);
+    }
+
+    #[test]
+    fn non_ascii_glyphs_fall_back_only_when_asked() {
+        let ramped = |argv: &[&str]| {
+            let args = args(&[&["--simulate", "--seed", "4", "--width", "16", "--height", "4", "--ramp", "░▒▓█"], argv].concat());
+            let (_, metrics, seed) = seeded_source(&args).unwrap();
+            let mut canvas = render_frame(&metrics, &mut StdRng::seed_from_u64(seed), &render_options(&args).unwrap());
+            decorate(&mut canvas, &args);
+            canvas.rows.concat()
+        };
+        assert!(ramped(&[]).contains(['░', '▒', '▓', '█']));
+        let ascii = ramped(&["--ascii-only"]);
+        assert!(ascii.is_ascii() && ascii.contains(DEFAULT_FALLBACK));
+        let custom = ramped(&["--fallback-glyph", "?"]);
+        assert!(custom.is_ascii() && custom.contains('?'));
+    }
+
+    #[test]
+    fn a_zero_interval_is_clamped() {
+        assert_eq!(styled(&["--interval", "0"]).interval, MIN_INTERVAL_MS);
+        assert_eq!(styled(&["--interval", "1"]).interval, 1);
+    }
+
+    #[test]
+    fn true_random_runs_get_fresh_seeds() {
+        let seed = |argv: &[&str]| resolve_seed(&args(argv)).unwrap();
+        assert_eq!(seed(&[]), None);
+        let (first, second) = (seed(&["--true-random"]), seed(&["--true-random"]));
+        assert!(first.is_some() && second.is_some());
+        assert_ne!(first, second);
+        let frame = |argv: &[&str]| {
+            let args = args(&[&["--simulate", "--width", "16", "--height", "4"], argv].concat());
+            let (_, metrics, seed) = seeded_source(&args).unwrap();
+            render_frame(&metrics, &mut StdRng::seed_from_u64(seed), &render_options(&args).unwrap()).rows
+        };
+        assert_eq!(frame(&[]), frame(&[]));
+        assert_ne!(frame(&["--true-random"]), frame(&["--true-random"]));
+    }
+
+    #[test]
+    fn stamp_commit_records_a_non_empty_commit() {
+        let snap = |argv: &[&str]| {
+            let args = args(&[&["--simulate", "--seed", "4", "--width", "8", "--height", "3"], argv].concat());
+            let (_, metrics, seed) = seeded_source(&args).unwrap();
+            let opts = render_options(&args).unwrap();
+            let canvas = render_frame(&metrics, &mut StdRng::seed_from_u64(seed), &opts);
+            capture(&args, &metrics, &canvas, &opts).commit
+        };
+        assert_eq!(snap(&[]), None);
+        let commit = snap(&["--stamp-commit"]).unwrap();
+        assert!(!commit.trim().is_empty());
+        assert_eq!(commit, BUILD_COMMIT);
+    }
+
+    #[test]
+    fn color_256_overrides_a_truecolor_terminal() {
+        let args = args(&["--color", "256", "--simulate", "--seed", "2", "--width", "12", "--height", "3"]);
+        let depth = args.color.resolve(ColorDepth::detect("xterm-256color", "truecolor"));
+        assert_eq!(depth, ColorDepth::Ansi256);
+        let (_, metrics, seed) = seeded_source(&args).unwrap();
+        let opts = render_options(&args).unwrap();
+        let canvas = render_frame(&metrics, &mut StdRng::seed_from_u64(seed), &opts);
+        let display = DisplayOptions {
+            smooth: false,
+            sixel: false,
+            image: None,
+            mono: false,
+            no_clear: false,
+            depth,
+            palette_bias: false,
+            palette: None,
+            banded: false,
+            min_contrast: None,
+            origin: (0, 0),
+            screen: ScreenMode::Inline,
+        };
+        let mut shown = Vec::new();
+        display_frame(&mut shown, &canvas, &opts.style, &display).unwrap();
+        let shown = String::from_utf8(shown).unwrap();
+        assert!(shown.contains("\x1b[48;5;"));
+        assert!(!shown.contains("\x1b[48;2;"));
+    }
+
+    #[test]
+    fn subcommands_parse_and_top_level_flags_work_without_one() {
+        let bare = args(&["--style", "waves", "--once", "--interval", "40"]);
+        assert!(bare.command.is_none());
+        assert_eq!((bare.style.as_str(), bare.once, bare.interval), ("waves", true, 40));
+        assert!(matches!(args(&["--once", "run"]).command, Some(Command::Run)));
+        assert!(matches!(args(&["metrics", "--oneline", "--watch"]).command, Some(Command::Metrics { oneline: true, watch: true, prometheus: false, .. })));
+    }
+}
@@ -0,0 +1,256 @@
+use crate::{
+    graphics::{draw_label, rasterize},
+    metrics::Metrics,
+    render::{morph_key, render_frame, stamp, Canvas, Flow, RenderOptions},
+};
+use image::RgbImage;
+use rand::{rngs::StdRng, SeedableRng};
+use serde::Serialize;
+use std::thread;
+
+#[derive(Clone, Copy, Serialize)]
+pub struct PanelGrid {
+    pub rows: u16,
+    pub cols: u16,
+}
+
+impl PanelGrid {
+    pub fn count(&self) -> usize {
+        self.rows as usize * self.cols as usize
+    }
+
+    pub fn panel_size(&self, width: u16, height: u16) -> (u16, u16) {
+        (width / self.cols, height / self.rows)
+    }
+}
+
+pub struct Panel {
+    pub seed: u64,
+    pub rng: StdRng,
+}
+
+pub fn parse_panels(value: &str) -> Result<PanelGrid, String> {
+    let (rows, cols) = value
+        .split_once('x')
+        .ok_or_else(|| format!("expected <rows>x<cols>, got '{value}'"))?;
+    let rows: u16 = rows.parse().map_err(|_| format!("invalid panel rows '{rows}'"))?;
+    let cols: u16 = cols.parse().map_err(|_| format!("invalid panel columns '{cols}'"))?;
+    if rows == 0 || cols == 0 {
+        return Err("panel grid needs at least one row and one column".to_string());
+    }
+    Ok(PanelGrid { rows, cols })
+}
+
+pub fn panel_seed(base: u64, index: usize) -> u64 {
+    base.wrapping_add((index as u64 + 1).wrapping_mul(0x9e37_79b9_7f4a_7c15))
+}
+
+pub fn make_panels(base: u64, grid: PanelGrid) -> Vec<Panel> {
+    (0..grid.count())
+        .map(|index| {
+            let seed = panel_seed(base, index);
+            Panel {
+                seed,
+                rng: StdRng::seed_from_u64(seed),
+            }
+        })
+        .collect()
+}
+
+pub fn seed_sequence(start: u64, count: usize) -> Vec<u64> {
+    (0..count as u64).map(|offset| start.wrapping_add(offset)).collect()
+}
+
+pub fn contact_grid(count: usize) -> PanelGrid {
+    let count = count.max(1);
+    let cols = (count as f64).sqrt().ceil() as usize;
+    let rows = count.div_ceil(cols);
+    PanelGrid {
+        rows: rows as u16,
+        cols: cols as u16,
+    }
+}
+
+pub fn seeded_panels(seeds: &[u64]) -> Vec<Panel> {
+    seeds
+        .iter()
+        .map(|&seed| Panel {
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+        })
+        .collect()
+}
+
+pub fn panel_label(index: usize, seed: u64) -> String {
+    format!("#{} {}", index + 1, seed)
+}
+
+fn fit(row: &str, width: usize) -> String {
+    let mut fitted: String = row.chars().take(width).collect();
+    let len = fitted.chars().count();
+    fitted.extend(std::iter::repeat_n(' ', width - len));
+    fitted
+}
+
+pub fn render_panels(metrics: &Metrics, panels: &mut [Panel], opts: &RenderOptions, grid: PanelGrid) -> Canvas {
+    let workers = thread::available_parallelism().map_or(1, usize::from);
+    render_panels_on(metrics, panels, opts, grid, workers)
+}
+
+fn render_panels_on(metrics: &Metrics, panels: &mut [Panel], opts: &RenderOptions, grid: PanelGrid, workers: usize) -> Canvas {
+    let (panel_width, panel_height) = grid.panel_size(opts.width, opts.height);
+    let panel_opts = RenderOptions {
+        width: panel_width,
+        height: panel_height,
+        ..opts.clone()
+    };
+    let chunk = panels.len().div_ceil(workers).max(1);
+    let render = |index: usize, panel: &mut Panel| {
+        let flow = panel_opts.flow.map(|flow| Flow { key: morph_key(panel.seed), ..flow });
+        let mut canvas = render_frame(metrics, &mut panel.rng, &RenderOptions { flow, ..panel_opts.clone() });
+        if let Some(first) = canvas.rows.first_mut() {
+            stamp(first, 0, &panel_label(index, panel.seed));
+        }
+        canvas
+    };
+    let rendered: Vec<Canvas> = thread::scope(|scope| {
+        let workers: Vec<_> = panels
+            .chunks_mut(chunk)
+            .enumerate()
+            .map(|(part, batch)| {
+                let render = &render;
+                scope.spawn(move || {
+                    batch
+                        .iter_mut()
+                        .enumerate()
+                        .map(|(offset, panel)| render(part * chunk + offset, panel))
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().expect("panel render thread panicked"))
+            .collect()
+    });
+
+    let mut rows = Vec::with_capacity(opts.height as usize);
+    let mut intensity = Vec::with_capacity(opts.height as usize);
+    for band in rendered.chunks(grid.cols as usize) {
+        for y in 0..panel_height as usize {
+            let mut row = String::new();
+            let mut values = Vec::new();
+            for canvas in band {
+                row.push_str(&fit(&canvas.rows[y], panel_width as usize));
+                values.extend_from_slice(&canvas.intensity[y]);
+            }
+            rows.push(row);
+            intensity.push(values);
+        }
+    }
+    let first = rendered.first();
+    Canvas {
+        rows,
+        intensity,
+        overlay_row: first.map_or(0, |canvas| canvas.overlay_row),
+        alerts: first.map_or_else(Vec::new, |canvas| canvas.alerts.clone()),
+        inputs: first.map_or_else(Default::default, |canvas| canvas.inputs),
+        sanitized: rendered.iter().map(|canvas| canvas.sanitized).sum(),
+    }
+}
+
+/// Rasterizes a `render_panels` canvas and labels each thumbnail's top-left corner like the text version does.
+pub fn contact_sheet(canvas: &Canvas, panels: &[Panel], opts: &RenderOptions, grid: PanelGrid, colors: &[(u8, u8, u8)], scale_x: usize, scale_y: usize) -> RgbImage {
+    let (panel_width, panel_height) = grid.panel_size(opts.width, opts.height);
+    let (tile_width, tile_height) = (panel_width as u32 * scale_x as u32, panel_height as u32 * scale_y as u32);
+    let mut image = rasterize(&canvas.intensity, colors, scale_x, scale_y);
+    for (index, panel) in panels.iter().enumerate() {
+        let (row, col) = (index as u32 / grid.cols as u32, index as u32 % grid.cols as u32);
+        draw_label(&mut image, col * tile_width, row * tile_height, tile_width, &panel_label(index, panel.seed));
+    }
+    image
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        metrics::{MetricsSource, MockSource},
+        sixel::{SCALE_X, SCALE_Y},
+    };
+    use image::Rgb;
+    use std::collections::HashSet;
+
+    #[test]
+    fn panel_seeds_are_distinct_and_derived_from_the_base() {
+        let grid = PanelGrid { rows: 3, cols: 4 };
+        let seeds: Vec<u64> = make_panels(42, grid).iter().map(|panel| panel.seed).collect();
+        assert_eq!(seeds.len(), 12);
+        assert_eq!(seeds.iter().collect::<HashSet<_>>().len(), 12);
+        assert_eq!(seeds, make_panels(42, grid).iter().map(|panel| panel.seed).collect::<Vec<_>>());
+        assert_eq!(seeds[0], panel_seed(42, 0));
+        assert!(!seeds.contains(&42));
+        assert_ne!(seeds[0], make_panels(43, grid)[0].seed);
+    }
+
+    #[test]
+    fn seed_grid_lists_consecutive_seeds_in_a_grid_that_fits_them() {
+        assert_eq!(seed_sequence(100, 5), [100, 101, 102, 103, 104]);
+        assert_eq!(seed_sequence(u64::MAX, 2), [u64::MAX, 0]);
+        for count in [1, 2, 5, 9, 10, 17] {
+            let grid = contact_grid(count);
+            assert!(grid.count() >= count && grid.count() < count + grid.cols as usize, "{count} in {}x{}", grid.rows, grid.cols);
+            assert!(grid.rows <= grid.cols);
+        }
+    }
+
+    #[test]
+    fn threaded_and_sequential_panels_render_identically() {
+        let grid = PanelGrid { rows: 2, cols: 3 };
+        let metrics = MockSource::new(5).sample();
+        let opts = RenderOptions::new(48, 12, "plasma");
+        let render = |workers: usize| {
+            let mut panels = make_panels(42, grid);
+            let first = render_panels_on(&metrics, &mut panels, &opts, grid, workers);
+            let second = render_panels_on(&metrics, &mut panels, &opts, grid, workers);
+            (first.rows, first.intensity, second.rows)
+        };
+        let sequential = render(1);
+        for workers in [2, 4, 16] {
+            assert_eq!(render(workers), sequential, "{workers} workers");
+        }
+    }
+
+    #[test]
+    fn flowing_panels_each_draw_their_own_art() {
+        let grid = PanelGrid { rows: 1, cols: 2 };
+        let opts = RenderOptions { overlay: false, flow: Some(Flow { key: morph_key(1), frame: 7 }), ..RenderOptions::new(32, 6, "plasma") };
+        let canvas = render_panels(&MockSource::new(1).sample(), &mut make_panels(1, grid), &opts, grid);
+        let (left, right): (Vec<&[f32]>, Vec<&[f32]>) = canvas.intensity.iter().map(|row| row.split_at(16)).unzip();
+        assert_ne!(left, right);
+    }
+
+    #[test]
+    fn contact_sheet_labels_each_tile_in_text_and_png() {
+        let seeds = seed_sequence(40, 5);
+        let grid = contact_grid(seeds.len());
+        let opts = RenderOptions { overlay: false, ..RenderOptions::new(20 * grid.cols, 4 * grid.rows, "plasma") };
+        let mut panels = seeded_panels(&seeds);
+        let canvas = render_panels(&MockSource::new(5).sample(), &mut panels, &opts, grid);
+        let image = contact_sheet(&canvas, &panels, &opts, grid, &[(10, 20, 30), (40, 50, 60)], SCALE_X, SCALE_Y);
+        assert_eq!(image.dimensions(), (20 * 3 * SCALE_X as u32, 4 * 2 * SCALE_Y as u32));
+        let white = Rgb([255, 255, 255]);
+        for slot in 0..grid.count() {
+            let (row, col) = (slot / grid.cols as usize, slot % grid.cols as usize);
+            let (x, y) = ((col * 20 * SCALE_X) as u32, (row * 4 * SCALE_Y) as u32);
+            match seeds.get(slot) {
+                Some(&seed) => {
+                    let label = panel_label(slot, seed);
+                    assert!(canvas.rows[row * 4].chars().skip(col * 20).collect::<String>().starts_with(&label), "{label} missing from {:?}", canvas.rows[row * 4]);
+                    assert_eq!((image[(x, y)], image[(x + 1, y + 1)], image[(x + 2, y + 1)]), (Rgb([0, 0, 0]), white, Rgb([0, 0, 0])), "{label}");
+                }
+                None => assert_ne!(image[(x + 1, y + 1)], white, "empty slot {slot} is labelled"),
+            }
+        }
+    }
+}
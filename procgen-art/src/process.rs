@@ -0,0 +1,48 @@
+use std::cmp::Ordering;
+use sysinfo::{PidExt, ProcessExt, System, SystemExt};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProcessMetrics {
+    pub pid: u32,
+    pub name: String,
+    pub cpu_usage: f32,
+}
+
+pub fn processes(sys: &System) -> Vec<ProcessMetrics> {
+    sys.processes()
+        .iter()
+        .map(|(pid, process)| ProcessMetrics { pid: pid.as_u32(), name: process.name().to_string(), cpu_usage: process.cpu_usage() })
+        .collect()
+}
+
+fn busiest_first(a: &ProcessMetrics, b: &ProcessMetrics) -> Ordering {
+    b.cpu_usage.total_cmp(&a.cpu_usage).then(a.pid.cmp(&b.pid)).then_with(|| a.name.cmp(&b.name))
+}
+
+pub fn top_process(processes: &[ProcessMetrics]) -> Option<&ProcessMetrics> {
+    processes.iter().min_by(|a, b| busiest_first(a, b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn process(pid: u32, name: &str, cpu_usage: f32) -> ProcessMetrics {
+        ProcessMetrics { pid, name: name.to_string(), cpu_usage }
+    }
+
+    #[test]
+    fn equal_cpu_picks_lower_pid() {
+        let procs = [process(42, "b", 12.5), process(7, "a", 12.5)];
+        assert_eq!(top_process(&procs).map(|proc| proc.pid), Some(7));
+        let reversed = [procs[1].clone(), procs[0].clone()];
+        assert_eq!(top_process(&reversed).map(|proc| proc.pid), Some(7));
+    }
+
+    #[test]
+    fn busier_process_wins_over_pid() {
+        let procs = [process(1, "idle", 0.5), process(900, "busy", 80.0)];
+        assert_eq!(top_process(&procs).map(|proc| proc.name.as_str()), Some("busy"));
+        assert_eq!(top_process(&[]), None);
+    }
+}
@@ -0,0 +1,83 @@
+use crate::metrics::{DiskMetrics, Metrics};
+use std::fmt::Write;
+
+fn escape_label(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+fn gauge(out: &mut String, name: &str, help: &str, samples: &[(Option<(&str, String)>, f64)]) {
+    let _ = writeln!(out, "# HELP {name} {help}");
+    let _ = writeln!(out, "# TYPE {name} gauge");
+    for (label, value) in samples {
+        match label {
+            Some((key, label)) => {
+                let _ = writeln!(out, "{name}{{{key}=\"{}\"}} {value}", escape_label(label));
+            }
+            None => {
+                let _ = writeln!(out, "{name} {value}");
+            }
+        }
+    }
+}
+
+pub fn prometheus_text(metrics: &Metrics) -> String {
+    let mut out = String::new();
+    gauge(&mut out, "procgen_cpu_usage", "Global CPU usage in percent.", &[(None, metrics.cpu_usage as f64)]);
+    let cores: Vec<_> = metrics
+        .cpu_cores
+        .iter()
+        .enumerate()
+        .map(|(idx, usage)| (Some(("core", idx.to_string())), *usage as f64))
+        .collect();
+    gauge(&mut out, "procgen_cpu_core_usage", "Per-core CPU usage in percent.", &cores);
+    gauge(&mut out, "procgen_load_average", "One-minute load average.", &[(None, metrics.load_avg)]);
+    gauge(&mut out, "procgen_memory_total_bytes", "Total memory in bytes.", &[(None, metrics.total_memory as f64)]);
+    gauge(&mut out, "procgen_memory_used_bytes", "Used memory in bytes.", &[(None, metrics.used_memory as f64)]);
+    gauge(&mut out, "procgen_network_received_bytes", "Bytes received since the last sample.", &[(None, metrics.network_rx as f64)]);
+    gauge(&mut out, "procgen_network_transmitted_bytes", "Bytes transmitted since the last sample.", &[(None, metrics.network_tx as f64)]);
+    let disk = |value: fn(&DiskMetrics) -> u64| -> Vec<_> {
+        metrics
+            .disk_usage
+            .iter()
+            .map(|disk| (Some(("disk", disk.name.clone())), value(disk) as f64))
+            .collect()
+    };
+    gauge(&mut out, "procgen_disk_total_bytes", "Disk capacity in bytes.", &disk(|disk| disk.total_space));
+    gauge(&mut out, "procgen_disk_available_bytes", "Available disk space in bytes.", &disk(|disk| disk.available_space));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::{MetricsSource, MockSource};
+
+    #[test]
+    fn output_is_valid_exposition_text() {
+        let mut metrics = MockSource::new(2).sample();
+        metrics.disk_usage[0].name = "my \"disk\"\\1".to_string();
+        let text = prometheus_text(&metrics);
+        let mut typed = Vec::new();
+        for line in text.lines() {
+            if let Some(rest) = line.strip_prefix("# TYPE ") {
+                let (name, kind) = rest.split_once(' ').unwrap();
+                assert_eq!(kind, "gauge");
+                typed.push(name);
+                continue;
+            }
+            if line.starts_with("# HELP ") {
+                continue;
+            }
+            let (series, value) = line.rsplit_once(' ').unwrap();
+            assert!(value.parse::<f64>().is_ok(), "{line}");
+            let name = series.split('{').next().unwrap();
+            assert!(name.chars().all(|ch| ch.is_ascii_alphanumeric() || ch == '_'), "{line}");
+            assert_eq!(typed.last(), Some(&name), "{line} has no TYPE line");
+            if let Some(labels) = series.strip_prefix(name).filter(|labels| !labels.is_empty()) {
+                assert!(labels.starts_with('{') && labels.ends_with("\"}") && labels.contains("=\""), "{line}");
+            }
+        }
+        assert!(text.lines().any(|line| line.starts_with("procgen_cpu_usage ")));
+        assert!(text.contains("{disk=\"my \\\"disk\\\"\\\\1\"}"));
+    }
+}
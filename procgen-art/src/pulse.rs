@@ -0,0 +1,30 @@
+use std::f32::consts::TAU;
+
+pub const PULSE_BASE_TICKS: f32 = 40.0;
+pub const PULSE_MIN_TICKS: f32 = 4.0;
+pub const PULSE_DEPTH: f32 = 0.35;
+
+pub fn pulse_period(load: f64) -> f32 {
+    (PULSE_BASE_TICKS / (1.0 + load.max(0.0) as f32)).max(PULSE_MIN_TICKS)
+}
+
+pub fn pulse_multiplier(tick: u64, load: f64) -> f32 {
+    let phase = (tick as f32 / pulse_period(load)).fract();
+    1.0 - PULSE_DEPTH * (1.0 - (phase * TAU).cos()) / 2.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pulse_completes_a_cycle_in_the_load_period() {
+        assert_eq!(pulse_period(0.0), 40.0);
+        assert_eq!(pulse_period(3.0), 10.0);
+        assert_eq!(pulse_period(100.0), PULSE_MIN_TICKS);
+        assert_eq!(pulse_multiplier(0, 3.0), 1.0);
+        assert!((pulse_multiplier(5, 3.0) - (1.0 - PULSE_DEPTH)).abs() < 1e-6);
+        assert_eq!(pulse_multiplier(10, 3.0), 1.0);
+        assert!((pulse_multiplier(13, 3.0) - pulse_multiplier(3, 3.0)).abs() < 1e-6);
+    }
+}
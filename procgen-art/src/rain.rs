@@ -0,0 +1,103 @@
+use crate::{
+    metrics::{Metrics, NormalizedMetrics},
+    render::{art_char, Canvas},
+};
+use rand::{rngs::StdRng, Rng};
+
+pub const RAIN_STYLE: &str = "rain";
+pub const RAIN_MAX_FALL: f32 = 4.0;
+pub const RAIN_TRAIL: usize = 8;
+
+#[derive(Clone, Copy)]
+pub struct RainTuning {
+    pub speed: f32,
+    pub density: f32,
+}
+
+impl Default for RainTuning {
+    fn default() -> Self {
+        RainTuning { speed: 1.0, density: 1.0 }
+    }
+}
+
+pub fn fall_rows(network_rx: u64, speed: f32) -> usize {
+    let rate = ((network_rx as f32).ln_1p() / 15.0).clamp(0.0, 1.0);
+    1 + (rate * RAIN_MAX_FALL * speed.max(0.0)).round() as usize
+}
+
+pub fn spawn_chance(cpu: f32, density: f32) -> f32 {
+    ((0.02 + cpu.clamp(0.0, 1.0) * 0.3) * density.max(0.0)).clamp(0.0, 1.0)
+}
+
+struct Raindrop {
+    x: usize,
+    y: usize,
+    trail: usize,
+}
+
+#[derive(Default)]
+pub struct Rain {
+    drops: Vec<Raindrop>,
+    size: (usize, usize),
+}
+
+impl Rain {
+    pub fn step(&mut self, metrics: &Metrics, tuning: RainTuning, rng: &mut StdRng, width: u16, height: u16) {
+        let (width, height) = (width as usize, height as usize);
+        if self.size != (width, height) {
+            self.drops.clear();
+            self.size = (width, height);
+        }
+        let fall = fall_rows(metrics.network_rx, tuning.speed);
+        for drop in &mut self.drops {
+            drop.y += fall;
+        }
+        self.drops.retain(|drop| drop.y < height + drop.trail);
+        let chance = spawn_chance(NormalizedMetrics::from_metrics(metrics).cpu, tuning.density);
+        for x in 0..width {
+            if rng.gen::<f32>() < chance {
+                self.drops.push(Raindrop { x, y: 0, trail: rng.gen_range(2..=RAIN_TRAIL) });
+            }
+        }
+    }
+
+    pub fn canvas(&self, ramp: &str, inputs: NormalizedMetrics) -> Canvas {
+        let (width, height) = self.size;
+        let mut intensity = vec![vec![0.0f32; width]; height];
+        for drop in &self.drops {
+            for step in 0..drop.trail {
+                let Some(y) = drop.y.checked_sub(step).filter(|&y| y < height) else {
+                    continue;
+                };
+                let cell = &mut intensity[y][drop.x];
+                *cell = cell.max(1.0 - step as f32 / drop.trail as f32);
+            }
+        }
+        Canvas {
+            rows: intensity
+                .iter()
+                .map(|row| row.iter().map(|&value| art_char(ramp, value)).collect())
+                .collect(),
+            intensity,
+            overlay_row: usize::MAX,
+            alerts: Vec::new(),
+            inputs,
+            sanitized: 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn higher_network_rate_advances_more_rows_per_frame() {
+        let idle = fall_rows(0, 1.0);
+        let busy = fall_rows(10_000_000, 1.0);
+        assert_eq!(idle, 1);
+        assert!(busy > idle);
+        assert!(fall_rows(1_000, 1.0) <= busy);
+        assert_eq!(fall_rows(10_000_000, 0.0), 1);
+    }
+}
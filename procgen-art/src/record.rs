@@ -0,0 +1,299 @@
+use crate::{
+    color::{ansi_rgb, gradient},
+    metrics::NormalizedMetrics,
+    render::{palette, Canvas},
+    snapshot::{FrameData, Snapshot},
+};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{File, OpenOptions},
+    io::{self, BufRead, BufWriter, Write},
+    path::Path,
+    time::{Duration, Instant},
+};
+
+const FRAME_MARKER: &str = "--- frame ";
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, ValueEnum, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RecordFormat {
+    Ndjson,
+    Text,
+    Ansi,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RecordedFrame {
+    #[serde(flatten)]
+    pub snapshot: Snapshot,
+    pub interval_ms: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub elapsed_us: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delta_us: Option<u64>,
+}
+
+pub struct Recorder {
+    out: BufWriter<File>,
+    start: Instant,
+    last: Option<Instant>,
+}
+
+impl Recorder {
+    pub fn create(path: &Path) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Recorder { out: BufWriter::new(file), start: Instant::now(), last: None })
+    }
+
+    pub fn write(&mut self, mut frame: RecordedFrame) -> io::Result<()> {
+        let now = Instant::now();
+        frame.elapsed_us = Some(now.duration_since(self.start).as_micros() as u64);
+        frame.delta_us = Some(self.last.replace(now).map_or(0, |last| now.duration_since(last).as_micros() as u64));
+        serde_json::to_writer(&mut self.out, &frame)?;
+        writeln!(self.out)?;
+        self.out.flush()
+    }
+}
+
+/// Appends frames as plain rows under `--- frame N ---` markers, optionally with truecolor backgrounds from the
+/// style's palette so `cat` shows the colours. Each frame is flushed as it is written, so an interrupted run
+/// keeps everything up to its last frame.
+pub struct TextRecorder {
+    out: BufWriter<File>,
+    ansi: bool,
+    frames: u64,
+}
+
+impl TextRecorder {
+    pub fn create(path: &Path, ansi: bool) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(TextRecorder { out: BufWriter::new(file), ansi, frames: 0 })
+    }
+
+    pub fn write(&mut self, canvas: &Canvas, style: &str) -> io::Result<()> {
+        self.frames += 1;
+        writeln!(self.out, "{FRAME_MARKER}{} ---", self.frames)?;
+        let colors = palette(style);
+        for (y, row) in canvas.rows.iter().enumerate() {
+            match canvas.intensity.get(y).filter(|_| self.ansi) {
+                Some(values) => {
+                    for (ch, intensity) in row.chars().zip(values.iter().chain(std::iter::repeat(&0.0))) {
+                        let (r, g, b) = ansi_rgb(gradient(&colors, *intensity));
+                        write!(self.out, "\x1b[48;2;{r};{g};{b}m{ch}")?;
+                    }
+                    writeln!(self.out, "\x1b[0m")?;
+                }
+                None => writeln!(self.out, "{row}")?,
+            }
+        }
+        self.out.flush()
+    }
+}
+
+fn strip_ansi(line: &str) -> String {
+    let mut plain = String::with_capacity(line.len());
+    let mut chars = line.chars();
+    while let Some(ch) = chars.next() {
+        if ch == '\x1b' {
+            if chars.next() == Some('[') {
+                chars.by_ref().find(|ch| ('@'..='~').contains(ch));
+            }
+        } else {
+            plain.push(ch);
+        }
+    }
+    plain
+}
+
+pub fn load_text_recording<R: BufRead>(reader: R) -> io::Result<Vec<Vec<String>>> {
+    let mut frames: Vec<Vec<String>> = Vec::new();
+    for line in reader.lines() {
+        let line = line?;
+        if line.starts_with(FRAME_MARKER) && line.ends_with(" ---") {
+            frames.push(Vec::new());
+        } else if let Some(frame) = frames.last_mut() {
+            frame.push(strip_ansi(&line));
+        } else if !line.trim().is_empty() {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, format!("expected a {FRAME_MARKER}N --- marker, found {line:?}")));
+        }
+    }
+    Ok(frames)
+}
+
+pub struct PlaybackFrame {
+    pub canvas: Canvas,
+    pub style: String,
+    pub delay: Duration,
+}
+
+/// Loads either recording format: text recordings start with a frame marker, take their colours from `style`
+/// and wait `interval_ms` between frames, while NDJSON ones carry their own style and timing.
+pub fn load_playback<R: BufRead>(mut reader: R, style: &str, interval_ms: u64) -> io::Result<Vec<PlaybackFrame>> {
+    if reader.fill_buf()?.starts_with(FRAME_MARKER.as_bytes()) {
+        let frames = load_text_recording(reader)?;
+        return Ok(frames
+            .into_iter()
+            .map(|rows| PlaybackFrame {
+                canvas: FrameData::Text(rows).to_canvas(),
+                style: style.to_string(),
+                delay: Duration::from_millis(interval_ms),
+            })
+            .collect());
+    }
+    let frames = load_recording(reader)?;
+    Ok((0..frames.len())
+        .map(|idx| PlaybackFrame {
+            canvas: frames[idx].snapshot.frame.to_canvas(),
+            style: frames[idx].snapshot.style.clone(),
+            delay: playback_delay(&frames, idx),
+        })
+        .collect())
+}
+
+#[derive(Serialize)]
+pub struct FrameLogEntry {
+    pub timestamp_ms: u64,
+    pub tick: u64,
+    pub metrics: NormalizedMetrics,
+    pub load_avg: f64,
+    pub checksum: String,
+    pub render_us: u64,
+}
+
+pub struct FrameLog {
+    out: BufWriter<File>,
+}
+
+impl FrameLog {
+    pub fn open(path: &Path) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(FrameLog { out: BufWriter::new(file) })
+    }
+
+    pub fn write(&mut self, entry: &FrameLogEntry) -> io::Result<()> {
+        serde_json::to_writer(&mut self.out, entry)?;
+        writeln!(self.out)?;
+        self.out.flush()
+    }
+}
+
+pub fn playback_delay(frames: &[RecordedFrame], idx: usize) -> Duration {
+    match frames.get(idx + 1).and_then(|next| next.delta_us) {
+        Some(delta) => Duration::from_micros(delta),
+        None => Duration::from_millis(frames.get(idx).map_or(0, |frame| frame.interval_ms)),
+    }
+}
+
+pub fn load_recording<R: BufRead>(reader: R) -> io::Result<Vec<RecordedFrame>> {
+    reader
+        .lines()
+        .filter(|line| line.as_ref().map_or(true, |line| !line.trim().is_empty()))
+        .map(|line| Ok(serde_json::from_str(&line?)?))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        metrics::{MetricsSource, MockSource},
+        render::{render_frame, RenderOptions},
+        snapshot::{frame_checksum, FrameData},
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+    use std::{env, fs, io::BufReader, path::PathBuf, process};
+
+    fn frame(rows: &[&str], interval_ms: u64, delta_us: Option<u64>) -> RecordedFrame {
+        let snapshot = Snapshot {
+            metrics: MockSource::new(1).sample(),
+            frame: FrameData::Text(rows.iter().map(|row| row.to_string()).collect()),
+            width: rows[0].len() as u16,
+            height: rows.len() as u16,
+            style: "plasma".to_string(),
+            stats: None,
+            tick: None,
+            rng: None,
+            checksum: None,
+            commit: None,
+            env: None,
+            palette: None,
+            history: None,
+        };
+        RecordedFrame { snapshot, interval_ms, elapsed_us: None, delta_us }
+    }
+
+    fn temp_path(name: &str) -> PathBuf {
+        env::temp_dir().join(format!("procgen-{name}-{}.jsonl", process::id()))
+    }
+
+    #[test]
+    fn a_two_frame_recording_plays_back_in_order() {
+        let path = temp_path("record");
+        let mut recorder = Recorder::create(&path).unwrap();
+        recorder.write(frame(&["ab", "cd"], 50, None)).unwrap();
+        recorder.write(frame(&["ef", "gh"], 50, None)).unwrap();
+        drop(recorder);
+        let frames = load_recording(BufReader::new(File::open(&path).unwrap())).unwrap();
+        fs::remove_file(&path).unwrap();
+        let rows: Vec<Vec<String>> = frames.iter().map(|frame| frame.snapshot.frame.to_canvas().rows).collect();
+        assert_eq!(rows, [["ab", "cd"], ["ef", "gh"]]);
+        assert_eq!(frames.iter().map(|frame| frame.interval_ms).collect::<Vec<_>>(), [50, 50]);
+        assert_eq!(frames[0].delta_us, Some(0));
+    }
+
+    #[test]
+    fn playback_waits_for_the_recorded_deltas() {
+        let frames = [frame(&["a"], 50, Some(0)), frame(&["b"], 50, Some(120_000)), frame(&["c"], 50, Some(30_000)), frame(&["d"], 50, None)];
+        let waits: Vec<Duration> = (0..frames.len()).map(|idx| playback_delay(&frames, idx)).collect();
+        assert_eq!(waits, [Duration::from_millis(120), Duration::from_millis(30), Duration::from_millis(50), Duration::from_millis(50)]);
+        let untimed = [frame(&["a"], 80, None), frame(&["b"], 80, None)];
+        assert_eq!(playback_delay(&untimed, 0), Duration::from_millis(80));
+    }
+
+    #[test]
+    fn frame_log_gains_one_record_per_frame() {
+        let path = temp_path("frame-log");
+        let _ = fs::remove_file(&path);
+        let metrics = MockSource::new(2).sample();
+        let mut log = FrameLog::open(&path).unwrap();
+        for tick in 0..3 {
+            log.write(&FrameLogEntry {
+                timestamp_ms: 1_000 + tick,
+                tick,
+                metrics: NormalizedMetrics::from_metrics(&metrics),
+                load_avg: metrics.load_avg,
+                checksum: frame_checksum(&["ab".to_string()]),
+                render_us: 50,
+            })
+            .unwrap();
+            let lines = fs::read_to_string(&path).unwrap().lines().map(str::to_string).collect::<Vec<_>>();
+            assert_eq!(lines.len(), tick as usize + 1);
+            let record: serde_json::Value = serde_json::from_str(lines.last().unwrap()).unwrap();
+            assert_eq!(record["tick"], tick);
+        }
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn text_recordings_replay_their_rows_with_or_without_colour() {
+        let metrics = MockSource::new(3).sample();
+        let canvases: Vec<Canvas> = (0..2).map(|tick| render_frame(&metrics, &mut StdRng::seed_from_u64(tick), &RenderOptions::new(12, 3, "plasma"))).collect();
+        for ansi in [false, true] {
+            let path = temp_path(if ansi { "record-ansi" } else { "record-text" });
+            let _ = fs::remove_file(&path);
+            let mut recorder = TextRecorder::create(&path, ansi).unwrap();
+            for canvas in &canvases {
+                recorder.write(canvas, "plasma").unwrap();
+            }
+            drop(recorder);
+            let written = fs::read_to_string(&path).unwrap();
+            assert_eq!(written.lines().filter(|line| line.starts_with("--- frame ")).collect::<Vec<_>>(), ["--- frame 1 ---", "--- frame 2 ---"]);
+            assert_eq!(written.contains("\x1b[48;2;"), ansi);
+            let frames = load_playback(BufReader::new(File::open(&path).unwrap()), "waves", 70).unwrap();
+            fs::remove_file(&path).unwrap();
+            assert_eq!(frames.iter().map(|frame| &frame.canvas.rows).collect::<Vec<_>>(), canvases.iter().map(|canvas| &canvas.rows).collect::<Vec<_>>());
+            assert!(frames.iter().all(|frame| frame.style == "waves" && frame.delay == Duration::from_millis(70)));
+        }
+    }
+}
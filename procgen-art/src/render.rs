@@ -0,0 +1,813 @@
+use crate::{
+    filters::FilterKind,
+    mask::Mask,
+    metrics::{Metrics, NormalizedMetrics},
+    rain::RainTuning,
+    styles::{load_ramp, style_spec},
+    substrate::{modulate, Substrate},
+    theme::theme_palette,
+};
+use clap::ValueEnum;
+use crossterm::style::Color;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::Serialize;
+use std::{
+    cmp::min,
+    io::{self, Write},
+    sync::Arc,
+};
+
+pub const DEFAULT_FALLBACK: char = '#';
+pub const PATTERN_WEIGHT: f32 = 0.5;
+pub const CPU_WEIGHT: f32 = 0.2;
+pub const MEMORY_WEIGHT: f32 = 0.1;
+pub const NETWORK_WEIGHT: f32 = 0.2;
+pub const DISK_BAND: f32 = 0.25;
+pub const FLOW_CELL: f32 = 6.0;
+pub const FLOW_DRIFT: f32 = 0.0625;
+// The noise lattice wraps at u16, so after 65536 / FLOW_DRIFT frames the field is back where it started.
+pub const FLOW_PERIOD: u64 = 1 << 20;
+pub const FLOW_PHASE_FRAMES: u64 = 64;
+pub const FLOW_PHASE_STEP: f32 = std::f32::consts::TAU / FLOW_PHASE_FRAMES as f32;
+
+pub fn blend_intensity(pattern: f32, cpu: f32, memory: f32, network: f32) -> f32 {
+    (PATTERN_WEIGHT * pattern + CPU_WEIGHT * cpu + MEMORY_WEIGHT * memory + NETWORK_WEIGHT * network).clamp(0.0, 1.0)
+}
+
+pub fn disk_waterline(disk: f32, height: u16) -> Option<u16> {
+    (disk > 0.0 && height > 0).then(|| ((1.0 - disk.clamp(0.0, 1.0)) * (height - 1) as f32).round() as u16)
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum OverlayPos {
+    Top,
+    Bottom,
+    Center,
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum OverlayLine {
+    Cpu,
+    Mem,
+    Net,
+    Disk,
+    Temp,
+}
+
+#[derive(Clone, Copy)]
+pub struct Influence {
+    pub cpu: f32,
+    pub memory: f32,
+    pub network: f32,
+}
+
+impl Default for Influence {
+    fn default() -> Self {
+        Influence { cpu: 1.0, memory: 1.0, network: 1.0 }
+    }
+}
+
+#[derive(Clone)]
+pub struct RenderOptions {
+    pub width: u16,
+    pub height: u16,
+    pub style: String,
+    pub overlay_pos: OverlayPos,
+    pub alert_cpu: Option<f32>,
+    pub alert_mem: Option<f32>,
+    pub watermark: bool,
+    pub overlay: bool,
+    pub substrate: Option<Arc<Substrate>>,
+    pub frozen: bool,
+    pub brightness: f32,
+    pub ramp: Option<String>,
+    pub ramp_by_load: bool,
+    pub levels: Option<u8>,
+    pub influence: Influence,
+    pub center: Option<(f32, f32)>,
+    pub loop_phase: Option<f32>,
+    pub overlay_scale: u8,
+    pub overlay_lines: Vec<OverlayLine>,
+    pub rain: RainTuning,
+    pub filters: Vec<FilterKind>,
+    pub zoom: f32,
+    pub pan: (f32, f32),
+    pub scanlines: Option<u16>,
+    pub scanline_roll: bool,
+    pub morph: Option<Morph>,
+    pub flow: Option<Flow>,
+    pub noise_key: Option<u64>,
+    pub mask: Option<Arc<Mask>>,
+}
+
+impl RenderOptions {
+    pub fn new(width: u16, height: u16, style: &str) -> Self {
+        RenderOptions {
+            width,
+            height,
+            style: style.to_string(),
+            overlay_pos: OverlayPos::Center,
+            alert_cpu: None,
+            alert_mem: None,
+            watermark: false,
+            overlay: true,
+            substrate: None,
+            frozen: false,
+            brightness: 1.0,
+            ramp: None,
+            ramp_by_load: false,
+            levels: None,
+            influence: Influence::default(),
+            center: None,
+            loop_phase: None,
+            overlay_scale: 1,
+            overlay_lines: Vec::new(),
+            rain: RainTuning::default(),
+            filters: Vec::new(),
+            zoom: 1.0,
+            pan: (0.0, 0.0),
+            scanlines: None,
+            scanline_roll: false,
+            morph: None,
+            flow: None,
+            noise_key: None,
+            mask: None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Morph {
+    pub key: u64,
+    pub t: f32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Flow {
+    pub key: u64,
+    pub frame: u64,
+}
+
+#[derive(Clone)]
+pub struct Canvas {
+    pub rows: Vec<String>,
+    pub intensity: Vec<Vec<f32>>,
+    pub overlay_row: usize,
+    pub alerts: Vec<&'static str>,
+    pub inputs: NormalizedMetrics,
+    pub sanitized: usize,
+}
+
+pub fn palette(style: &str) -> Vec<Color> {
+    theme_palette().map_or_else(|| style_spec(style).palette.to_vec(), <[Color]>::to_vec)
+}
+
+pub fn palette_stops(len: usize, intensity: f32) -> (usize, usize, f32) {
+    if len < 2 {
+        return (0, 0, 0.0);
+    }
+    let pos = intensity.clamp(0.0, 1.0) * (len - 1) as f32;
+    let lo = (pos.floor() as usize).min(len - 2);
+    (lo, lo + 1, pos - lo as f32)
+}
+
+pub fn dither_stop(lo: usize, hi: usize, frac: f32, x: usize, y: usize) -> usize {
+    const BAYER: [[u8; 4]; 4] = [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]];
+    let threshold = (BAYER[y % 4][x % 4] as f32 + 0.5) / 16.0;
+    if frac > threshold {
+        hi
+    } else {
+        lo
+    }
+}
+
+pub fn dominant_bias(inputs: &NormalizedMetrics) -> (f32, f32) {
+    [(0.0, inputs.cpu), (0.35, inputs.memory), (0.7, inputs.disk), (1.0, inputs.network)]
+        .into_iter()
+        .fold((0.0, 0.0), |best, candidate| {
+            if candidate.1 > best.1 {
+                candidate
+            } else {
+                best
+            }
+        })
+}
+
+pub fn biased_index(len: usize, intensity: f32, inputs: &NormalizedMetrics) -> usize {
+    if len == 0 {
+        return 0;
+    }
+    let (target, strength) = dominant_bias(inputs);
+    let pos = intensity.clamp(0.0, 1.0) * (1.0 - strength) + target * strength;
+    ((pos * (len - 1) as f32).round() as usize).min(len - 1)
+}
+
+pub fn quantize(intensity: f32, levels: u8) -> f32 {
+    let steps = levels.saturating_sub(1).max(1) as f32;
+    (intensity.clamp(0.0, 1.0) * steps).round() / steps
+}
+
+pub fn parse_fraction(value: &str) -> Result<f32, String> {
+    let fraction: f32 = value.parse().map_err(|_| format!("invalid fraction '{value}'"))?;
+    if !(0.0..=1.0).contains(&fraction) {
+        return Err("expected a fraction between 0.0 and 1.0".to_string());
+    }
+    Ok(fraction)
+}
+
+pub fn parse_zoom(value: &str) -> Result<f32, String> {
+    let zoom: f32 = value.parse().map_err(|_| format!("invalid zoom '{value}'"))?;
+    if !(zoom.is_finite() && zoom > 0.0) {
+        return Err("zoom must be a positive number".to_string());
+    }
+    Ok(zoom)
+}
+
+pub fn field_coord(cell: u16, extent: u16, zoom: f32, pan: f32) -> f32 {
+    cell as f32 / extent as f32 / zoom + pan
+}
+
+pub fn parse_ramp(value: &str) -> Result<String, String> {
+    if value.is_empty() {
+        return Err("ramp needs at least one character".to_string());
+    }
+    Ok(value.to_string())
+}
+
+pub fn cap_canvas(width: u16, height: u16, max_cells: Option<u32>) -> (u16, u16) {
+    let cells = width as u32 * height as u32;
+    match max_cells {
+        Some(limit) if cells > limit => {
+            let scale = (limit as f64 / cells as f64).sqrt();
+            let shrink = |side: u16| ((side as f64 * scale).floor() as u16).max(1);
+            let height = shrink(height);
+            ((shrink(width) as u32).min((limit / height as u32).max(1)) as u16, height)
+        }
+        _ => (width, height),
+    }
+}
+
+pub fn effective_ramp<'a>(opts: &'a RenderOptions, inputs: &NormalizedMetrics) -> &'a str {
+    match &opts.ramp {
+        Some(ramp) => ramp,
+        None if opts.ramp_by_load => load_ramp(style_spec(&opts.style), inputs.cpu),
+        None => style_spec(&opts.style).ramp,
+    }
+}
+
+pub fn art_char(ramp: &str, intensity: f32) -> char {
+    let len = ramp.chars().count();
+    if len == 0 {
+        return '*';
+    }
+    let idx = ((intensity.clamp(0.0, 1.0)) * (len as f32 - 1.0)).round() as usize;
+    ramp.chars().nth(min(idx, len - 1)).unwrap_or('*')
+}
+
+pub fn overlay_origin(pos: OverlayPos, text_len: usize, width: usize, height: usize) -> (usize, usize) {
+    let row = match pos {
+        OverlayPos::Top | OverlayPos::TopLeft | OverlayPos::TopRight => 0,
+        OverlayPos::Center => height / 2,
+        OverlayPos::Bottom | OverlayPos::BottomLeft | OverlayPos::BottomRight => height.saturating_sub(1),
+    };
+    let col = match pos {
+        OverlayPos::TopLeft | OverlayPos::BottomLeft => 0,
+        OverlayPos::TopRight | OverlayPos::BottomRight => width.saturating_sub(text_len),
+        _ => width.saturating_sub(text_len) / 2,
+    };
+    (row, col)
+}
+
+pub fn evaluate_alerts(metrics: &Metrics, opts: &RenderOptions) -> Vec<&'static str> {
+    let memory_pct = NormalizedMetrics::from_metrics(metrics).memory * 100.0;
+    let mut alerts = Vec::new();
+    if opts.alert_cpu.is_some_and(|limit| metrics.cpu_usage > limit) {
+        alerts.push("CPU");
+    }
+    if opts.alert_mem.is_some_and(|limit| memory_pct > limit) {
+        alerts.push("MEM");
+    }
+    alerts
+}
+
+pub fn overlay_text(metrics: &Metrics, frozen: bool, alerts: &[&str]) -> String {
+    let mut text = format!(
+        "CPU {:>5.1}% | MEM {:>5.1}% | NET {:>7.1}k/s",
+        metrics.cpu_usage,
+        NormalizedMetrics::from_metrics(metrics).memory * 100.0,
+        metrics.network_rx.saturating_add(metrics.network_tx) as f32 / 1024.0
+    );
+    if frozen {
+        text.push_str(" | FROZEN");
+    }
+    if !alerts.is_empty() {
+        text.push_str(&format!(" | ALERT {}", alerts.join(" ")));
+    }
+    text
+}
+
+pub fn overlay_line(line: OverlayLine, metrics: &Metrics) -> String {
+    let inputs = NormalizedMetrics::from_metrics(metrics);
+    match line {
+        OverlayLine::Cpu => format!("CPU  {:>5.1}%", metrics.cpu_usage),
+        OverlayLine::Mem => format!("MEM  {:>5.1}%", inputs.memory * 100.0),
+        OverlayLine::Net => format!("NET  {:>7.1}k/s", metrics.network_rx.saturating_add(metrics.network_tx) as f32 / 1024.0),
+        OverlayLine::Disk => format!("DISK {:>5.1}%", inputs.disk * 100.0),
+        OverlayLine::Temp => match metrics.temperature {
+            Some(temp) => format!("TEMP {temp:>5.1}C"),
+            None => "TEMP   n/a".to_string(),
+        },
+    }
+}
+
+pub fn overlay_block(metrics: &Metrics, frozen: bool, alerts: &[&str], lines: &[OverlayLine]) -> Vec<String> {
+    if lines.is_empty() {
+        return vec![overlay_text(metrics, frozen, alerts)];
+    }
+    let mut block: Vec<String> = lines.iter().map(|&line| overlay_line(line, metrics)).collect();
+    if frozen {
+        block.push("FROZEN".to_string());
+    }
+    if !alerts.is_empty() {
+        block.push(format!("ALERT {}", alerts.join(" ")));
+    }
+    let width = block.iter().map(|line| line.chars().count()).max().unwrap_or(0);
+    block.into_iter().map(|line| format!("{line:<width$}")).collect()
+}
+
+pub fn scale_text(text: &str, scale: u8) -> String {
+    text.chars().flat_map(|ch| std::iter::repeat_n(ch, scale.max(1) as usize)).collect()
+}
+
+pub fn layout_overlay(metrics: &Metrics, opts: &RenderOptions, alerts: &[&str]) -> (Vec<String>, usize, usize) {
+    let block: Vec<String> = overlay_block(metrics, opts.frozen, alerts, &opts.overlay_lines)
+        .iter()
+        .map(|line| scale_text(line, opts.overlay_scale))
+        .collect();
+    let rows = block.len() * opts.overlay_scale.max(1) as usize;
+    let text_len = block.iter().map(|line| line.chars().count()).max().unwrap_or(0);
+    let (row, col) = overlay_origin(opts.overlay_pos, text_len, opts.width as usize, opts.height as usize);
+    let row = match opts.overlay_pos {
+        OverlayPos::Center => row.saturating_sub(rows / 2),
+        _ => row,
+    };
+    (block, row.min((opts.height as usize).saturating_sub(rows)), col)
+}
+
+pub fn overlay_line_at(block: &[String], top: usize, scale: u8, y: usize) -> Option<&str> {
+    let offset = y.checked_sub(top)? / scale.max(1) as usize;
+    block.get(offset).map(String::as_str)
+}
+
+pub fn stamp(line: &mut String, start: usize, text: &str) {
+    let mut chars: Vec<char> = line.chars().collect();
+    for (i, ch) in text.chars().enumerate() {
+        if let Some(slot) = chars.get_mut(start + i) {
+            *slot = ch;
+        }
+    }
+    *line = chars.into_iter().collect();
+}
+
+pub fn looped_noise(a: f32, b: f32, phase: f32) -> f32 {
+    let angle = phase * std::f32::consts::TAU;
+    (0.5 + (a - 0.5) * angle.cos() + (b - 0.5) * angle.sin()).clamp(0.0, 1.0)
+}
+
+pub fn value_noise(key: u64, px: f32, py: f32) -> f32 {
+    let (ix, iy) = (px.floor(), py.floor());
+    let smooth = |t: f32| t * t * (3.0 - 2.0 * t);
+    let (tx, ty) = (smooth(px - ix), smooth(py - iy));
+    let (x0, y0) = (ix as i64 as u16, iy as i64 as u16);
+    let corner = |dx: u16, dy: u16| cell_noise(key, x0.wrapping_add(dx), y0.wrapping_add(dy), 2);
+    let top = corner(0, 0) + (corner(1, 0) - corner(0, 0)) * tx;
+    let bottom = corner(0, 1) + (corner(1, 1) - corner(0, 1)) * tx;
+    top + (bottom - top) * ty
+}
+
+pub fn flow_noise(flow: Flow, x: u16, y: u16) -> f32 {
+    value_noise(flow.key, x as f32 / FLOW_CELL + (flow.frame % FLOW_PERIOD) as f32 * FLOW_DRIFT, y as f32 / FLOW_CELL)
+}
+
+pub fn morph_key(seed: u64) -> u64 {
+    StdRng::seed_from_u64(seed).gen()
+}
+
+pub fn cell_noise(key: u64, x: u16, y: u16, salt: u64) -> f32 {
+    let cell = x as u64 | (y as u64) << 16 | salt << 32;
+    let mut z = key ^ cell.wrapping_mul(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    ((z ^ (z >> 31)) >> 40) as f32 / (1u64 << 24) as f32
+}
+
+pub fn replace_unsupported(rows: &mut [String], fallback: char) {
+    for row in rows.iter_mut().filter(|row| !row.is_ascii()) {
+        *row = row.chars().map(|ch| if ch.is_ascii() { ch } else { fallback }).collect();
+    }
+}
+
+pub fn sanitize(intensity: f32) -> Option<f32> {
+    (!intensity.is_finite()).then_some(0.0)
+}
+
+pub fn render_rows<F>(metrics: &Metrics, rng: &mut StdRng, opts: &RenderOptions, mut emit: F) -> io::Result<usize>
+where
+    F: FnMut(String, Vec<f32>) -> io::Result<()>,
+{
+    let (width, height, style) = (opts.width, opts.height, opts.style.as_str());
+    let inputs = NormalizedMetrics::from_metrics(metrics);
+    let ramp = effective_ramp(opts, &inputs);
+    let NormalizedMetrics { cpu, memory, network, .. } = inputs;
+    let (cpu, memory, network) = (cpu * opts.influence.cpu, memory * opts.influence.memory, network * opts.influence.network);
+    let base_seed = metrics.entropy;
+    let (block, overlay_row, start) = layout_overlay(metrics, opts, &evaluate_alerts(metrics, opts));
+    let frame_key: u64 = rng.gen();
+    let waterline = disk_waterline(inputs.disk, height);
+    let mut sanitized = 0;
+
+    for y in 0..height {
+        let mut row = String::with_capacity(width as usize);
+        let mut values = Vec::with_capacity(width as usize);
+        for x in 0..width {
+            let sample = |key: u64| match opts.loop_phase {
+                Some(phase) => looped_noise(cell_noise(key, x, y, 0), cell_noise(key, x, y, 1), phase),
+                None => cell_noise(key, x, y, 0),
+            };
+            let noise = match (opts.flow, opts.morph) {
+                (Some(flow), _) => flow_noise(flow, x, y),
+                (None, Some(Morph { key, t })) => sample(frame_key) + (sample(key) - sample(frame_key)) * t,
+                (None, None) => sample(frame_key),
+            };
+            let texture = opts.noise_key.map_or(noise, sample);
+            let (fx, fy) = (field_coord(x, width, opts.zoom, opts.pan.0), field_coord(y, height, opts.zoom, opts.pan.1));
+            let gradient = match opts.center {
+                Some((cx, cy)) => (fx - cx).hypot(fy - cy) * (cpu + memory),
+                None => fx * cpu + fy * memory,
+            };
+            let drift = opts.flow.map_or(0.0, |flow| (flow.frame % FLOW_PHASE_FRAMES) as f32 * FLOW_PHASE_STEP);
+            let swirl = (gradient + noise * network + drift).sin();
+            let mut intensity = blend_intensity(((swirl + 1.0) / 2.0 + texture) / 2.0, cpu, memory, network);
+            if waterline == Some(y) {
+                intensity = (intensity + DISK_BAND).min(1.0);
+            }
+            if let Some(substrate) = &opts.substrate {
+                intensity = modulate(intensity, substrate.luma_at(x, y, width, height));
+            }
+            intensity = (intensity * opts.brightness).clamp(0.0, 1.0);
+            if let Some(levels) = opts.levels {
+                intensity = quantize(intensity, levels);
+            }
+            if let Some(clean) = sanitize(intensity) {
+                intensity = clean;
+                sanitized += 1;
+            }
+            row.push(art_char(ramp, intensity));
+            values.push(intensity);
+        }
+        if let Some(text) = overlay_line_at(&block, overlay_row, opts.overlay_scale, y as usize).filter(|_| opts.overlay) {
+            stamp(&mut row, start, text);
+        }
+        if opts.watermark && y == 0 && base_seed.is_multiple_of(7) {
+            row = format!("{:<width$.width$}", format!("Style: {style} | Frames seeded by entropy {base_seed}"), width = width as usize);
+        }
+        emit(row, values)?;
+    }
+    Ok(sanitized)
+}
+
+pub fn render_frame(metrics: &Metrics, rng: &mut StdRng, opts: &RenderOptions) -> Canvas {
+    let alerts = evaluate_alerts(metrics, opts);
+    let mut canvas = Canvas {
+        rows: Vec::with_capacity(opts.height as usize),
+        intensity: Vec::with_capacity(opts.height as usize),
+        overlay_row: if opts.overlay { layout_overlay(metrics, opts, &alerts).1 } else { usize::MAX },
+        alerts,
+        inputs: NormalizedMetrics::from_metrics(metrics),
+        sanitized: 0,
+    };
+    canvas.sanitized = render_rows(metrics, rng, opts, |row, values| {
+        canvas.rows.push(row);
+        canvas.intensity.push(values);
+        Ok(())
+    })
+    .unwrap_or(0);
+    canvas
+}
+
+pub fn stream_frame<W: Write>(metrics: &Metrics, rng: &mut StdRng, opts: &RenderOptions, out: &mut W) -> io::Result<()> {
+    render_rows(metrics, rng, opts, |row, _| writeln!(out, "{row}"))?;
+    out.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::{MetricsSource, MockSource};
+
+    fn flowing(frame: u64) -> Canvas {
+        let opts = RenderOptions { flow: Some(Flow { key: 11, frame }), ..RenderOptions::new(24, 6, "plasma") };
+        render_frame(&MockSource::new(5).sample(), &mut StdRng::seed_from_u64(5), &opts)
+    }
+
+    #[test]
+    fn flow_frames_are_deterministic_and_drift_slowly() {
+        let (now, next) = (flowing(40), flowing(41));
+        assert_eq!(now.rows, flowing(40).rows);
+        assert_ne!(now.intensity, next.intensity);
+        let moved = now.intensity.iter().flatten().zip(next.intensity.iter().flatten()).map(|(a, b)| (a - b).abs()).fold(0.0, f32::max);
+        assert!(moved < 0.1, "neighbouring frames differ by {moved}");
+        assert_eq!(flowing(FLOW_PERIOD + 3).intensity, flowing(3).intensity);
+    }
+
+    #[test]
+    fn busier_network_brightens_the_art() {
+        let mean_index = |network: u64| {
+            let metrics = Metrics { network_rx: network, network_tx: 0, ..MockSource::new(6).sample() };
+            let opts = RenderOptions::new(40, 10, "plasma");
+            let ramp = effective_ramp(&opts, &NormalizedMetrics::from_metrics(&metrics)).to_string();
+            let canvas = render_frame(&metrics, &mut StdRng::seed_from_u64(6), &opts);
+            let indices: Vec<usize> = canvas.rows.iter().flat_map(|row| row.chars()).filter_map(|ch| ramp.chars().position(|step| step == ch)).collect();
+            indices.iter().sum::<usize>() as f32 / indices.len() as f32
+        };
+        let (idle, saturated) = (mean_index(0), mean_index(50 << 20));
+        assert!(saturated > idle + 0.5, "idle {idle}, saturated {saturated}");
+    }
+
+    #[test]
+    fn watermark_is_opt_in_and_fits_the_row() {
+        let watermarked = |entropy: u64, watermark: bool, width: u16| {
+            let metrics = Metrics { entropy, ..MockSource::new(8).sample() };
+            let opts = RenderOptions { watermark, overlay: false, ..RenderOptions::new(width, 3, "plasma") };
+            render_frame(&metrics, &mut StdRng::seed_from_u64(8), &opts).rows[0].clone()
+        };
+        for entropy in (0..70).step_by(7) {
+            assert!(!watermarked(entropy, false, 60).starts_with("Style:"));
+        }
+        let (short, wide) = (watermarked(14, true, 10), watermarked(14, true, 60));
+        assert_eq!((short.as_str(), short.chars().count()), ("Style: pla", 10));
+        assert!(wide.starts_with("Style: plasma | Frames seeded by entropy 14") && wide.chars().count() == 60);
+    }
+
+    #[test]
+    fn overlay_positions_map_to_rows_and_columns() {
+        let expected = [
+            (OverlayPos::Top, (0, 15)),
+            (OverlayPos::TopLeft, (0, 0)),
+            (OverlayPos::TopRight, (0, 30)),
+            (OverlayPos::Center, (5, 15)),
+            (OverlayPos::Bottom, (9, 15)),
+            (OverlayPos::BottomLeft, (9, 0)),
+            (OverlayPos::BottomRight, (9, 30)),
+        ];
+        for (pos, origin) in expected {
+            assert_eq!(overlay_origin(pos, 10, 40, 10), origin);
+        }
+        assert_eq!(overlay_origin(OverlayPos::BottomRight, 50, 40, 0), (0, 0));
+    }
+
+    #[test]
+    fn streamed_rows_match_the_buffered_frame() {
+        let (metrics, opts) = (MockSource::new(12).sample(), RenderOptions::new(33, 9, "plasma"));
+        let mut out = Vec::new();
+        stream_frame(&metrics, &mut StdRng::seed_from_u64(12), &opts, &mut out).unwrap();
+        let buffered = render_frame(&metrics, &mut StdRng::seed_from_u64(12), &opts);
+        assert_eq!(String::from_utf8(out).unwrap(), format!("{}\n", buffered.rows.join("\n")));
+    }
+
+    #[test]
+    fn alerts_fire_only_above_their_thresholds() {
+        let opts = RenderOptions { alert_cpu: Some(80.0), alert_mem: Some(50.0), ..RenderOptions::new(10, 2, "plasma") };
+        let metrics = |cpu_usage: f32, used_memory: u64| Metrics { cpu_usage, used_memory, total_memory: 100, ..MockSource::new(1).sample() };
+        assert!(evaluate_alerts(&metrics(40.0, 30), &opts).is_empty());
+        assert!(evaluate_alerts(&metrics(80.0, 50), &opts).is_empty());
+        assert_eq!(evaluate_alerts(&metrics(95.0, 30), &opts), ["CPU"]);
+        assert_eq!(evaluate_alerts(&metrics(95.0, 75), &opts), ["CPU", "MEM"]);
+        assert!(evaluate_alerts(&metrics(95.0, 75), &RenderOptions::new(10, 2, "plasma")).is_empty());
+    }
+
+    #[test]
+    fn intensity_between_stops_picks_the_surrounding_pair() {
+        let (lo, hi, frac) = palette_stops(5, 0.6);
+        assert_eq!((lo, hi), (2, 3));
+        assert!((frac - 0.4).abs() < 1e-5);
+        assert_eq!(palette_stops(5, 1.0), (3, 4, 1.0));
+        assert_eq!(palette_stops(1, 0.5), (0, 0, 0.0));
+        let picks: Vec<usize> = (0..4).flat_map(|y| (0..4).map(move |x| dither_stop(lo, hi, frac, x, y))).collect();
+        assert_eq!(picks.iter().filter(|&&stop| stop == hi).count(), 6);
+    }
+
+    #[test]
+    fn hot_metrics_pull_the_palette_index_toward_their_end() {
+        let network_heavy = NormalizedMetrics { cpu: 0.1, memory: 0.2, network: 0.9, disk: 0.0 };
+        let cpu_heavy = NormalizedMetrics { cpu: 0.9, memory: 0.2, network: 0.1, disk: 0.0 };
+        assert_eq!(biased_index(8, 0.5, &network_heavy), 7);
+        assert_eq!(biased_index(8, 0.5, &cpu_heavy), 0);
+        assert_eq!(biased_index(8, 0.5, &NormalizedMetrics::default()), 4);
+        assert_eq!(biased_index(0, 0.5, &network_heavy), 0);
+    }
+
+    #[test]
+    fn custom_ramps_drive_art_char() {
+        assert_eq!(art_char("ab", 0.0), 'a');
+        assert_eq!(art_char("ab", 1.0), 'b');
+        assert_eq!(art_char("abc", 0.5), 'b');
+        assert!([0.0, 0.3, 0.7, 1.0, 2.0].iter().all(|&intensity| art_char("x", intensity) == 'x'));
+        let opts = RenderOptions { ramp: Some("x".to_string()), ..RenderOptions::new(12, 4, "plasma") };
+        let canvas = render_frame(&MockSource::new(3).sample(), &mut StdRng::seed_from_u64(3), &opts);
+        let art = canvas.rows.iter().enumerate().filter(|(row, _)| *row != canvas.overlay_row);
+        assert!(art.flat_map(|(_, row)| row.chars()).all(|ch| ch == 'x'));
+    }
+
+    #[test]
+    fn two_levels_leave_at_most_two_intensities() {
+        let opts = RenderOptions { levels: Some(2), ..RenderOptions::new(24, 6, "plasma") };
+        let canvas = render_frame(&MockSource::new(8).sample(), &mut StdRng::seed_from_u64(8), &opts);
+        let mut seen: Vec<f32> = canvas.intensity.iter().flatten().copied().collect();
+        seen.sort_by(f32::total_cmp);
+        seen.dedup();
+        assert!(seen.len() <= 2 && seen.iter().all(|value| [0.0, 1.0].contains(value)), "{seen:?}");
+    }
+
+    #[test]
+    fn zero_influence_leaves_only_the_noise_pattern() {
+        let opts = RenderOptions { influence: Influence { cpu: 0.0, memory: 0.0, network: 0.0 }, overlay: false, ..RenderOptions::new(12, 4, "plasma") };
+        let field = |tick: usize| {
+            let mut source = MockSource::new(4);
+            let mut metrics = (0..tick).map(|_| source.sample()).last().unwrap();
+            metrics.disk_usage.clear();
+            render_frame(&metrics, &mut StdRng::seed_from_u64(6), &opts).intensity
+        };
+        let key: u64 = StdRng::seed_from_u64(6).gen();
+        assert_eq!(field(1), field(9));
+        for (y, row) in field(1).iter().enumerate() {
+            for (x, value) in row.iter().enumerate() {
+                let pattern = blend_intensity((0.5 + cell_noise(key, x as u16, y as u16, 0)) / 2.0, 0.0, 0.0, 0.0);
+                assert!((value - pattern).abs() < 1e-6);
+            }
+        }
+    }
+
+    #[test]
+    fn high_load_switches_to_the_jagged_ramp() {
+        let opts = RenderOptions { ramp_by_load: true, ..RenderOptions::new(8, 2, "plasma") };
+        let at = |cpu: f32| NormalizedMetrics { cpu, ..NormalizedMetrics::default() };
+        assert_eq!(effective_ramp(&opts, &at(0.1)), " .:+*#%@");
+        assert_eq!(effective_ramp(&opts, &at(0.9)), " /\\|<>X#@");
+        assert_eq!(art_char(effective_ramp(&opts, &at(0.9)), 0.5), '<');
+        assert_eq!(effective_ramp(&RenderOptions::new(8, 2, "plasma"), &at(0.9)), " .:+*#%@");
+    }
+
+    #[test]
+    fn moving_the_center_moves_the_radial_minimum() {
+        let mut metrics = MockSource::new(4).sample();
+        metrics.disk_usage.clear();
+        let key: u64 = StdRng::seed_from_u64(6).gen();
+        let trough = |center: (f32, f32)| {
+            let opts = RenderOptions {
+                center: Some(center),
+                influence: Influence { network: 0.0, ..Influence::default() },
+                overlay: false,
+                ..RenderOptions::new(20, 10, "plasma")
+            };
+            let canvas = render_frame(&metrics, &mut StdRng::seed_from_u64(6), &opts);
+            let radial = |x: usize, y: usize| canvas.intensity[y][x] - PATTERN_WEIGHT / 2.0 * cell_noise(key, x as u16, y as u16, 0);
+            let cells = (0..10).flat_map(|y| (0..20).map(move |x| (x, y)));
+            cells.min_by(|&(ax, ay), &(bx, by)| radial(ax, ay).total_cmp(&radial(bx, by))).unwrap()
+        };
+        assert_eq!(trough((0.5, 0.5)), (10, 5));
+        assert_eq!(trough((0.25, 0.2)), (5, 2));
+    }
+
+    #[test]
+    fn over_budget_canvases_shrink_keeping_their_aspect() {
+        assert_eq!(cap_canvas(200, 50, Some(2500)), (100, 25));
+        let (width, height) = cap_canvas(200, 50, Some(1000));
+        assert_eq!((width, height), (63, 15));
+        assert!(width as u32 * height as u32 <= 1000);
+        assert_eq!(cap_canvas(200, 50, Some(1)), (1, 1));
+        assert_eq!(cap_canvas(80, 24, Some(1920)), (80, 24));
+        assert_eq!(cap_canvas(80, 24, None), (80, 24));
+    }
+
+    #[test]
+    fn a_seamless_loop_wraps_with_a_small_step() {
+        let metrics = MockSource::new(2).sample();
+        let frames = 24;
+        let field = |tick: u64| {
+            let opts = RenderOptions { loop_phase: Some(tick as f32 / frames as f32), ..RenderOptions::new(24, 6, "plasma") };
+            render_frame(&metrics, &mut StdRng::seed_from_u64(2), &opts).intensity
+        };
+        let distance = |a: &[Vec<f32>], b: &[Vec<f32>]| a.iter().flatten().zip(b.iter().flatten()).map(|(a, b)| (a - b).abs()).fold(0.0, f32::max);
+        let (first, last) = (field(0), field(frames - 1));
+        let step = distance(&field(0), &field(1));
+        assert!(distance(&last, &first) <= step * 1.5 + 1e-6, "wrap {} vs step {step}", distance(&last, &first));
+        assert!(distance(&field(frames), &first) < 1e-5);
+        assert!(distance(&field(frames / 2), &first) > step);
+    }
+
+    #[test]
+    fn scaled_overlays_widen_and_repeat_rows() {
+        let metrics = MockSource::new(1).sample();
+        let plain = overlay_text(&metrics, false, &[]);
+        let opts = RenderOptions { overlay_scale: 3, overlay_pos: OverlayPos::Bottom, ..RenderOptions::new(200, 20, "plasma") };
+        let (block, top, col) = layout_overlay(&metrics, &opts, &[]);
+        assert_eq!(scale_text("ab", 3), "aaabbb");
+        assert_eq!(block, vec![scale_text(&plain, 3)]);
+        assert_eq!(block[0].chars().count(), plain.chars().count() * 3);
+        assert_eq!(top, 17);
+        assert!(col + block[0].chars().count() <= 200);
+        let rows: Vec<_> = (15..20).map(|y| overlay_line_at(&block, top, 3, y).is_some()).collect();
+        assert_eq!(rows, [false, false, true, true, true]);
+    }
+
+    #[test]
+    fn overlay_lines_stack_on_consecutive_rows_inside_the_canvas() {
+        let metrics = MockSource::new(1).sample();
+        let lines = vec![OverlayLine::Cpu, OverlayLine::Mem, OverlayLine::Net];
+        let render = |height: u16| {
+            let opts = RenderOptions { overlay_lines: lines.clone(), overlay_pos: OverlayPos::BottomLeft, ..RenderOptions::new(20, height, "plasma") };
+            render_frame(&metrics, &mut StdRng::seed_from_u64(1), &opts).rows
+        };
+        let rows = render(6);
+        assert_eq!(rows.len(), 6);
+        assert!(rows.iter().all(|row| row.chars().count() == 20));
+        for (row, label) in rows[3..].iter().zip(["CPU", "MEM", "NET"]) {
+            assert!(row.starts_with(label), "{row:?}");
+        }
+        let short = render(2);
+        assert_eq!(short.len(), 2);
+        assert!(short[0].starts_with("CPU") && short[1].starts_with("MEM"));
+    }
+
+    #[test]
+    fn cell_noise_does_not_depend_on_iteration_order() {
+        let cells: Vec<(u16, u16)> = (0..6).flat_map(|y| (0..8).map(move |x| (x, y))).collect();
+        let forward: Vec<f32> = cells.iter().map(|&(x, y)| cell_noise(42, x, y, 0)).collect();
+        let mut backward: Vec<f32> = cells.iter().rev().map(|&(x, y)| cell_noise(42, x, y, 0)).collect();
+        backward.reverse();
+        assert_eq!(forward, backward);
+        assert_eq!(cell_noise(42, 3, 2, 0), cell_noise(42, 3, 2, 0));
+        assert_ne!(cell_noise(42, 3, 2, 0), cell_noise(42, 2, 3, 0));
+        assert!(forward.iter().all(|value| (0.0..1.0).contains(value)));
+    }
+
+    #[test]
+    fn zoom_two_samples_at_half_the_step() {
+        let step = |zoom: f32| field_coord(1, 40, zoom, 0.0) - field_coord(0, 40, zoom, 0.0);
+        assert!((step(2.0) - step(1.0) / 2.0).abs() < 1e-6);
+        assert!((field_coord(10, 40, 2.0, 0.5) - 0.625).abs() < 1e-6);
+        assert_eq!(parse_zoom("0"), Err("zoom must be a positive number".to_string()));
+    }
+
+    #[test]
+    fn nan_intensities_render_blank_and_are_counted() {
+        let opts = RenderOptions { brightness: f32::NAN, overlay: false, ..RenderOptions::new(10, 3, "plasma") };
+        let canvas = render_frame(&MockSource::new(2).sample(), &mut StdRng::seed_from_u64(2), &opts);
+        assert_eq!(canvas.sanitized, 30);
+        assert!(canvas.intensity.iter().flatten().all(|&value| value == 0.0));
+        let blank = art_char(effective_ramp(&opts, &canvas.inputs), 0.0);
+        assert!(canvas.rows.iter().all(|row| row.chars().all(|ch| ch == blank)));
+        let clean = render_frame(&MockSource::new(2).sample(), &mut StdRng::seed_from_u64(2), &RenderOptions { overlay: false, ..RenderOptions::new(10, 3, "plasma") });
+        assert_eq!(clean.sanitized, 0);
+    }
+
+    #[test]
+    fn morph_starts_on_the_source_and_ends_on_the_target() {
+        let metrics = MockSource::new(9).sample();
+        let base = RenderOptions { overlay: false, ..RenderOptions::new(16, 5, "plasma") };
+        let render = |seed: u64, morph: Option<Morph>| render_frame(&metrics, &mut StdRng::seed_from_u64(seed), &RenderOptions { morph, ..base.clone() }).intensity;
+        let (source, target) = (render(4, None), render(21, None));
+        assert_eq!(render(4, Some(Morph { key: morph_key(21), t: 0.0 })), source);
+        let end = render(4, Some(Morph { key: morph_key(21), t: 1.0 }));
+        assert!(end.iter().flatten().zip(target.iter().flatten()).all(|(a, b)| (a - b).abs() < 1e-5));
+        assert_ne!(source, target);
+    }
+
+    #[test]
+    fn noise_key_changes_only_the_texture_term() {
+        let metrics = MockSource::new(8).sample();
+        let base = RenderOptions { overlay: false, ..RenderOptions::new(20, 6, "plasma") };
+        let render = |key: u64| render_frame(&metrics, &mut StdRng::seed_from_u64(3), &RenderOptions { noise_key: Some(key), ..base.clone() }).intensity;
+        let (first, second) = (render(1), render(2));
+        assert_ne!(first, second);
+        let mut compared = 0;
+        for (y, (a_row, b_row)) in first.iter().zip(&second).enumerate() {
+            for (x, (&a, &b)) in a_row.iter().zip(b_row).enumerate() {
+                if [a, b].iter().any(|&value| value <= 0.0 || value >= 1.0) {
+                    continue;
+                }
+                let texture = cell_noise(1, x as u16, y as u16, 0) - cell_noise(2, x as u16, y as u16, 0);
+                assert!((a - b - PATTERN_WEIGHT * texture / 2.0).abs() < 1e-5, "cell ({x}, {y})");
+                compared += 1;
+            }
+        }
+        assert!(compared > 0);
+    }
+}
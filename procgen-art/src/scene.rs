@@ -0,0 +1,89 @@
+use crate::{
+    disks::{disk_canvas, DISK_STYLE},
+    eq::{Equalizer, EQ_STYLE},
+    filters::{apply_filters, apply_scanlines},
+    ink::{InkField, INK_STYLE},
+    mask::apply_mask,
+    memmap::{memmap_canvas, MEMMAP_STYLE},
+    metrics::{Metrics, NormalizedMetrics},
+    panels::{make_panels, render_panels, Panel, PanelGrid},
+    rain::{Rain, RAIN_STYLE},
+    render::{effective_ramp, palette, render_frame, Canvas, RenderOptions},
+    shapes::{shapes_canvas, SHAPES_STYLE},
+    solid::{solid_canvas, solid_level, SOLID_STYLE},
+    spectrum::{Spectrum, SPECTRUM_STYLE},
+};
+use rand::rngs::StdRng;
+
+pub struct Scene {
+    pub panels: Vec<Panel>,
+    pub grid: Option<PanelGrid>,
+    pub eq: Equalizer,
+    pub ink: InkField,
+    pub rain: Rain,
+    pub spectrum: Spectrum,
+    pub frame: u64,
+    pub scanline_roll: u64,
+}
+
+impl Scene {
+    pub fn new(seed: u64, grid: Option<PanelGrid>) -> Self {
+        let panels = grid.map_or_else(Vec::new, |grid| make_panels(seed, grid));
+        Scene { panels, grid, eq: Equalizer::default(), ink: InkField::default(), rain: Rain::default(), spectrum: Spectrum::default(), frame: 0, scanline_roll: 0 }
+    }
+}
+
+pub fn draw(metrics: &Metrics, rng: &mut StdRng, scene: &mut Scene, opts: &RenderOptions) -> Canvas {
+    let mut canvas = draw_style(metrics, rng, scene, opts);
+    let inputs = canvas.inputs;
+    let ramp = effective_ramp(opts, &inputs);
+    apply_filters(&mut canvas, &opts.filters, ramp);
+    if let Some(mask) = &opts.mask {
+        apply_mask(&mut canvas, mask);
+    }
+    if let Some(every) = opts.scanlines {
+        apply_scanlines(&mut canvas, every, scene.scanline_roll, ramp);
+        scene.scanline_roll += opts.scanline_roll as u64;
+    }
+    canvas
+}
+
+pub fn draw_style(metrics: &Metrics, rng: &mut StdRng, scene: &mut Scene, opts: &RenderOptions) -> Canvas {
+    let inputs = NormalizedMetrics::from_metrics(metrics);
+    let ramp = effective_ramp(opts, &inputs);
+    if opts.style == EQ_STYLE {
+        scene.eq.update(&metrics.cpu_cores);
+        return scene.eq.canvas(ramp, inputs, opts.width, opts.height);
+    }
+    if opts.style == SPECTRUM_STYLE {
+        scene.spectrum.update(&metrics.cpu_cores, opts.width.div_ceil(2) as usize);
+        return scene.spectrum.canvas(ramp, inputs, opts.width, opts.height);
+    }
+    if opts.style == SOLID_STYLE {
+        let level = solid_level(scene.frame, palette(&opts.style).len());
+        scene.frame += 1;
+        return solid_canvas(ramp.chars().last().unwrap_or('#'), level, inputs, opts.width, opts.height);
+    }
+    if opts.style == SHAPES_STYLE {
+        scene.frame += 1;
+        return shapes_canvas(ramp, inputs, scene.frame, opts.width, opts.height);
+    }
+    if opts.style == MEMMAP_STYLE {
+        return memmap_canvas(ramp, inputs, opts.width, opts.height);
+    }
+    if opts.style == DISK_STYLE {
+        return disk_canvas(&metrics.disk_usage, ramp, inputs, opts.width, opts.height);
+    }
+    if opts.style == RAIN_STYLE {
+        scene.rain.step(metrics, opts.rain, rng, opts.width, opts.height);
+        return scene.rain.canvas(ramp, inputs);
+    }
+    if opts.style == INK_STYLE {
+        scene.ink.step(&inputs, rng, opts.width, opts.height);
+        return scene.ink.canvas(ramp, inputs);
+    }
+    match scene.grid {
+        Some(grid) => render_panels(metrics, &mut scene.panels, opts, grid),
+        None => render_frame(metrics, rng, opts),
+    }
+}
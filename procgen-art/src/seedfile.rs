@@ -0,0 +1,65 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+pub fn read_or_create_seed(path: &Path) -> io::Result<u64> {
+    match fs::read_to_string(path) {
+        Ok(text) => text.trim().parse().map_err(|_| {
+            let message = format!("{} does not contain a u64 seed", path.display());
+            io::Error::new(io::ErrorKind::InvalidData, message)
+        }),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {
+            let seed = rand::random::<u64>();
+            fs::write(path, format!("{seed}\n"))?;
+            Ok(seed)
+        }
+        Err(err) => Err(err),
+    }
+}
+
+pub struct SeedWatch {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+}
+
+impl SeedWatch {
+    pub fn new(path: &Path) -> Self {
+        SeedWatch {
+            path: path.to_path_buf(),
+            modified: modified(path),
+        }
+    }
+
+    pub fn poll(&mut self) -> Option<u64> {
+        let current = modified(&self.path);
+        if current == self.modified {
+            return None;
+        }
+        self.modified = current;
+        read_or_create_seed(&self.path).ok()
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|meta| meta.modified()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{env, process};
+
+    #[test]
+    fn existing_seed_is_read_and_missing_file_is_created() {
+        let path = env::temp_dir().join(format!("procgen-seedfile-{}", process::id()));
+        let _ = fs::remove_file(&path);
+        let created = read_or_create_seed(&path).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), format!("{created}\n"));
+        assert_eq!(read_or_create_seed(&path).unwrap(), created);
+        fs::write(&path, "12345\n").unwrap();
+        assert_eq!(read_or_create_seed(&path).unwrap(), 12345);
+        fs::remove_file(&path).unwrap();
+    }
+}
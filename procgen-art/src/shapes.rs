@@ -0,0 +1,95 @@
+use crate::{
+    metrics::NormalizedMetrics,
+    render::{art_char, Canvas},
+};
+use std::f32::consts::TAU;
+
+pub const SHAPES_STYLE: &str = "shapes";
+pub const SPOKES: usize = 3;
+
+pub fn circle_points(cx: isize, cy: isize, radius: isize) -> Vec<(isize, isize)> {
+    let (mut x, mut y, mut err) = (radius, 0, 1 - radius);
+    let mut points = Vec::new();
+    while x >= y {
+        for (dx, dy) in [(x, y), (y, x), (-y, x), (-x, y), (-x, -y), (-y, -x), (y, -x), (x, -y)] {
+            points.push((cx + dx, cy + dy));
+        }
+        y += 1;
+        if err < 0 {
+            err += 2 * y + 1;
+        } else {
+            x -= 1;
+            err += 2 * (y - x) + 1;
+        }
+    }
+    points.sort_unstable();
+    points.dedup();
+    points
+}
+
+pub fn line_points(from: (isize, isize), to: (isize, isize)) -> Vec<(isize, isize)> {
+    let (dx, dy) = ((to.0 - from.0).abs(), -(to.1 - from.1).abs());
+    let (sx, sy) = ((to.0 - from.0).signum(), (to.1 - from.1).signum());
+    let (mut x, mut y, mut err) = (from.0, from.1, dx + dy);
+    let mut points = vec![(x, y)];
+    while (x, y) != to {
+        let doubled = 2 * err;
+        if doubled >= dy {
+            err += dy;
+            x += sx;
+        }
+        if doubled <= dx {
+            err += dx;
+            y += sy;
+        }
+        points.push((x, y));
+    }
+    points
+}
+
+pub fn shapes_canvas(ramp: &str, inputs: NormalizedMetrics, tick: u64, width: u16, height: u16) -> Canvas {
+    let (width, height) = (width as usize, height as usize);
+    let mut field = vec![vec![0.0f32; width]; height];
+    let mut plot = |points: Vec<(isize, isize)>, value: f32| {
+        for (x, y) in points {
+            if let Some(cell) = usize::try_from(y).ok().and_then(|y| field.get_mut(y)).and_then(|row| row.get_mut(usize::try_from(x).ok()?)) {
+                *cell = cell.max(value);
+            }
+        }
+    };
+    let center = (width as isize / 2, height as isize / 2);
+    let reach = (width.min(height) / 2).saturating_sub(1).max(1) as f32;
+    plot(circle_points(center.0, center.1, (1.0 + inputs.cpu * (reach - 1.0)).round() as isize), 1.0);
+    plot(circle_points(center.0, center.1, (inputs.memory * reach).round() as isize), 0.6);
+    let length = reach * (0.3 + 0.7 * inputs.network);
+    for spoke in 0..SPOKES {
+        let angle = tick as f32 * 0.1 * (0.5 + inputs.cpu) + spoke as f32 * TAU / SPOKES as f32;
+        let end = (center.0 + (angle.cos() * length).round() as isize, center.1 + (angle.sin() * length).round() as isize);
+        plot(line_points(center, end), 0.4);
+    }
+    Canvas {
+        rows: field.iter().map(|row| row.iter().map(|&value| art_char(ramp, value)).collect()).collect(),
+        intensity: field,
+        overlay_row: usize::MAX,
+        alerts: Vec::new(),
+        inputs,
+        sanitized: 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn radius_two_circle_sets_its_boundary_cells() {
+        let mut expected = vec![(3, 5), (7, 5), (5, 3), (5, 7)];
+        for (dx, dy) in [(2, 1), (1, 2)] {
+            for (sx, sy) in [(1, 1), (1, -1), (-1, 1), (-1, -1)] {
+                expected.push((5 + sx * dx, 5 + sy * dy));
+            }
+        }
+        expected.sort_unstable();
+        assert_eq!(circle_points(5, 5, 2), expected);
+    }
+}
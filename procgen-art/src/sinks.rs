@@ -0,0 +1,129 @@
+use crate::cli::Args;
+use procgen_art::{
+    atomic::write_atomic,
+    emit::Emitter,
+    fifo::{open_fd, FifoSink},
+    html::render_html,
+    metrics::{Metrics, NormalizedMetrics},
+    phase::unix_millis,
+    record::{FrameLog, FrameLogEntry, RecordFormat, RecordedFrame, Recorder, TextRecorder},
+    render::Canvas,
+    snapshot::{frame_checksum, to_json, JsonCase, Snapshot},
+};
+use std::{
+    fs::File,
+    io::{self, Write},
+    path::PathBuf,
+    time::{Instant, SystemTime},
+};
+
+pub struct Sinks {
+    frame_log: Option<FrameLog>,
+    json_fd: Option<File>,
+    fifo: Option<FifoSink>,
+    emitter: Option<Emitter>,
+    recorder: Option<Recorder>,
+    text_recorder: Option<TextRecorder>,
+    metrics_out: Option<PathBuf>,
+    frame_out: Option<PathBuf>,
+    html: Option<PathBuf>,
+    json_case: JsonCase,
+    interval_ms: u64,
+}
+
+impl Sinks {
+    pub fn open(args: &Args, interval_ms: u64) -> io::Result<Self> {
+        Ok(Sinks {
+            frame_log: args.log.as_deref().map(FrameLog::open).transpose()?,
+            json_fd: args.json_fd.map(open_fd).transpose()?,
+            fifo: args.fifo.clone().map(FifoSink::new),
+            emitter: args.emit.clone().map(Emitter::new),
+            recorder: args.record.as_deref().filter(|_| args.record_format == RecordFormat::Ndjson).map(Recorder::create).transpose()?,
+            text_recorder: args
+                .record
+                .as_deref()
+                .filter(|_| args.record_format != RecordFormat::Ndjson)
+                .map(|path| TextRecorder::create(path, args.record_format == RecordFormat::Ansi))
+                .transpose()?,
+            metrics_out: args.metrics_out.clone(),
+            frame_out: args.frame_out.clone(),
+            html: args.html.clone(),
+            json_case: args.json_case,
+            interval_ms,
+        })
+    }
+
+    pub fn write(&mut self, metrics: &Metrics, canvas: &Canvas, style: &str, snapshot: impl Fn() -> Snapshot, tick: u64, render_us: u64) -> io::Result<()> {
+        if let Some(log) = self.frame_log.as_mut() {
+            log.write(&FrameLogEntry {
+                timestamp_ms: unix_millis(SystemTime::now()),
+                tick,
+                metrics: NormalizedMetrics::from_metrics(metrics),
+                load_avg: metrics.load_avg,
+                checksum: frame_checksum(&canvas.rows),
+                render_us,
+            })?;
+        }
+        if let Some(out) = self.json_fd.as_mut() {
+            writeln!(out, "{}", to_json(&snapshot(), true, self.json_case)?)?;
+        }
+        if let Some(path) = &self.metrics_out {
+            write_atomic(path, to_json(metrics, false, self.json_case)?.as_bytes())?;
+        }
+        if let Some(path) = &self.frame_out {
+            write_atomic(path, format!("{}\n", canvas.rows.join("\n")).as_bytes())?;
+        }
+        if let Some(path) = &self.html {
+            write_atomic(path, render_html(canvas, style).as_bytes())?;
+        }
+        if let Some(fifo) = self.fifo.as_mut() {
+            fifo.write_frame(&format!("{}\n", canvas.rows.join("\n")))?;
+        }
+        if let Some(emitter) = self.emitter.as_mut() {
+            emitter.send(metrics, Instant::now());
+        }
+        if let Some(recorder) = self.recorder.as_mut() {
+            recorder.write(RecordedFrame { snapshot: snapshot(), interval_ms: self.interval_ms, elapsed_us: None, delta_us: None })?;
+        }
+        if let Some(recorder) = self.text_recorder.as_mut() {
+            recorder.write(canvas, style)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::options::{capture, render_options, seeded_source};
+    use clap::Parser;
+    use procgen_art::render::render_frame;
+    use rand::{rngs::StdRng, SeedableRng};
+    use std::{env, fs, process};
+
+    #[cfg(unix)]
+    #[test]
+    fn json_fd_receives_each_snapshot_as_one_json_line() {
+        use std::os::fd::AsRawFd;
+        let path = env::temp_dir().join(format!("procgen-json-fd-{}.jsonl", process::id()));
+        let target = File::create(&path).unwrap();
+        let fd = target.as_raw_fd().to_string();
+        let args = Args::parse_from(["procgen-art", "--simulate", "--seed", "3", "--width", "8", "--height", "2", "--json-fd", &fd]);
+        let (_, metrics, _) = seeded_source(&args).unwrap();
+        let opts = render_options(&args).unwrap();
+        let canvas = render_frame(&metrics, &mut StdRng::seed_from_u64(3), &opts);
+        let mut sinks = Sinks::open(&args, 100).unwrap();
+        for tick in 0..2 {
+            sinks.write(&metrics, &canvas, &opts.style, || capture(&args, &metrics, &canvas, &opts), tick, 0).unwrap();
+        }
+        drop(sinks);
+        let written = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        let lines: Vec<&str> = written.lines().collect();
+        assert_eq!(lines.len(), 2);
+        for line in lines {
+            let value: serde_json::Value = serde_json::from_str(line).unwrap();
+            assert_eq!(value["width"], 8);
+        }
+    }
+}
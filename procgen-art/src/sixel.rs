@@ -0,0 +1,61 @@
+use std::{env, fmt::Write};
+
+pub const SCALE_X: usize = 4;
+pub const SCALE_Y: usize = 8;
+
+pub fn sixel_supported() -> bool {
+    let term = env::var("TERM").unwrap_or_default();
+    let program = env::var("TERM_PROGRAM").unwrap_or_default();
+    ["mlterm", "yaft", "foot", "contour", "wezterm"]
+        .iter()
+        .any(|name| term.contains(name) || program.to_lowercase().contains(name))
+}
+
+pub fn stop_index(intensity: f32, len: usize) -> usize {
+    (intensity.clamp(0.0, 1.0) * (len - 1) as f32).round() as usize
+}
+
+pub fn encode_sixel(field: &[Vec<f32>], colors: &[(u8, u8, u8)], scale_x: usize, scale_y: usize) -> String {
+    let rows = field.len() * scale_y;
+    let cols = field.first().map_or(0, Vec::len) * scale_x;
+    let mut out = format!("\x1bPq\"1;1;{cols};{rows}");
+    for (idx, (r, g, b)) in colors.iter().enumerate() {
+        let pct = |channel: u8| channel as u32 * 100 / 255;
+        let _ = write!(out, "#{idx};2;{};{};{}", pct(*r), pct(*g), pct(*b));
+    }
+    if colors.is_empty() {
+        out.push_str("\x1b\\");
+        return out;
+    }
+    let pixel = |x: usize, y: usize| {
+        let intensity = field[y / scale_y].get(x / scale_x).copied().unwrap_or(0.0);
+        stop_index(intensity, colors.len())
+    };
+    for band in (0..rows).step_by(6) {
+        for color in 0..colors.len() {
+            let _ = write!(out, "#{color}");
+            for x in 0..cols {
+                let bits = (0..6)
+                    .filter(|dy| band + dy < rows && pixel(x, band + dy) == color)
+                    .fold(0u8, |bits, dy| bits | (1 << dy));
+                out.push((63 + bits) as char);
+            }
+            out.push('$');
+        }
+        out.push('-');
+    }
+    out.push_str("\x1b\\");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn small_frame_encodes_header_palette_and_band() {
+        let sixel = encode_sixel(&[vec![0.0, 1.0]], &[(0, 0, 0), (255, 255, 255)], 1, 1);
+        assert!(sixel.starts_with("\x1bPq\"1;1;2;1#0;2;0;0;0#1;2;100;100;100"));
+        assert_eq!(sixel, "\x1bPq\"1;1;2;1#0;2;0;0;0#1;2;100;100;100#0@?$#1?@$-\x1b\\");
+    }
+}
@@ -0,0 +1,245 @@
+use crate::{
+    metrics::{Metrics, MetricsSummary},
+    phase::RngState,
+    render::{palette, Canvas},
+    stats::FrameStats,
+    termenv::TermEnv,
+};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::io;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum JsonCase {
+    #[default]
+    Snake,
+    Camel,
+}
+
+pub fn camel_case(key: &str) -> String {
+    let mut out = String::with_capacity(key.len());
+    let mut upper = false;
+    for ch in key.chars() {
+        if ch == '_' && !out.is_empty() {
+            upper = true;
+        } else if upper {
+            out.extend(ch.to_uppercase());
+            upper = false;
+        } else {
+            out.push(ch);
+        }
+    }
+    out
+}
+
+pub fn recase_keys(value: Value, case: JsonCase) -> Value {
+    match value {
+        _ if case == JsonCase::Snake => value,
+        Value::Object(map) => Value::Object(map.into_iter().map(|(key, value)| (camel_case(&key), recase_keys(value, case))).collect()),
+        Value::Array(items) => Value::Array(items.into_iter().map(|item| recase_keys(item, case)).collect()),
+        other => other,
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Cell {
+    pub char: char,
+    pub intensity: f32,
+    pub color: String,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum FrameData {
+    Text(Vec<String>),
+    Cells(Vec<Vec<Cell>>),
+    Codepoints(Vec<Vec<u32>>),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum FrameFormat {
+    Text,
+    Cells,
+    Codepoints,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Snapshot {
+    pub metrics: Metrics,
+    pub frame: FrameData,
+    pub width: u16,
+    pub height: u16,
+    pub style: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stats: Option<FrameStats>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tick: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rng: Option<RngState>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checksum: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub env: Option<TermEnv>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub palette: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub history: Option<Vec<MetricsSummary>>,
+}
+
+pub const BUILD_COMMIT: &str = env!("GIT_HASH");
+
+pub fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+pub fn frame_checksum(rows: &[String]) -> String {
+    format!("{:016x}", fnv1a(rows.join("\n").as_bytes()))
+}
+
+pub fn rich_cells(canvas: &Canvas, style: &str) -> Vec<Vec<Cell>> {
+    let colors = palette(style);
+    canvas
+        .rows
+        .iter()
+        .zip(&canvas.intensity)
+        .enumerate()
+        .map(|(idx, (line, values))| {
+            let color = format!("{:?}", colors[idx % colors.len()]).to_lowercase();
+            line.chars()
+                .zip(values)
+                .map(|(char, &intensity)| Cell {
+                    char,
+                    intensity,
+                    color: color.clone(),
+                })
+                .collect()
+        })
+        .collect()
+}
+
+pub fn codepoints(rows: &[String]) -> Vec<Vec<u32>> {
+    rows.iter().map(|row| row.chars().map(|ch| ch as u32).collect()).collect()
+}
+
+pub fn frame_data(canvas: &Canvas, style: &str, format: FrameFormat) -> FrameData {
+    match format {
+        FrameFormat::Text => FrameData::Text(canvas.rows.clone()),
+        FrameFormat::Cells => FrameData::Cells(rich_cells(canvas, style)),
+        FrameFormat::Codepoints => FrameData::Codepoints(codepoints(&canvas.rows)),
+    }
+}
+
+impl FrameData {
+    pub fn to_canvas(&self) -> Canvas {
+        let (rows, intensity) = match self {
+            FrameData::Text(rows) => (rows.clone(), Vec::new()),
+            FrameData::Codepoints(rows) => (
+                rows.iter()
+                    .map(|row| row.iter().filter_map(|&code| char::from_u32(code)).collect())
+                    .collect(),
+                Vec::new(),
+            ),
+            FrameData::Cells(cells) => (
+                cells.iter().map(|row| row.iter().map(|cell| cell.char).collect()).collect(),
+                cells.iter().map(|row| row.iter().map(|cell| cell.intensity).collect()).collect(),
+            ),
+        };
+        Canvas {
+            rows,
+            intensity,
+            overlay_row: usize::MAX,
+            alerts: Vec::new(),
+            inputs: Default::default(),
+            sanitized: 0,
+        }
+    }
+}
+
+fn encode<T: Serialize>(value: &T, compact: bool) -> serde_json::Result<String> {
+    if compact {
+        serde_json::to_string(value)
+    } else {
+        serde_json::to_string_pretty(value)
+    }
+}
+
+pub fn to_json<T: Serialize>(value: &T, compact: bool, case: JsonCase) -> io::Result<String> {
+    let json = match case {
+        JsonCase::Snake => encode(value, compact),
+        JsonCase::Camel => serde_json::to_value(value).and_then(|value| encode(&recase_keys(value, case), compact)),
+    };
+    json.map_err(io::Error::other)
+}
+
+pub fn print_snapshot(payload: &Snapshot, compact: bool, case: JsonCase) -> io::Result<()> {
+    println!("{}", to_json(payload, compact, case)?);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        metrics::{MetricsSource, MockSource},
+        render::{render_frame, RenderOptions},
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn camel_case_renames_keys_but_not_values() {
+        let json = to_json(&MockSource::new(4).sample(), true, JsonCase::Camel).unwrap();
+        assert!(json.contains("\"cpuUsage\":") && json.contains("\"totalMemory\":"), "{json}");
+        assert!(!json.contains("cpu_usage") && !json.contains("total_memory"), "{json}");
+        let nested = recase_keys(serde_json::json!({ "disk_usage": [{ "total_space": 1, "name": "scratch_disk\": 2" }] }), JsonCase::Camel);
+        assert_eq!(nested, serde_json::json!({ "diskUsage": [{ "totalSpace": 1, "name": "scratch_disk\": 2" }] }));
+    }
+
+    #[test]
+    fn rich_cells_cover_the_canvas_with_unit_intensities() {
+        let canvas = render_frame(&MockSource::new(9).sample(), &mut StdRng::seed_from_u64(9), &RenderOptions::new(14, 5, "plasma"));
+        let FrameData::Cells(cells) = frame_data(&canvas, "plasma", FrameFormat::Cells) else {
+            panic!("expected cell frame data");
+        };
+        assert_eq!(cells.len(), 5);
+        assert!(cells.iter().all(|row| row.len() == 14));
+        assert!(cells.iter().flatten().all(|cell| (0.0..=1.0).contains(&cell.intensity)));
+    }
+
+    #[test]
+    fn compact_json_is_one_line_and_pretty_json_is_not() {
+        let metrics = MockSource::new(4).sample();
+        for case in [JsonCase::Snake, JsonCase::Camel] {
+            let compact = to_json(&metrics, true, case).unwrap();
+            let pretty = to_json(&metrics, false, case).unwrap();
+            assert!(!compact.contains('\n'));
+            assert!(pretty.lines().count() > 1);
+            assert_eq!(serde_json::from_str::<Value>(&compact).unwrap(), serde_json::from_str::<Value>(&pretty).unwrap());
+        }
+    }
+
+    #[test]
+    fn codepoints_match_char_values() {
+        let rows = vec!["A #".to_string(), "█é".to_string()];
+        assert_eq!(codepoints(&rows), vec![vec![65, 32, 35], vec![0x2588, 0xe9]]);
+        let json = serde_json::to_string(&FrameData::Codepoints(codepoints(&rows))).unwrap();
+        assert!(json.contains("[[65,32,35],[9608,233]]"), "{json}");
+        assert_eq!(FrameData::Codepoints(codepoints(&rows)).to_canvas().rows, rows);
+    }
+
+    #[test]
+    fn checksums_track_the_seed() {
+        let metrics = MockSource::new(1).sample();
+        let checksum = |seed: u64| frame_checksum(&render_frame(&metrics, &mut StdRng::seed_from_u64(seed), &RenderOptions::new(16, 4, "plasma")).rows);
+        assert_eq!(checksum(5), checksum(5));
+        assert_ne!(checksum(5), checksum(6));
+        assert_eq!(checksum(5).len(), 16);
+        assert_eq!(fnv1a(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(fnv1a(b"a"), 0xaf63_dc4c_8601_ec8c);
+    }
+}
@@ -0,0 +1,46 @@
+use crate::{metrics::NormalizedMetrics, render::Canvas};
+
+pub const SOLID_STYLE: &str = "solid";
+
+pub fn solid_level(frame: u64, stops: usize) -> f32 {
+    if stops < 2 {
+        return 0.0;
+    }
+    (frame % stops as u64) as f32 / (stops - 1) as f32
+}
+
+pub fn solid_canvas(glyph: char, level: f32, inputs: NormalizedMetrics, width: u16, height: u16) -> Canvas {
+    let row: String = std::iter::repeat_n(glyph, width as usize).collect();
+    Canvas {
+        rows: vec![row; height as usize],
+        intensity: vec![vec![level; width as usize]; height as usize],
+        overlay_row: usize::MAX,
+        alerts: Vec::new(),
+        inputs,
+        sanitized: 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        metrics::{MetricsSource, MockSource},
+        render::RenderOptions,
+        scene::{draw, Scene},
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn every_cell_is_the_chosen_glyph() {
+        let opts = RenderOptions { ramp: Some(" @".to_string()), ..RenderOptions::new(12, 5, SOLID_STYLE) };
+        let mut scene = Scene::new(1, None);
+        let metrics = MockSource::new(1).sample();
+        for frame in 0..3 {
+            let canvas = draw(&metrics, &mut StdRng::seed_from_u64(1), &mut scene, &opts);
+            assert_eq!(canvas.rows, vec!["@".repeat(12); 5], "frame {frame}");
+            assert!(canvas.intensity.iter().flatten().all(|&level| level == canvas.intensity[0][0]));
+        }
+        assert_eq!(solid_level(5, 4), 1.0 / 3.0);
+    }
+}
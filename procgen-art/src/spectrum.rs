@@ -0,0 +1,100 @@
+use crate::{metrics::NormalizedMetrics, render::Canvas};
+
+pub const SPECTRUM_STYLE: &str = "spectrum";
+pub const SPECTRUM_SMOOTHING: f32 = 0.5;
+pub const PEAK_DECAY: f32 = 0.02;
+
+pub fn core_bins(cores: &[f32], bins: usize) -> Vec<f32> {
+    if cores.is_empty() {
+        return vec![0.0; bins];
+    }
+    (0..bins)
+        .map(|bin| {
+            let pos = if bins > 1 { bin as f32 / (bins - 1) as f32 } else { 0.0 };
+            let at = pos * (cores.len() - 1) as f32;
+            let (lo, frac) = (at.floor() as usize, at.fract());
+            let hi = (lo + 1).min(cores.len() - 1);
+            let usage = cores[lo] + (cores[hi] - cores[lo]) * frac;
+            (usage / 100.0 * (1.0 - 0.3 * pos)).clamp(0.0, 1.0)
+        })
+        .collect()
+}
+
+pub fn decay_peak(peak: f32, level: f32) -> f32 {
+    level.max(peak - PEAK_DECAY).clamp(0.0, 1.0)
+}
+
+#[derive(Default)]
+pub struct Spectrum {
+    levels: Vec<f32>,
+    peaks: Vec<f32>,
+}
+
+impl Spectrum {
+    pub fn update(&mut self, cores: &[f32], bins: usize) {
+        let targets = core_bins(cores, bins);
+        self.levels.resize(bins, 0.0);
+        self.peaks.resize(bins, 0.0);
+        for ((level, peak), target) in self.levels.iter_mut().zip(self.peaks.iter_mut()).zip(targets) {
+            *level += (target - *level) * SPECTRUM_SMOOTHING;
+            *peak = decay_peak(*peak, *level);
+        }
+    }
+
+    pub fn canvas(&self, ramp: &str, inputs: NormalizedMetrics, width: u16, height: u16) -> Canvas {
+        let (width, height) = (width as usize, height as usize);
+        let empty = ramp.chars().next().unwrap_or(' ');
+        let full = ramp.chars().last().unwrap_or('#');
+        let scale = |value: f32| (value * height as f32).round() as usize;
+        let mut rows = Vec::with_capacity(height);
+        let mut intensity = Vec::with_capacity(height);
+        for y in 0..height {
+            let depth = height - y;
+            let mut row = String::with_capacity(width);
+            let mut values = Vec::with_capacity(width);
+            for x in 0..width {
+                let bar = x / 2;
+                let (level, peak) = (self.levels.get(bar).copied(), self.peaks.get(bar).copied());
+                let lit = x % 2 == 0 && level.is_some_and(|level| scale(level) >= depth);
+                let cap = x % 2 == 0 && !lit && peak.is_some_and(|peak| scale(peak) == depth);
+                row.push(if lit { full } else if cap { '-' } else { empty });
+                values.push(if lit || cap { depth as f32 / height as f32 } else { 0.0 });
+            }
+            rows.push(row);
+            intensity.push(values);
+        }
+        Canvas {
+            rows,
+            intensity,
+            overlay_row: usize::MAX,
+            alerts: Vec::new(),
+            inputs,
+            sanitized: 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cores_spread_across_bins_with_a_treble_rolloff() {
+        let bins = core_bins(&[100.0, 0.0], 3);
+        assert!(bins.iter().zip([1.0, 0.425, 0.0]).all(|(bin, want)| (bin - want).abs() < 1e-6), "{bins:?}");
+        assert_eq!(core_bins(&[80.0], 1), vec![0.8]);
+        assert_eq!(core_bins(&[], 4), vec![0.0; 4]);
+    }
+
+    #[test]
+    fn peaks_hold_then_fall_slowly() {
+        let mut peak = decay_peak(0.0, 0.9);
+        let mut held = Vec::new();
+        for _ in 0..3 {
+            peak = decay_peak(peak, 0.1);
+            held.push(peak);
+        }
+        assert!(held.iter().zip([0.88, 0.86, 0.84]).all(|(peak, want)| (peak - want).abs() < 1e-6), "{held:?}");
+        assert_eq!(decay_peak(0.5, 0.7), 0.7);
+    }
+}
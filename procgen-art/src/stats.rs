@@ -0,0 +1,86 @@
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum SeedScore {
+    Coverage,
+    Entropy,
+    Contrast,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct FrameStats {
+    pub coverage: f32,
+    pub histogram: BTreeMap<char, usize>,
+}
+
+impl FrameStats {
+    pub fn from_rows(rows: &[String]) -> Self {
+        let mut histogram = BTreeMap::new();
+        let mut total = 0;
+        for ch in rows.iter().flat_map(|row| row.chars()) {
+            *histogram.entry(ch).or_insert(0) += 1;
+            total += 1;
+        }
+        let blank = histogram.get(&' ').copied().unwrap_or(0);
+        let coverage = if total == 0 { 0.0 } else { (total - blank) as f32 / total as f32 };
+        FrameStats { coverage, histogram }
+    }
+
+    pub fn entropy(&self) -> f32 {
+        let total: usize = self.histogram.values().sum();
+        self.histogram
+            .values()
+            .map(|&count| count as f32 / total as f32)
+            .map(|p| -p * p.log2())
+            .sum()
+    }
+}
+
+pub fn contrast(field: &[Vec<f32>]) -> f32 {
+    let values: Vec<f32> = field.iter().flatten().copied().filter(|value| value.is_finite()).collect();
+    if values.is_empty() {
+        return 0.0;
+    }
+    let mean = values.iter().sum::<f32>() / values.len() as f32;
+    (values.iter().map(|value| (value - mean).powi(2)).sum::<f32>() / values.len() as f32).sqrt()
+}
+
+pub fn score_frame(rows: &[String], field: &[Vec<f32>], by: SeedScore) -> f32 {
+    match by {
+        SeedScore::Coverage => FrameStats::from_rows(rows).coverage,
+        SeedScore::Entropy => FrameStats::from_rows(rows).entropy(),
+        SeedScore::Contrast => contrast(field),
+    }
+}
+
+pub fn rank_seeds(mut scored: Vec<(u64, f32)>) -> Vec<(u64, f32)> {
+    scored.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+    scored
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn coverage_spans_blank_to_dense_frames() {
+        let rows = |text: &str| vec![text.to_string(); 3];
+        assert_eq!(FrameStats::from_rows(&rows("        ")).coverage, 0.0);
+        assert_eq!(FrameStats::from_rows(&rows("########")).coverage, 1.0);
+        assert!(FrameStats::from_rows(&rows("####### ")).coverage > 0.85);
+        assert_eq!(FrameStats::from_rows(&[]).coverage, 0.0);
+    }
+
+    #[test]
+    fn ranking_orders_frames_by_the_chosen_score() {
+        let frames = [(7, "#  #"), (3, "    "), (5, "####"), (9, "#   ")];
+        let scored = |by: SeedScore| {
+            let scored = frames.iter().map(|&(seed, text)| (seed, score_frame(&[text.to_string()], &[], by))).collect();
+            rank_seeds(scored).into_iter().map(|(seed, _)| seed).collect::<Vec<u64>>()
+        };
+        assert_eq!(scored(SeedScore::Coverage), vec![5, 7, 9, 3]);
+        assert_eq!(scored(SeedScore::Entropy), vec![7, 9, 3, 5]);
+    }
+}
@@ -0,0 +1,190 @@
+use crossterm::style::{Attribute, Color};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+pub struct StyleSpec {
+    pub name: &'static str,
+    pub palette: &'static [Color],
+    pub ramp: &'static str,
+    pub load_ramps: &'static [&'static str],
+    pub attributes: &'static [Attribute],
+    pub interval_ms: u64,
+    pub width: u16,
+    pub height: u16,
+}
+
+pub const STYLES: &[StyleSpec] = &[
+    StyleSpec {
+        name: "plasma",
+        palette: &[Color::Magenta, Color::DarkMagenta, Color::Blue, Color::Black],
+        ramp: " .:+*#%@",
+        load_ramps: &[" .:+*#%@", " ,;x<>#%@", " /\\|<>X#@"],
+        attributes: &[],
+        interval_ms: 500,
+        width: 80,
+        height: 24,
+    },
+    StyleSpec {
+        name: "waves",
+        palette: &[Color::Blue, Color::Cyan, Color::Black],
+        ramp: " .-~*~=",
+        load_ramps: &[" .-~*~=", " _/^\\v=#"],
+        attributes: &[Attribute::Dim],
+        interval_ms: 300,
+        width: 100,
+        height: 20,
+    },
+    StyleSpec {
+        name: "ember",
+        palette: &[Color::DarkRed, Color::Red, Color::DarkYellow, Color::Yellow],
+        ramp: " `^\"*#",
+        load_ramps: &[" `^\"*#", " '^!%&#"],
+        attributes: &[Attribute::Bold],
+        interval_ms: 200,
+        width: 60,
+        height: 24,
+    },
+    StyleSpec {
+        name: "eq",
+        palette: &[Color::DarkGreen, Color::Green, Color::Yellow, Color::Red],
+        ramp: " █",
+        load_ramps: &[],
+        attributes: &[],
+        interval_ms: 100,
+        width: 64,
+        height: 16,
+    },
+    StyleSpec {
+        name: "ink",
+        palette: &[Color::Black, Color::DarkBlue, Color::Blue, Color::Cyan, Color::White],
+        ramp: " .:-=+*#%@",
+        load_ramps: &[],
+        attributes: &[],
+        interval_ms: 100,
+        width: 80,
+        height: 24,
+    },
+    StyleSpec {
+        name: "spectrum",
+        palette: &[Color::DarkGreen, Color::Green, Color::Yellow, Color::Red],
+        ramp: " ▮",
+        load_ramps: &[],
+        attributes: &[],
+        interval_ms: 80,
+        width: 64,
+        height: 16,
+    },
+    StyleSpec {
+        name: "mem-map",
+        palette: &[Color::DarkCyan, Color::Cyan, Color::DarkBlue],
+        ramp: "·▪",
+        load_ramps: &[],
+        attributes: &[],
+        interval_ms: 1000,
+        width: 64,
+        height: 16,
+    },
+    StyleSpec {
+        name: "solid",
+        palette: &[Color::Red, Color::Green, Color::Blue, Color::White, Color::Black],
+        ramp: "█",
+        load_ramps: &[],
+        attributes: &[],
+        interval_ms: 1000,
+        width: 80,
+        height: 24,
+    },
+    StyleSpec {
+        name: "disks",
+        palette: &[Color::Green, Color::Yellow, Color::Red],
+        ramp: " ▒",
+        load_ramps: &[],
+        attributes: &[],
+        interval_ms: 1000,
+        width: 80,
+        height: 20,
+    },
+    StyleSpec {
+        name: "shapes",
+        palette: &[Color::Black, Color::DarkMagenta, Color::Magenta, Color::Cyan, Color::White],
+        ramp: " .o@",
+        load_ramps: &[],
+        attributes: &[],
+        interval_ms: 100,
+        width: 60,
+        height: 30,
+    },
+    StyleSpec {
+        name: "rain",
+        palette: &[Color::Black, Color::DarkGreen, Color::Green, Color::White],
+        ramp: " .:|",
+        load_ramps: &[],
+        attributes: &[],
+        interval_ms: 80,
+        width: 80,
+        height: 24,
+    },
+];
+
+pub const COOL_PALETTE: &[Color] = &[Color::DarkBlue, Color::Blue, Color::Cyan, Color::White];
+pub const MILD_PALETTE: &[Color] = &[Color::DarkGreen, Color::Green, Color::DarkYellow, Color::Yellow];
+pub const WARM_PALETTE: &[Color] = &[Color::DarkYellow, Color::Yellow, Color::Red, Color::DarkRed];
+
+pub fn auto_palette(temperature: Option<f32>) -> Option<&'static [Color]> {
+    temperature.map(|celsius| match celsius {
+        c if c < 50.0 => COOL_PALETTE,
+        c if c < 70.0 => MILD_PALETTE,
+        _ => WARM_PALETTE,
+    })
+}
+
+pub fn load_ramp(spec: &StyleSpec, load: f32) -> &'static str {
+    if spec.load_ramps.is_empty() {
+        return spec.ramp;
+    }
+    let band = (load.clamp(0.0, 1.0) * spec.load_ramps.len() as f32) as usize;
+    spec.load_ramps[band.min(spec.load_ramps.len() - 1)]
+}
+
+pub fn style_names() -> Vec<&'static str> {
+    STYLES.iter().map(|spec| spec.name).collect()
+}
+
+pub fn random_style(seed: u64) -> &'static str {
+    STYLES[StdRng::seed_from_u64(seed).gen_range(0..STYLES.len())].name
+}
+
+pub fn next_style(current: &str) -> &'static str {
+    let idx = STYLES.iter().position(|spec| spec.name == current).map_or(0, |idx| idx + 1);
+    STYLES[idx % STYLES.len()].name
+}
+
+pub fn style_spec(name: &str) -> &'static StyleSpec {
+    STYLES.iter().find(|spec| spec.name == name).unwrap_or(&STYLES[0])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::ansi_rgb;
+
+    #[test]
+    fn hotter_sensors_pick_warmer_palettes() {
+        let warmth = |celsius: f32| {
+            let stops = auto_palette(Some(celsius)).unwrap();
+            stops.iter().map(|&color| ansi_rgb(color)).map(|(r, _, b)| r as i32 - b as i32).sum::<i32>()
+        };
+        assert!(warmth(35.0) < warmth(60.0));
+        assert!(warmth(60.0) < warmth(85.0));
+        assert_eq!(auto_palette(Some(49.9)), Some(COOL_PALETTE));
+        assert_eq!(auto_palette(Some(70.0)), Some(WARM_PALETTE));
+        assert_eq!(auto_palette(None), None);
+    }
+
+    #[test]
+    fn random_style_is_deterministic_per_seed() {
+        assert_eq!(random_style(17), random_style(17));
+        assert!(STYLES.iter().any(|spec| spec.name == random_style(17)));
+        let picks: Vec<&str> = (0..32).map(random_style).collect();
+        assert!(picks.iter().any(|&name| name != picks[0]));
+    }
+}
@@ -0,0 +1,60 @@
+use image::{imageops::FilterType, ImageError};
+use std::path::Path;
+
+pub struct Substrate {
+    pub width: u32,
+    pub height: u32,
+    pub luma: Vec<f32>,
+}
+
+impl Substrate {
+    pub fn load(path: &Path, width: u16, height: u16) -> Result<Self, ImageError> {
+        let (width, height) = (u32::from(width.max(1)), u32::from(height.max(1)));
+        let image = image::open(path)?.resize_exact(width, height, FilterType::Triangle).to_luma8();
+        let luma = image.pixels().map(|pixel| pixel.0[0] as f32 / 255.0).collect();
+        Ok(Substrate { width, height, luma })
+    }
+
+    pub fn luma_at(&self, x: u16, y: u16, width: u16, height: u16) -> f32 {
+        let sx = (x as u32 * self.width / u32::from(width.max(1))).min(self.width - 1);
+        let sy = (y as u32 * self.height / u32::from(height.max(1))).min(self.height - 1);
+        self.luma[(sy * self.width + sx) as usize]
+    }
+}
+
+pub fn modulate(intensity: f32, luma: f32) -> f32 {
+    intensity * luma
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        metrics::{MetricsSource, MockSource},
+        render::{render_frame, RenderOptions},
+    };
+    use image::{Rgb, RgbImage};
+    use rand::{rngs::StdRng, SeedableRng};
+    use std::{env, fs, process, sync::Arc};
+
+    #[test]
+    fn white_images_keep_the_field_and_black_ones_darken_it() {
+        let metrics = MockSource::new(10).sample();
+        let field = |substrate: Option<Substrate>| {
+            let opts = RenderOptions { substrate: substrate.map(Arc::new), ..RenderOptions::new(12, 6, "plasma") };
+            render_frame(&metrics, &mut StdRng::seed_from_u64(10), &opts).intensity
+        };
+        let solid = |level: u8| {
+            let path = env::temp_dir().join(format!("procgen-substrate-{level}-{}.png", process::id()));
+            RgbImage::from_pixel(8, 8, Rgb([level; 3])).save(&path).unwrap();
+            let substrate = Substrate::load(&path, 12, 6).unwrap();
+            fs::remove_file(&path).unwrap();
+            substrate
+        };
+        let plain = field(None);
+        assert_eq!(field(Some(solid(255))), plain);
+        let dark = field(Some(solid(0)));
+        assert!(dark.iter().flatten().all(|&value| value == 0.0));
+        assert!(plain.iter().flatten().any(|&value| value > 0.0));
+    }
+}
@@ -0,0 +1,59 @@
+use crate::metrics::{Metrics, NormalizedMetrics};
+
+pub struct Thresholds {
+    pub idle_cpu: f32,
+    pub busy_cpu: f32,
+    pub high_memory: f32,
+    pub heavy_network_kib: f32,
+}
+
+pub fn health_summary(metrics: &Metrics, limits: &Thresholds) -> String {
+    let memory_pct = NormalizedMetrics::from_metrics(metrics).memory * 100.0;
+    let network_kib = metrics.network_rx.saturating_add(metrics.network_tx) as f32 / 1024.0;
+    let mut findings = Vec::new();
+    if metrics.cpu_usage >= limits.busy_cpu {
+        findings.push("CPU busy");
+    }
+    if memory_pct >= limits.high_memory {
+        findings.push("Memory pressure high");
+    }
+    if network_kib >= limits.heavy_network_kib {
+        findings.push("Heavy network");
+    }
+    match findings.is_empty() {
+        true if metrics.cpu_usage < limits.idle_cpu => "System idle".to_string(),
+        true => "System normal".to_string(),
+        false => findings.join(", "),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metrics(cpu_usage: f32, used_memory: u64, network_rx: u64) -> Metrics {
+        Metrics {
+            cpu_usage,
+            cpu_cores: vec![cpu_usage],
+            load_avg: 0.0,
+            total_memory: 100,
+            used_memory,
+            disk_usage: Vec::new(),
+            network_rx,
+            network_tx: 0,
+            interfaces: Vec::new(),
+            entropy: 0,
+            temperature: None,
+        }
+    }
+
+    #[test]
+    fn representative_metrics_map_to_summaries() {
+        let limits = Thresholds { idle_cpu: 10.0, busy_cpu: 80.0, high_memory: 90.0, heavy_network_kib: 1024.0 };
+        assert_eq!(health_summary(&metrics(3.0, 40, 0), &limits), "System idle");
+        assert_eq!(health_summary(&metrics(45.0, 40, 0), &limits), "System normal");
+        assert_eq!(health_summary(&metrics(95.0, 40, 0), &limits), "CPU busy");
+        assert_eq!(health_summary(&metrics(95.0, 95, 2 << 20), &limits), "CPU busy, Memory pressure high, Heavy network");
+        assert_eq!(health_summary(&metrics(3.0, 95, 0), &limits), "Memory pressure high");
+    }
+}
@@ -0,0 +1,38 @@
+use crate::metrics::{Metrics, NormalizedMetrics};
+
+pub const DEFAULT_ONELINE: &str = "CPU {cpu}% MEM {mem}% NET {net}k/s";
+
+pub fn render_template(template: &str, metrics: &Metrics) -> String {
+    let inputs = NormalizedMetrics::from_metrics(metrics);
+    let net_kb = metrics.network_rx.saturating_add(metrics.network_tx) as f64 / 1024.0;
+    template
+        .replace("{cpu}", &format!("{:.0}", metrics.cpu_usage))
+        .replace("{mem}", &format!("{:.0}", inputs.memory * 100.0))
+        .replace("{net}", &format!("{net_kb:.0}"))
+        .replace("{disk}", &format!("{:.0}", inputs.disk * 100.0))
+        .replace("{load}", &format!("{:.2}", metrics.load_avg))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_oneline_formats_known_metrics() {
+        let metrics = Metrics {
+            cpu_usage: 42.4,
+            cpu_cores: vec![42.4],
+            load_avg: 1.5,
+            total_memory: 1000,
+            used_memory: 250,
+            disk_usage: Vec::new(),
+            network_rx: 2048,
+            network_tx: 1024,
+            interfaces: Vec::new(),
+            entropy: 0,
+            temperature: None,
+        };
+        assert_eq!(render_template(DEFAULT_ONELINE, &metrics), "CPU 42% MEM 25% NET 3k/s");
+        assert_eq!(render_template("{disk}|{load}", &metrics), "0|1.50");
+    }
+}
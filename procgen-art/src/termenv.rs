@@ -0,0 +1,40 @@
+use crate::color::ColorDepth;
+use crossterm::terminal;
+use serde::{Deserialize, Serialize};
+use std::env;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct TermEnv {
+    pub term: Option<String>,
+    pub colorterm: Option<String>,
+    pub term_program: Option<String>,
+    pub size: Option<(u16, u16)>,
+    pub color_depth: String,
+}
+
+pub fn detect_depth() -> ColorDepth {
+    ColorDepth::detect(&env::var("TERM").unwrap_or_default(), &env::var("COLORTERM").unwrap_or_default())
+}
+
+pub fn capture_env() -> TermEnv {
+    TermEnv {
+        term: env::var("TERM").ok(),
+        colorterm: env::var("COLORTERM").ok(),
+        term_program: env::var("TERM_PROGRAM").ok(),
+        size: terminal::size().ok(),
+        color_depth: detect_depth().label().to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn captured_env_includes_the_current_term() {
+        env::set_var("TERM", "xterm-256color");
+        let captured = capture_env();
+        assert_eq!(captured.term.as_deref(), Some("xterm-256color"));
+        assert!(!captured.color_depth.is_empty());
+    }
+}
@@ -0,0 +1,84 @@
+use crate::render::parse_ramp;
+use crossterm::style::Color;
+use serde::Deserialize;
+use std::{fs, io, path::Path, sync::OnceLock};
+
+static THEME_PALETTE: OnceLock<Vec<Color>> = OnceLock::new();
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ThemeFile {
+    colors: Vec<String>,
+    ramp: String,
+}
+
+pub struct Theme {
+    pub colors: Vec<Color>,
+    pub ramp: String,
+}
+
+pub fn parse_color(value: &str) -> Result<Color, String> {
+    if let Some(digits) = value.strip_prefix('#') {
+        let channel = |idx: usize| digits.get(idx..idx + 2).and_then(|pair| u8::from_str_radix(pair, 16).ok());
+        return match (digits.len(), channel(0), channel(2), channel(4)) {
+            (6, Some(r), Some(g), Some(b)) => Ok(Color::Rgb { r, g, b }),
+            _ => Err(format!("invalid colour '{value}' (expected #rrggbb)")),
+        };
+    }
+    match Color::try_from(value.to_lowercase().replace('-', "_").as_str()) {
+        Ok(Color::Reset) | Err(()) => Err(format!("unknown colour '{value}' (expected a name like dark_cyan or #rrggbb)")),
+        Ok(color) => Ok(color),
+    }
+}
+
+impl Theme {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let file: ThemeFile = serde_json::from_str(text).map_err(|err| err.to_string())?;
+        if file.colors.is_empty() {
+            return Err("theme needs at least one colour".to_string());
+        }
+        Ok(Theme {
+            colors: file.colors.iter().map(|name| parse_color(name)).collect::<Result<_, _>>()?,
+            ramp: parse_ramp(&file.ramp)?,
+        })
+    }
+
+    pub fn load(path: &Path) -> io::Result<Self> {
+        Theme::parse(&fs::read_to_string(path)?).map_err(io::Error::other)
+    }
+}
+
+pub fn set_theme_palette(colors: Vec<Color>) {
+    let _ = THEME_PALETTE.set(colors);
+}
+
+pub fn theme_palette() -> Option<&'static [Color]> {
+    THEME_PALETTE.get().map(Vec::as_slice)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        metrics::{MetricsSource, MockSource},
+        render::{render_frame, RenderOptions},
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn themes_parse_colours_and_a_one_glyph_ramp_fills_solid() {
+        let theme = Theme::parse(r##"{"colors": ["dark_red", "#ff8800"], "ramp": "#"}"##).unwrap();
+        assert_eq!(theme.colors, [Color::DarkRed, Color::Rgb { r: 255, g: 136, b: 0 }]);
+        let opts = RenderOptions { ramp: Some(theme.ramp), overlay: false, ..RenderOptions::new(16, 4, "plasma") };
+        let canvas = render_frame(&MockSource::new(3).sample(), &mut StdRng::seed_from_u64(3), &opts);
+        assert!(canvas.rows.iter().all(|row| row.chars().all(|ch| ch == '#')), "{:?}", canvas.rows);
+    }
+
+    #[test]
+    fn bad_theme_colours_are_errors_that_name_them() {
+        for (colors, needle) in [(r#"["ember"]"#, "'ember'"), (r##"["#ff88"]"##, "'#ff88'"), ("[]", "at least one")] {
+            let err = Theme::parse(&format!(r#"{{"colors": {colors}, "ramp": ".:"}}"#)).err().unwrap();
+            assert!(err.contains(needle), "{err}");
+        }
+    }
+}
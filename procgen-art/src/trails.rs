@@ -0,0 +1,62 @@
+use crate::render::{art_char, Canvas};
+
+pub fn parse_decay(value: &str) -> Result<f32, String> {
+    let decay: f32 = value.parse().map_err(|_| format!("invalid decay '{value}'"))?;
+    if !(0.0..1.0).contains(&decay) {
+        return Err("decay must be in [0, 1)".to_string());
+    }
+    Ok(decay)
+}
+
+pub fn trail_blend(current: f32, previous: f32, decay: f32) -> f32 {
+    current.max(previous * decay)
+}
+
+pub fn apply_trails(canvas: &mut Canvas, previous: &[Vec<f32>], decay: f32, ramp: &str) {
+    for (y, values) in canvas.intensity.iter_mut().enumerate() {
+        if y == canvas.overlay_row {
+            continue;
+        }
+        let Some(prior) = previous.get(y) else { continue };
+        let mut glyphs: Vec<char> = canvas.rows[y].chars().collect();
+        for (x, value) in values.iter_mut().enumerate() {
+            let blended = trail_blend(*value, prior.get(x).copied().unwrap_or(0.0), decay);
+            if blended > *value {
+                *value = blended;
+                if let Some(glyph) = glyphs.get_mut(x) {
+                    *glyph = art_char(ramp, blended);
+                }
+            }
+        }
+        canvas.rows[y] = glyphs.into_iter().collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::NormalizedMetrics;
+
+    #[test]
+    fn trails_keep_the_brighter_of_now_and_decayed_past() {
+        assert_eq!(trail_blend(0.2, 1.0, 0.5), 0.5);
+        assert_eq!(trail_blend(0.7, 1.0, 0.5), 0.7);
+        let previous = vec![vec![1.0, 0.0, 0.8]];
+        let mut canvas = Canvas {
+            rows: vec!["  #".to_string()],
+            intensity: vec![vec![0.0, 0.0, 1.0]],
+            overlay_row: usize::MAX,
+            alerts: Vec::new(),
+            inputs: NormalizedMetrics::default(),
+            sanitized: 0,
+        };
+        apply_trails(&mut canvas, &previous, 0.5, " .#");
+        assert_eq!(canvas.intensity, vec![vec![0.5, 0.0, 1.0]]);
+        assert_eq!(canvas.rows, vec![". #"]);
+        let faded = canvas.intensity.clone();
+        canvas.intensity = vec![vec![0.0; 3]];
+        canvas.rows = vec!["   ".to_string()];
+        apply_trails(&mut canvas, &faded, 0.5, " .#");
+        assert_eq!(canvas.intensity, vec![vec![0.25, 0.0, 0.5]]);
+    }
+}
@@ -0,0 +1,84 @@
+use crate::{
+    render::{art_char, Canvas},
+    styles::style_names,
+};
+use serde::Serialize;
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Transition {
+    pub from: String,
+    pub to: String,
+    pub frames: u64,
+}
+
+pub fn parse_transition(value: &str) -> Result<Transition, String> {
+    let parts: Vec<&str> = value.split(':').collect();
+    let [from, to, frames] = parts[..] else {
+        return Err(format!("expected FROM:TO:FRAMES, got '{value}'"));
+    };
+    for style in [from, to] {
+        if !style_names().contains(&style) {
+            return Err(format!("unknown style '{style}'"));
+        }
+    }
+    let frames: u64 = frames.parse().map_err(|_| format!("invalid frame count '{frames}'"))?;
+    if frames < 2 {
+        return Err("a transition needs at least 2 frames".to_string());
+    }
+    Ok(Transition { from: from.to_string(), to: to.to_string(), frames })
+}
+
+pub fn transition_t(tick: u64, frames: u64) -> f32 {
+    tick as f32 / frames.saturating_sub(1).max(1) as f32
+}
+
+pub fn blend_canvases(from: &Canvas, to: &Canvas, t: f32, ramp: &str) -> Canvas {
+    let dominant = if t < 0.5 { from } else { to };
+    let intensity: Vec<Vec<f32>> = from
+        .intensity
+        .iter()
+        .zip(&to.intensity)
+        .map(|(a, b)| a.iter().zip(b).map(|(a, b)| a + (b - a) * t).collect())
+        .collect();
+    let rows = intensity
+        .iter()
+        .enumerate()
+        .map(|(y, values)| match dominant.rows.get(y).filter(|_| y == dominant.overlay_row) {
+            Some(row) => row.clone(),
+            None => values.iter().map(|&value| art_char(ramp, value)).collect(),
+        })
+        .collect();
+    Canvas {
+        rows,
+        intensity,
+        overlay_row: dominant.overlay_row,
+        alerts: dominant.alerts.clone(),
+        inputs: dominant.inputs,
+        sanitized: from.sanitized + to.sanitized,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        metrics::{MetricsSource, MockSource},
+        render::{render_frame, RenderOptions},
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn the_midpoint_frame_is_an_even_blend() {
+        let metrics = MockSource::new(6).sample();
+        let from = render_frame(&metrics, &mut StdRng::seed_from_u64(6), &RenderOptions::new(16, 4, "plasma"));
+        let to = render_frame(&metrics, &mut StdRng::seed_from_u64(7), &RenderOptions::new(16, 4, "ember"));
+        assert_ne!(from.intensity, to.intensity);
+        assert_eq!(transition_t(2, 5), 0.5);
+        let mid = blend_canvases(&from, &to, transition_t(2, 5), " .:#");
+        for ((mid, a), b) in mid.intensity.iter().flatten().zip(from.intensity.iter().flatten()).zip(to.intensity.iter().flatten()) {
+            assert!((mid - (a + b) / 2.0).abs() < 1e-6);
+        }
+        assert_eq!(blend_canvases(&from, &to, transition_t(0, 5), " .:#").intensity, from.intensity);
+        assert_eq!(blend_canvases(&from, &to, transition_t(4, 5), " .:#").intensity, to.intensity);
+    }
+}
@@ -0,0 +1,88 @@
+use crate::{
+    snapshot::{FrameData, Snapshot},
+    styles::style_names,
+};
+
+fn row_lengths(frame: &FrameData) -> Vec<usize> {
+    match frame {
+        FrameData::Text(rows) => rows.iter().map(|row| row.chars().count()).collect(),
+        FrameData::Cells(rows) => rows.iter().map(Vec::len).collect(),
+        FrameData::Codepoints(rows) => rows.iter().map(Vec::len).collect(),
+    }
+}
+
+pub fn validate_snapshot(snapshot: &Snapshot) -> Result<(), String> {
+    let rows = row_lengths(&snapshot.frame);
+    if rows.len() != snapshot.height as usize {
+        return Err(format!("frame has {} rows but height is {}", rows.len(), snapshot.height));
+    }
+    if let Some((idx, len)) = rows.iter().enumerate().find(|(_, len)| **len != snapshot.width as usize) {
+        return Err(format!("row {idx} has {len} cells but width is {}", snapshot.width));
+    }
+    if !style_names().contains(&snapshot.style.as_str()) {
+        return Err(format!("unknown style '{}'", snapshot.style));
+    }
+    let metrics = &snapshot.metrics;
+    if !metrics.cpu_usage.is_finite() {
+        return Err("metrics.cpu_usage is not finite".to_string());
+    }
+    if let Some(idx) = metrics.cpu_cores.iter().position(|core| !core.is_finite()) {
+        return Err(format!("metrics.cpu_cores[{idx}] is not finite"));
+    }
+    if !metrics.load_avg.is_finite() {
+        return Err("metrics.load_avg is not finite".to_string());
+    }
+    if metrics.temperature.is_some_and(|temp| !temp.is_finite()) {
+        return Err("metrics.temperature is not finite".to_string());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::{MetricsSource, MockSource};
+
+    fn snapshot() -> Snapshot {
+        Snapshot {
+            metrics: MockSource::new(1).sample(),
+            frame: FrameData::Text(vec!["abcd".to_string(), "efgh".to_string()]),
+            width: 4,
+            height: 2,
+            style: "plasma".to_string(),
+            stats: None,
+            tick: None,
+            rng: None,
+            checksum: None,
+            commit: None,
+            env: None,
+            palette: None,
+            history: None,
+        }
+    }
+
+    fn rejects(edit: impl FnOnce(&mut Snapshot)) -> String {
+        let mut snapshot = snapshot();
+        edit(&mut snapshot);
+        validate_snapshot(&snapshot).err().unwrap_or_default()
+    }
+
+    #[test]
+    fn accepts_a_consistent_snapshot() {
+        assert_eq!(validate_snapshot(&snapshot()), Ok(()));
+        let json = serde_json::to_string(&snapshot()).unwrap();
+        assert_eq!(validate_snapshot(&serde_json::from_str(&json).unwrap()), Ok(()));
+        assert!(serde_json::from_str::<Snapshot>("{\"width\": 4}").is_err());
+    }
+
+    #[test]
+    fn reports_each_failure_mode() {
+        assert_eq!(rejects(|s| s.height = 3), "frame has 2 rows but height is 3");
+        assert_eq!(rejects(|s| s.frame = FrameData::Codepoints(vec![vec![65; 4], vec![65; 3]])), "row 1 has 3 cells but width is 4");
+        assert_eq!(rejects(|s| s.style = "sparkles".to_string()), "unknown style 'sparkles'");
+        assert_eq!(rejects(|s| s.metrics.cpu_usage = f32::NAN), "metrics.cpu_usage is not finite");
+        assert_eq!(rejects(|s| s.metrics.cpu_cores[2] = f32::INFINITY), "metrics.cpu_cores[2] is not finite");
+        assert_eq!(rejects(|s| s.metrics.load_avg = f64::NAN), "metrics.load_avg is not finite");
+        assert_eq!(rejects(|s| s.metrics.temperature = Some(f32::NAN)), "metrics.temperature is not finite");
+    }
+}